@@ -23,6 +23,7 @@ fn create_mock_preview(url: &str) -> Preview {
         image_url: Some("https://example.com/image.jpg".to_string()),
         favicon: Some("https://example.com/favicon.ico".to_string()),
         site_name: Some("Example Site".to_string()),
+        ..Default::default()
     }
 }
 
@@ -109,6 +109,7 @@ fn generate_test_preview() -> Preview {
         image_url: Some("https://example.com/image.jpg".to_string()),
         favicon: Some("https://example.com/favicon.ico".to_string()),
         site_name: Some("Example Site".to_string()),
+        ..Default::default()
     }
 }
 
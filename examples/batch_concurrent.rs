@@ -98,64 +98,72 @@ async fn test_improved_batch_processing(
 
     let url_strings: Vec<&str> = urls.iter().map(|u| u.url.as_str()).collect();
 
-    match service
+    // Unlike `fetch_batch`, a failing URL does not discard the other results
+    let results = service
         .default_generator
         .fetcher
-        .fetch_batch(url_strings)
-        .await
-    {
-        Ok(results) => {
-            let duration = start.elapsed();
-            log_info!("Batch fetch completed in {:?}", duration);
-
-            for (idx, result) in results.iter().enumerate() {
-                match result {
-                    FetchResult::Html(content) => {
-                        log_info!(
-                            "URL: {} - Successfully fetched HTML content ({} bytes)",
-                            urls[idx].url,
-                            content.len()
-                        );
-                    }
-                    FetchResult::OEmbed(oembed) => {
-                        log_info!(
-                            "URL: {} - Successfully fetched oEmbed content from {}",
-                            urls[idx].url,
-                            oembed.provider_name
-                        );
-                    }
-                }
+        .fetch_batch_all(url_strings)
+        .await;
+    let duration = start.elapsed();
+    log_info!("Batch fetch completed in {:?}", duration);
+
+    let (mut html_count, mut oembed_count, mut error_count) = (0, 0, 0);
+    for (url_data, result) in urls.iter().zip(&results) {
+        match result {
+            Ok(FetchResult::Html(content)) => {
+                html_count += 1;
+                log_info!(
+                    "URL: {} - Successfully fetched HTML content ({} bytes)",
+                    url_data.url,
+                    content.len()
+                );
             }
-
-            let html_count = results
-                .iter()
-                .filter(|r| matches!(r, FetchResult::Html(_)))
-                .count();
-            let oembed_count = results
-                .iter()
-                .filter(|r| matches!(r, FetchResult::OEmbed(_)))
-                .count();
-
-            log_info!("\nBatch Processing Summary:");
-            log_info!("Total URLs processed: {}", results.len());
-            log_info!("HTML responses: {}", html_count);
-            log_info!("oEmbed responses: {}", oembed_count);
-            log_info!(
-                "Average time per URL: {:?}",
-                duration / results.len() as u32
-            );
-        }
-        Err(e) => {
-            log_warn!("Batch processing failed: {}", e);
-            for url_data in urls {
-                match service.default_generator.fetcher.fetch(&url_data.url).await {
-                    Ok(_) => log_info!("Individual fetch succeeded for {}", url_data.url),
-                    Err(e) => log_warn!("Individual fetch failed for {}: {}", url_data.url, e),
-                }
+            Ok(FetchResult::OEmbed(oembed)) => {
+                oembed_count += 1;
+                log_info!(
+                    "URL: {} - Successfully fetched oEmbed content from {}",
+                    url_data.url,
+                    oembed.provider_name
+                );
+            }
+            Ok(FetchResult::Image(image)) => {
+                html_count += 1;
+                log_info!(
+                    "URL: {} - Successfully fetched {} image",
+                    url_data.url,
+                    image.content_type
+                );
+            }
+            Ok(FetchResult::Pdf(pdf)) => {
+                html_count += 1;
+                log_info!(
+                    "URL: {} - Successfully fetched PDF {:?}",
+                    url_data.url,
+                    pdf.title
+                );
+            }
+            Ok(FetchResult::NotModified) => {
+                log_info!("URL: {} - Not modified", url_data.url);
+            }
+            Err(e) => {
+                error_count += 1;
+                log_warn!("URL: {} - Fetch failed: {}", url_data.url, e);
             }
         }
     }
 
+    log_info!("\nBatch Processing Summary:");
+    log_info!("Total URLs processed: {}", results.len());
+    log_info!("HTML responses: {}", html_count);
+    log_info!("oEmbed responses: {}", oembed_count);
+    log_info!("Failed: {}", error_count);
+    if !results.is_empty() {
+        log_info!(
+            "Average time per URL: {:?}",
+            duration / results.len() as u32
+        );
+    }
+
     Ok(())
 }
 
@@ -40,6 +40,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         transport: McpTransport::Stdio,
         browser_timeout: 30,
         max_sessions: 3,
+        ..Default::default()
     };
     
     // Create service with browser support
@@ -54,6 +54,7 @@ async fn main() -> Result<(), PreviewError> {
         clean_html: true,
         max_content_length: 10_000, // Smaller limit for faster processing
         model_params: Default::default(),
+        ..Default::default()
     };
     
     let extractor = LLMExtractor::with_config(provider, extractor_config);
@@ -39,6 +39,7 @@ async fn main() -> Result<(), PreviewError> {
         clean_html: true,
         max_content_length: 5_000,
         model_params: Default::default(),
+        ..Default::default()
     };
     
     let extractor = LLMExtractor::with_config(provider, extractor_config);
@@ -61,6 +61,7 @@ fn create_optimized_extractor(model: &str) -> LLMExtractor {
         clean_html: true,
         max_content_length: 5_000, // Small limit for speed
         model_params: Default::default(),
+        ..Default::default()
     };
     
     LLMExtractor::with_config(provider, extractor_config)
@@ -95,6 +95,7 @@ async fn test_standard_provider() -> Result<(), PreviewError> {
         clean_html: true,
         max_content_length: 3_000,
         model_params: Default::default(),
+        ..Default::default()
     };
     
     let extractor = LLMExtractor::with_config(provider, extractor_config);
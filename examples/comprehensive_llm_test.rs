@@ -187,6 +187,7 @@ async fn test_provider_extraction(
         clean_html: true,
         max_content_length: 3_000,
         model_params: Default::default(),
+        ..Default::default()
     };
     
     let extractor = LLMExtractor::with_config(provider, config);
@@ -229,6 +230,7 @@ async fn test_content_formats() -> Result<(), Box<dyn std::error::Error>> {
             clean_html: true,
             max_content_length: 5_000,
             model_params: Default::default(),
+            ..Default::default()
         };
         
         let extractor = LLMExtractor::with_config(provider.clone(), config);
@@ -142,6 +142,7 @@ async fn main() -> Result<(), PreviewError> {
         clean_html: true,
         max_content_length: 5_000,
         model_params: Default::default(),
+        ..Default::default()
     };
     
     let extractor = LLMExtractor::with_config(provider, config);
@@ -86,11 +86,7 @@ async fn test_browser_preview(force: bool) -> Result<(), Box<dyn std::error::Err
         let url = get_url_from_user("请输入 URL (默认: https://twitter.com/rustlang): ", 
                                      "https://twitter.com/rustlang");
         
-        println!("正在初始化浏览器...");
-        if let Some(browser_service) = &service.browser_service {
-            browser_service.initialize().await?;
-        }
-        
+        // 浏览器会在第一次需要时自动启动
         match service.generate_preview(&url).await {
             Ok(preview) => {
                 println!("\n✅ 预览成功:");
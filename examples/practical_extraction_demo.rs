@@ -200,6 +200,7 @@ fn create_extractor() -> LLMExtractor {
         clean_html: true,
         max_content_length: 100_000, // Larger limit for detailed extraction
         model_params: Default::default(),
+        ..Default::default()
     };
     
     LLMExtractor::with_config(provider, extractor_config)
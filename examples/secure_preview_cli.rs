@@ -208,11 +208,7 @@ async fn main() -> Result<(), Box<dyn Error>> {
                 pb.inc(1);
 
                 match &e {
-                    PreviewError::LocalhostBlocked
-                    | PreviewError::PrivateIpBlocked(_)
-                    | PreviewError::InvalidUrlScheme(_)
-                    | PreviewError::DomainBlocked(_)
-                    | PreviewError::DomainNotAllowed(_) => {
+                    e if e.is_security_block() => {
                         blocked_count += 1;
                         println!("\n{}: {} - {}", "⚠ Blocked".bold().yellow(), url, e);
                     }
@@ -45,6 +45,7 @@ async fn main() -> Result<(), PreviewError> {
         clean_html: true,
         max_content_length: 10_000,
         model_params: Default::default(),
+        ..Default::default()
     };
     
     let extractor = LLMExtractor::with_config(provider, config);
@@ -33,6 +33,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         transport: url_preview::McpTransport::Stdio,
         browser_timeout: 30,
         max_sessions: 5,
+        ..Default::default()
     };
     
     let browser_service = Arc::new(BrowserPreviewService::new(
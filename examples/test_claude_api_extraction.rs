@@ -356,6 +356,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         clean_html: true,
         max_content_length: 50_000,
         model_params: Default::default(),
+        ..Default::default()
     };
     
     let extractor = LLMExtractor::with_config(provider, config);
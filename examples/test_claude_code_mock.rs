@@ -142,6 +142,7 @@ async fn demonstrate_custom_config() -> Result<(), PreviewError> {
         clean_html: true,
         max_content_length: 5000,
         model_params: Default::default(),
+        ..Default::default()
     };
     
     // Create fetcher
@@ -137,6 +137,7 @@ async fn test_with_claude_api() -> Result<(), PreviewError> {
         clean_html: true,
         max_content_length: 1000,
         model_params: Default::default(),
+        ..Default::default()
     };
     
     let extractor = LLMExtractor::with_config(provider, config);
@@ -83,6 +83,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         transport: McpTransport::Stdio,
         browser_timeout: 30,
         max_sessions: 3,
+        ..Default::default()
     };
     
     let browser_service = Arc::new(BrowserPreviewService::new(mcp_config, BrowserUsagePolicy::Always));
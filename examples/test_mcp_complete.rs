@@ -32,6 +32,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         transport: McpTransport::Stdio,
         browser_timeout: 30,
         max_sessions: 5,
+        ..Default::default()
     };
     
     let browser_service = Arc::new(BrowserPreviewService::new(
@@ -44,6 +44,7 @@ async fn main() -> Result<(), PreviewError> {
         clean_html: true,
         max_content_length: 15_000,
         model_params: Default::default(),
+        ..Default::default()
     };
     
     let extractor = LLMExtractor::with_config(provider, config);
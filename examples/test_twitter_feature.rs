@@ -138,6 +138,15 @@ async fn main() -> Result<(), Box<dyn Error>> {
                 println!("  ✓ Received HTML response");
                 println!("    Content length: {} bytes", html.len());
             }
+            url_preview::FetchResult::Image(image) => {
+                println!("  ✓ Received {} image", image.content_type);
+            }
+            url_preview::FetchResult::Pdf(pdf) => {
+                println!("  ✓ Received PDF: {:?}", pdf.title);
+            }
+            url_preview::FetchResult::NotModified => {
+                println!("  ✓ Not modified");
+            }
         },
         Err(e) => {
             println!("  ✗ Fetch error: {}", e);
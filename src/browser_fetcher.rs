@@ -7,11 +7,19 @@ use crate::{PreviewError, Preview};
 use crate::MetadataExtractor;
 use crate::mcp_client::{McpClient, McpConfig, BrowserUsagePolicy};
 use std::sync::Arc;
+use tokio::sync::OnceCell;
 use url::Url;
 
 #[cfg(feature = "logging")]
 use tracing::{debug, warn, instrument};
 
+/// Reads the `og:` title, description and image from the live DOM
+const LIVE_OG_TAGS_SCRIPT: &str = r#"() => {
+    const og = (property) =>
+        document.querySelector(`meta[property="og:${property}"]`)?.getAttribute('content') ?? null;
+    return { title: og('title'), description: og('description'), image: og('image') };
+}"#;
+
 /// Browser-based content fetcher
 pub struct BrowserFetcher {
     /// MCP client for browser automation
@@ -20,6 +28,8 @@ pub struct BrowserFetcher {
     usage_policy: BrowserUsagePolicy,
     /// Metadata extractor for preview generation
     metadata_extractor: Arc<MetadataExtractor>,
+    /// Set once the MCP server has been started successfully
+    started: OnceCell<()>,
 }
 
 impl BrowserFetcher {
@@ -29,19 +39,44 @@ impl BrowserFetcher {
             mcp_client: Arc::new(McpClient::new(config)),
             usage_policy,
             metadata_extractor: Arc::new(MetadataExtractor::new()),
+            started: OnceCell::new(),
         }
     }
     
     /// Initialize the browser fetcher
+    ///
+    /// Calling this is optional: the MCP server is started lazily on first use.
+    /// Concurrent callers share a single startup, and a failed startup is retried
+    /// on the next call.
     pub async fn initialize(&self) -> Result<(), PreviewError> {
-        self.mcp_client.start().await
+        self.started
+            .get_or_try_init(|| self.mcp_client.start())
+            .await
+            .map(|_| ())
+    }
+    
+    /// Whether the MCP server has been started
+    pub fn is_initialized(&self) -> bool {
+        self.started.initialized()
     }
     
-    /// Shutdown the browser fetcher
+    /// Shutdown the browser fetcher, waiting for the MCP server to exit
+    ///
+    /// The fetcher is not restarted afterwards.
     pub async fn shutdown(&self) -> Result<(), PreviewError> {
         self.mcp_client.stop().await
     }
     
+    /// Whether the MCP server process is running, or the remote server connected
+    pub async fn is_running(&self) -> bool {
+        self.mcp_client.is_running().await
+    }
+    
+    /// The policy deciding when pages are rendered in the browser
+    pub fn usage_policy(&self) -> &BrowserUsagePolicy {
+        &self.usage_policy
+    }
+    
     /// Check if browser should be used for this URL
     pub fn should_use_browser(&self, url: &str) -> bool {
         match self.usage_policy {
@@ -102,12 +137,13 @@ impl BrowserFetcher {
         #[cfg(feature = "logging")]
         debug!("Fetching content with browser for URL: {}", url);
         
+        self.initialize().await?;
+        
         // Navigate to the URL
         self.mcp_client.navigate(url).await?;
         
-        // Wait for page to load (simple implementation)
-        // In production, we'd use better wait strategies
-        tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
+        // Wait for page to load according to the configured strategy
+        self.mcp_client.wait_for_load().await?;
         
         // Get the page HTML
         let html = self.mcp_client.get_page_html().await?;
@@ -119,13 +155,86 @@ impl BrowserFetcher {
     }
     
     /// Generate preview using browser
+    ///
+    /// The DOM is captured once the wait strategy completes. Open Graph tags that
+    /// are still missing from it are then read from the live page, which catches
+    /// tags injected late by scripts.
     #[cfg_attr(feature = "logging", instrument(skip(self)))]
     pub async fn generate_preview(&self, url: &str) -> Result<Preview, PreviewError> {
         // Fetch content with browser
         let html = self.fetch_with_browser(url).await?;
         
         // Extract metadata using metadata extractor
-        self.metadata_extractor.extract(&html, url)
+        let mut preview = self.metadata_extractor.extract(&html, url)?;
+        self.fill_from_live_dom(&mut preview, url).await;
+        Ok(preview)
+    }
+    
+    /// Fill a missing title, description or image from the `og:` meta tags of the
+    /// page currently loaded in the browser
+    async fn fill_from_live_dom(&self, preview: &mut Preview, url: &str) {
+        if preview.title.is_some() && preview.description.is_some() && preview.image_url.is_some() {
+            return;
+        }
+        
+        let found = match self.mcp_client.evaluate(LIVE_OG_TAGS_SCRIPT).await {
+            // Some servers hand back the serialized object as a string
+            Ok(serde_json::Value::String(text)) => serde_json::from_str(&text).unwrap_or_default(),
+            Ok(found) => found,
+            Err(_) => return,
+        };
+        let tag = |name: &str| {
+            found[name]
+                .as_str()
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(String::from)
+        };
+        
+        #[cfg(feature = "logging")]
+        debug!("Filling missing metadata from the live DOM: {}", found);
+        
+        if preview.title.is_none() {
+            preview.title = tag("title");
+        }
+        if preview.description.is_none() {
+            preview.description = tag("description");
+        }
+        if preview.image_url.is_none() {
+            preview.image_url = tag("image").and_then(|image| {
+                let base = Url::parse(url).ok()?;
+                crate::utils::resolve_url(&base, &image)
+            });
+        }
+    }
+    
+    /// Generate a preview and a PNG screenshot from a single page load.
+    ///
+    /// When the page declares no preview image, the screenshot is embedded in
+    /// `image_url` as a `data:image/png;base64,...` URL.
+    #[cfg_attr(feature = "logging", instrument(skip(self)))]
+    pub async fn generate_preview_with_screenshot(
+        &self,
+        url: &str,
+    ) -> Result<(Preview, Vec<u8>), PreviewError> {
+        self.initialize().await?;
+        
+        self.mcp_client.navigate(url).await?;
+        self.mcp_client.wait_for_load().await?;
+        
+        let html = self.mcp_client.get_page_html().await?;
+        let mut preview = self.metadata_extractor.extract(&html, url)?;
+        self.fill_from_live_dom(&mut preview, url).await;
+        let screenshot = self.mcp_client.take_screenshot().await?;
+        
+        if preview.image_url.is_none() {
+            preview.image_url = Some(format!(
+                "data:image/png;base64,{}",
+                base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &screenshot)
+            ));
+        }
+        
+        Ok((preview, screenshot))
     }
     
     /// Take a screenshot of the page
@@ -134,6 +243,8 @@ impl BrowserFetcher {
         #[cfg(feature = "logging")]
         debug!("Taking screenshot of URL: {}", url);
         
+        self.initialize().await?;
+        
         // Navigate to the URL if not already there
         self.mcp_client.navigate(url).await?;
         
@@ -150,6 +261,8 @@ impl BrowserFetcher {
     where
         T: serde::de::DeserializeOwned,
     {
+        self.initialize().await?;
+        
         // Navigate to the URL
         self.mcp_client.navigate(url).await?;
         
@@ -173,8 +286,14 @@ pub struct BrowserPreviewService {
     fallback_fetcher: Arc<crate::Fetcher>,
     /// Metadata extractor
     metadata_extractor: Arc<MetadataExtractor>,
+    /// Static previews less complete than this are retried in the browser
+    /// under [`BrowserUsagePolicy::Auto`]
+    fallback_threshold: f32,
 }
 
+/// Default fallback threshold: at least three of the five key preview fields
+pub(crate) const DEFAULT_FALLBACK_THRESHOLD: f32 = 0.6;
+
 impl BrowserPreviewService {
     /// Create a new browser preview service
     pub fn new(mcp_config: McpConfig, usage_policy: BrowserUsagePolicy) -> Self {
@@ -182,19 +301,95 @@ impl BrowserPreviewService {
             browser_fetcher: Arc::new(BrowserFetcher::new(mcp_config, usage_policy)),
             fallback_fetcher: Arc::new(crate::Fetcher::new()),
             metadata_extractor: Arc::new(MetadataExtractor::new()),
+            fallback_threshold: DEFAULT_FALLBACK_THRESHOLD,
+        }
+    }
+    
+    /// Use `fetcher` for pages that are not rendered in the browser
+    pub fn with_fetcher(mut self, fetcher: crate::Fetcher) -> Self {
+        self.fallback_fetcher = Arc::new(fetcher);
+        self
+    }
+    
+    /// Under [`BrowserUsagePolicy::Auto`], retry a page in the browser when its
+    /// static preview has a [`Preview::completeness`] below `threshold`. A
+    /// threshold of `0.0` disables the retry.
+    pub fn with_fallback_threshold(mut self, threshold: f32) -> Self {
+        self.fallback_threshold = threshold;
+        self
+    }
+    
+    /// Whether a preview fetched without the browser is too sparse to keep
+    /// without first trying the browser
+    pub fn needs_browser_fallback(&self, preview: &Preview) -> bool {
+        *self.browser_fetcher.usage_policy() == BrowserUsagePolicy::Auto
+            && preview.completeness() < self.fallback_threshold
+    }
+    
+    /// Retry a sparse static preview in the browser, keeping whichever of the
+    /// two previews is more complete. Browser failures keep the static preview.
+    pub async fn complete_with_browser(&self, url: &str, preview: Preview) -> Preview {
+        if !self.needs_browser_fallback(&preview) {
+            return preview;
+        }
+        
+        #[cfg(feature = "logging")]
+        debug!(
+            "Static preview is {:.0}% complete, retrying in the browser: {}",
+            preview.completeness() * 100.0,
+            url
+        );
+        
+        match self.browser_fetcher.generate_preview(url).await {
+            Ok(rendered) if rendered.completeness() > preview.completeness() => rendered,
+            Ok(_) => preview,
+            Err(_e) => {
+                #[cfg(feature = "logging")]
+                warn!("Browser fallback failed, keeping the static preview: {}", _e);
+                preview
+            }
         }
     }
     
     /// Initialize the service
+    ///
+    /// Optional: the browser is started lazily the first time it is needed.
     pub async fn initialize(&self) -> Result<(), PreviewError> {
         self.browser_fetcher.initialize().await
     }
     
+    /// Whether the browser has been started
+    pub fn is_initialized(&self) -> bool {
+        self.browser_fetcher.is_initialized()
+    }
+    
+    /// Whether the browser's MCP server is currently running
+    pub async fn is_running(&self) -> bool {
+        self.browser_fetcher.is_running().await
+    }
+    
+    /// Stop the browser and wait for the MCP server to exit
+    ///
+    /// Prefer this over dropping the service: `Drop` can only schedule the
+    /// shutdown, which never runs if the Tokio runtime is shutting down.
+    pub async fn shutdown(&self) -> Result<(), PreviewError> {
+        self.browser_fetcher.shutdown().await
+    }
+    
     /// Check if browser should be used for this URL
     pub fn should_use_browser(&self, url: &str) -> bool {
         self.browser_fetcher.should_use_browser(url)
     }
     
+    /// Render the page in the browser and return its preview together with a PNG
+    /// screenshot, regardless of the usage policy
+    pub async fn generate_preview_with_screenshot(
+        &self,
+        url: &str,
+    ) -> Result<(Preview, Vec<u8>), PreviewError> {
+        self.browser_fetcher.generate_preview_with_screenshot(url).await
+    }
+    
     /// Generate preview with automatic browser detection
     #[cfg_attr(feature = "logging", instrument(skip(self)))]
     pub async fn generate_preview(&self, url: &str) -> Result<Preview, PreviewError> {
@@ -226,18 +421,22 @@ impl BrowserPreviewService {
                 crate::FetchResult::Html(h) => h,
                 _ => return Err(PreviewError::InvalidContentType("Expected HTML".to_string())),
             };
-            self.metadata_extractor.extract(&html, url)
+            let preview = self.metadata_extractor.extract(&html, url)?;
+            Ok(self.complete_with_browser(url, preview).await)
         }
     }
 }
 
 impl Drop for BrowserPreviewService {
     fn drop(&mut self) {
-        // Ensure browser is shut down when service is dropped
+        // Best effort: schedule a shutdown if a runtime is still available. A
+        // spawned server is also killed when its process handle is dropped.
         let fetcher = self.browser_fetcher.clone();
-        tokio::spawn(async move {
-            let _ = fetcher.shutdown().await;
-        });
+        if let Ok(handle) = tokio::runtime::Handle::try_current() {
+            handle.spawn(async move {
+                let _ = fetcher.shutdown().await;
+            });
+        }
     }
 }
 
@@ -278,4 +477,44 @@ mod tests {
         assert!(fetcher.should_use_browser("https://twitter.com"));
         assert!(!fetcher.should_use_browser("https://example.com"));
     }
-}
\ No newline at end of file
+    
+    #[tokio::test]
+    async fn test_lazy_initialization_is_idempotent() {
+        let config = McpConfig {
+            enabled: false,
+            ..Default::default()
+        };
+        let fetcher = Arc::new(BrowserFetcher::new(config, BrowserUsagePolicy::Auto));
+        assert!(!fetcher.is_initialized());
+        
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let fetcher = fetcher.clone();
+                tokio::spawn(async move { fetcher.initialize().await })
+            })
+            .collect();
+        for handle in handles {
+            handle.await.unwrap().unwrap();
+        }
+        
+        assert!(fetcher.is_initialized());
+        fetcher.initialize().await.unwrap();
+    }
+    
+    #[tokio::test]
+    async fn test_failed_initialization_is_retried() {
+        let config = McpConfig {
+            enabled: true,
+            server_command: vec!["/nonexistent/mcp-server".to_string()],
+            ..Default::default()
+        };
+        let fetcher = BrowserFetcher::new(config, BrowserUsagePolicy::Always);
+        
+        assert!(matches!(
+            fetcher.initialize().await,
+            Err(PreviewError::BrowserUnavailable(_))
+        ));
+        assert!(!fetcher.is_initialized());
+        assert!(fetcher.initialize().await.is_err());
+    }
+}
@@ -1,26 +1,465 @@
 use crate::Preview;
+use async_trait::async_trait;
 use dashmap::DashMap;
 use std::num::NonZeroUsize;
+use std::ops::Add;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Configuration for [`Cache`]
+#[derive(Debug, Clone)]
+pub struct CacheConfig {
+    /// TTL applied by [`Cache::set`]; `None` keeps entries until evicted
+    pub default_ttl: Option<Duration>,
+    /// Maximum number of live entries (0 falls back to 100)
+    pub capacity: usize,
+    /// How long past its TTL an entry with an `ETag` or `Last-Modified` is kept so it
+    /// can be revalidated with a conditional request (default: one hour). Entries
+    /// without validators are dropped as soon as they expire; `Duration::ZERO`
+    /// disables revalidation.
+    pub stale_retention: Duration,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            default_ttl: None,
+            capacity: 100,
+            stale_retention: Duration::from_secs(60 * 60),
+        }
+    }
+}
+
+/// Snapshot of cache activity counters
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    /// Entries dropped by the cache itself, either expired or evicted for capacity
+    pub evictions: u64,
+    pub inserts: u64,
+}
+
+impl Add for CacheStats {
+    type Output = CacheStats;
+
+    fn add(self, other: CacheStats) -> CacheStats {
+        CacheStats {
+            hits: self.hits + other.hits,
+            misses: self.misses + other.misses,
+            evictions: self.evictions + other.evictions,
+            inserts: self.inserts + other.inserts,
+        }
+    }
+}
+
+/// Storage backend for generated previews, keyed by URL.
+///
+/// [`Cache`] is the built-in in-memory implementation; implement this trait to back
+/// a [`UrlPreviewGenerator`](crate::UrlPreviewGenerator) with Redis, Memcached or
+/// any other store.
+#[async_trait]
+pub trait PreviewCache: Send + Sync {
+    async fn get(&self, key: &str) -> Option<Preview>;
+
+    async fn set(&self, key: String, value: Preview);
+
+    /// Drops the entry for `key`, if any, so the next lookup is a miss
+    async fn remove(&self, key: &str);
+
+    /// Whether a live entry exists for `key`. Backends should override this when a
+    /// lookup can skip fetching the preview or updating hit/miss counters.
+    async fn contains(&self, key: &str) -> bool {
+        self.get(key).await.is_some()
+    }
+
+    /// Stores a preview that should expire after `ttl`. Backends without expiry
+    /// support fall back to [`set`](Self::set).
+    async fn set_with_ttl(&self, key: String, value: Preview, _ttl: Duration) {
+        self.set(key, value).await
+    }
+
+    /// Returns the entry for `key` even after it has expired, as long as the backend
+    /// still holds it, so it can be revalidated with a conditional request instead
+    /// of fetched again. Backends that drop expired entries return `None`.
+    async fn get_stale(&self, _key: &str) -> Option<Preview> {
+        None
+    }
+
+    /// Activity counters; backends that do not track them report zeros
+    fn stats(&self) -> CacheStats {
+        CacheStats::default()
+    }
+}
+
+#[derive(Debug, Default)]
+struct CacheCounters {
+    hits: AtomicU64,
+    misses: AtomicU64,
+    evictions: AtomicU64,
+    inserts: AtomicU64,
+}
+
+#[derive(Clone)]
+struct CacheEntry {
+    preview: Preview,
+    inserted_at: Instant,
+    expires_at: Option<Instant>,
+}
+
+impl CacheEntry {
+    fn is_expired(&self, now: Instant) -> bool {
+        self.expires_at.is_some_and(|expires_at| now >= expires_at)
+    }
+
+    /// Expired entries with an `ETag` or `Last-Modified` are kept for revalidation
+    /// until `retention` past their expiry
+    fn is_revalidatable(&self, now: Instant, retention: Duration) -> bool {
+        self.expires_at
+            .is_none_or(|expires_at| now < expires_at + retention)
+            && self
+                .preview
+                .http_metadata
+                .as_ref()
+                .is_some_and(crate::HttpMeta::has_validators)
+    }
+}
 
 #[derive(Clone)]
 pub struct Cache {
-    cache: Arc<DashMap<String, Preview>>,
+    cache: Arc<DashMap<String, CacheEntry>>,
+    default_ttl: Option<Duration>,
+    capacity: usize,
+    stale_retention: Duration,
+    counters: Arc<CacheCounters>,
 }
 
 impl Cache {
     pub fn new(capacity: usize) -> Self {
-        let capacity = NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::new(100).unwrap());
+        Self::with_config(CacheConfig {
+            capacity,
+            ..Default::default()
+        })
+    }
+
+    pub fn with_config(config: CacheConfig) -> Self {
+        let capacity =
+            NonZeroUsize::new(config.capacity).unwrap_or(NonZeroUsize::new(100).unwrap());
         Self {
             cache: Arc::new(DashMap::with_capacity(capacity.get())),
+            default_ttl: config.default_ttl,
+            capacity: capacity.get(),
+            stale_retention: config.stale_retention,
+            counters: Arc::new(CacheCounters::default()),
         }
     }
 
+    /// Returns a snapshot of the hit/miss/eviction/insert counters
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.counters.hits.load(Ordering::Relaxed),
+            misses: self.counters.misses.load(Ordering::Relaxed),
+            evictions: self.counters.evictions.load(Ordering::Relaxed),
+            inserts: self.counters.inserts.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Returns the cached preview, treating expired entries as misses. Expired entries
+    /// are dropped unless they can be revalidated, see [`get_stale`](Self::get_stale).
     pub async fn get(&self, key: &str) -> Option<Preview> {
-        self.cache.get(key).map(|entry| entry.clone())
+        let now = Instant::now();
+        let Some(entry) = self.cache.get(key) else {
+            self.counters.misses.fetch_add(1, Ordering::Relaxed);
+            return None;
+        };
+        if !entry.is_expired(now) {
+            self.counters.hits.fetch_add(1, Ordering::Relaxed);
+            return Some(entry.preview.clone());
+        }
+
+        drop(entry);
+        if self
+            .cache
+            .remove_if(key, |_, entry| {
+                entry.is_expired(now) && !entry.is_revalidatable(now, self.stale_retention)
+            })
+            .is_some()
+        {
+            self.counters.evictions.fetch_add(1, Ordering::Relaxed);
+        }
+        self.counters.misses.fetch_add(1, Ordering::Relaxed);
+        None
+    }
+
+    /// Returns the entry for `key` whether or not it has expired, without touching
+    /// the counters. Expired entries remain only while they can be revalidated, see
+    /// [`CacheConfig::stale_retention`], and until they are evicted to make room.
+    pub async fn get_stale(&self, key: &str) -> Option<Preview> {
+        let now = Instant::now();
+        let entry = self.cache.get(key)?;
+        if !entry.is_expired(now) || entry.is_revalidatable(now, self.stale_retention) {
+            return Some(entry.preview.clone());
+        }
+
+        drop(entry);
+        if self
+            .cache
+            .remove_if(key, |_, entry| {
+                entry.is_expired(now) && !entry.is_revalidatable(now, self.stale_retention)
+            })
+            .is_some()
+        {
+            self.counters.evictions.fetch_add(1, Ordering::Relaxed);
+        }
+        None
     }
 
+    /// Whether a live entry exists for `key`, without touching the counters
+    pub fn contains(&self, key: &str) -> bool {
+        self.cache
+            .get(key)
+            .is_some_and(|entry| !entry.is_expired(Instant::now()))
+    }
+
+    /// Drops the entry for `key`, returning whether one was present. Removed
+    /// entries are not counted as evictions.
+    pub async fn remove(&self, key: &str) -> bool {
+        self.cache.remove(key).is_some()
+    }
+
+    /// Drops every entry
+    pub async fn clear(&self) {
+        self.cache.clear();
+    }
+
+    /// Inserts a preview using the configured default TTL
     pub async fn set(&self, key: String, value: Preview) {
-        self.cache.insert(key, value);
+        self.insert(key, value, self.default_ttl);
+    }
+
+    /// Inserts a preview that expires after `ttl`
+    pub async fn set_with_ttl(&self, key: String, value: Preview, ttl: Duration) {
+        self.insert(key, value, Some(ttl));
+    }
+
+    fn insert(&self, key: String, value: Preview, ttl: Option<Duration>) {
+        let now = Instant::now();
+        if !self.cache.contains_key(&key) && self.cache.len() >= self.capacity {
+            self.make_room(now);
+        }
+
+        self.counters.inserts.fetch_add(1, Ordering::Relaxed);
+        self.cache.insert(
+            key,
+            CacheEntry {
+                preview: value,
+                inserted_at: now,
+                expires_at: ttl.map(|ttl| now + ttl),
+            },
+        );
+    }
+
+    /// Drops expired entries, then the oldest entry if the cache is still full
+    fn make_room(&self, now: Instant) {
+        self.cache.retain(|_, entry| {
+            let expired = entry.is_expired(now);
+            if expired {
+                self.counters.evictions.fetch_add(1, Ordering::Relaxed);
+            }
+            !expired
+        });
+        if self.cache.len() < self.capacity {
+            return;
+        }
+
+        let oldest = self
+            .cache
+            .iter()
+            .min_by_key(|entry| entry.inserted_at)
+            .map(|entry| entry.key().clone());
+        if let Some(key) = oldest {
+            if self.cache.remove(&key).is_some() {
+                self.counters.evictions.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl PreviewCache for Cache {
+    async fn get(&self, key: &str) -> Option<Preview> {
+        Cache::get(self, key).await
+    }
+
+    async fn set(&self, key: String, value: Preview) {
+        Cache::set(self, key, value).await
+    }
+
+    async fn remove(&self, key: &str) {
+        Cache::remove(self, key).await;
+    }
+
+    async fn contains(&self, key: &str) -> bool {
+        Cache::contains(self, key)
+    }
+
+    async fn get_stale(&self, key: &str) -> Option<Preview> {
+        Cache::get_stale(self, key).await
+    }
+
+    async fn set_with_ttl(&self, key: String, value: Preview, ttl: Duration) {
+        Cache::set_with_ttl(self, key, value, ttl).await
+    }
+
+    fn stats(&self) -> CacheStats {
+        Cache::stats(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn preview(url: &str) -> Preview {
+        Preview {
+            url: url.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_zero_ttl_expires_immediately() {
+        let cache = Cache::new(10);
+        cache
+            .set_with_ttl("a".into(), preview("a"), Duration::from_secs(0))
+            .await;
+
+        assert!(cache.get("a").await.is_none());
+        assert!(cache.cache.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_default_ttl_applies_to_set() {
+        let cache = Cache::with_config(CacheConfig {
+            default_ttl: Some(Duration::ZERO),
+            capacity: 10,
+            ..Default::default()
+        });
+        cache.set("a".into(), preview("a")).await;
+        assert!(cache.get("a").await.is_none());
+
+        let cache = Cache::new(10);
+        cache.set("a".into(), preview("a")).await;
+        assert_eq!(cache.get("a").await.unwrap().url, "a");
+    }
+
+    #[tokio::test]
+    async fn test_capacity_evicts_expired_before_live_entries() {
+        let cache = Cache::new(2);
+        cache
+            .set_with_ttl("stale".into(), preview("stale"), Duration::ZERO)
+            .await;
+        cache.set("live".into(), preview("live")).await;
+        tokio::time::sleep(Duration::from_millis(2)).await;
+        cache.set("new".into(), preview("new")).await;
+
+        assert!(cache.get("live").await.is_some());
+        assert!(cache.get("new").await.is_some());
+        assert_eq!(cache.cache.len(), 2);
+
+        cache.set("newest".into(), preview("newest")).await;
+        assert!(cache.get("live").await.is_none());
+        assert!(cache.get("newest").await.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_expired_entries_with_validators_are_kept_for_revalidation() {
+        let mut revalidatable = preview("etag");
+        revalidatable.http_metadata = Some(crate::HttpMeta {
+            etag: Some("\"v1\"".into()),
+            ..Default::default()
+        });
+        let cache = Cache::new(10);
+        cache
+            .set_with_ttl("etag".into(), revalidatable, Duration::ZERO)
+            .await;
+        cache
+            .set_with_ttl("plain".into(), preview("plain"), Duration::ZERO)
+            .await;
+
+        assert!(cache.get("etag").await.is_none());
+        assert!(cache.get("plain").await.is_none());
+        assert_eq!(cache.get_stale("etag").await.unwrap().url, "etag");
+        assert!(cache.get_stale("plain").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_stale_retention_bounds_revalidatable_entries() {
+        let mut revalidatable = preview("etag");
+        revalidatable.http_metadata = Some(crate::HttpMeta {
+            etag: Some("\"v1\"".into()),
+            ..Default::default()
+        });
+        let cache = Cache::with_config(CacheConfig {
+            capacity: 10,
+            stale_retention: Duration::ZERO,
+            ..Default::default()
+        });
+        cache
+            .set_with_ttl("etag".into(), revalidatable, Duration::ZERO)
+            .await;
+
+        assert!(cache.get("etag").await.is_none());
+        assert!(cache.get_stale("etag").await.is_none());
+        assert!(cache.cache.is_empty());
+        assert_eq!(cache.stats().evictions, 1);
+    }
+
+    #[tokio::test]
+    async fn test_remove_and_clear() {
+        let cache = Cache::new(10);
+        cache.set("a".into(), preview("a")).await;
+        cache.set("b".into(), preview("b")).await;
+
+        assert!(cache.remove("a").await);
+        assert!(!cache.remove("a").await);
+        assert!(cache.get("a").await.is_none());
+        assert!(cache.get("b").await.is_some());
+
+        cache.clear().await;
+        assert!(cache.get("b").await.is_none());
+        assert_eq!(cache.stats().evictions, 0);
+    }
+
+    #[tokio::test]
+    async fn test_stats_count_known_sequence() {
+        let cache = Cache::new(2);
+        assert_eq!(cache.stats(), CacheStats::default());
+
+        cache.set("a".into(), preview("a")).await;
+        cache.set("b".into(), preview("b")).await;
+        assert!(cache.get("a").await.is_some());
+        assert!(cache.get("missing").await.is_none());
+
+        // Full: inserting a new key evicts the oldest entry
+        cache.set("c".into(), preview("c")).await;
+        // Overwriting an existing key does not evict
+        cache.set("c".into(), preview("c")).await;
+
+        cache
+            .set_with_ttl("c".into(), preview("c"), Duration::ZERO)
+            .await;
+        assert!(cache.get("c").await.is_none());
+
+        assert_eq!(
+            cache.stats(),
+            CacheStats {
+                hits: 1,
+                misses: 2,
+                evictions: 2,
+                inserts: 5,
+            }
+        );
     }
 }
@@ -1,3 +1,4 @@
+use std::time::Duration;
 use thiserror::Error;
 #[cfg(feature = "logging")]
 use tracing::{error, warn};
@@ -19,6 +20,10 @@ pub enum PreviewError {
     #[error("Rate limit exceeded: {0}")]
     RateLimitError(String),
 
+    /// The server kept answering 429 (or 503 with `Retry-After`) until retries ran out
+    #[error("Rate limited by server (retry after {retry_after:?})")]
+    RateLimited { retry_after: Option<Duration> },
+
     #[error("Invalid content type: {0}")]
     InvalidContentType(String),
 
@@ -76,11 +81,29 @@ pub enum PreviewError {
     #[error("Download time exceeded: {elapsed}s > {limit}s")]
     DownloadTimeExceeded { elapsed: u64, limit: u64 },
 
+    #[error("Too many redirects ({count}) while fetching {url}")]
+    RedirectLoop { url: String, count: usize },
+
+    #[error("Disallowed by robots.txt: {0}")]
+    RobotsDisallowed(String),
+
+    #[error("Port not allowed: {0}")]
+    PortNotAllowed(u16),
+
     #[error("Content type not allowed: {0}")]
     ContentTypeNotAllowed(String),
+
+    #[error("LLM output size exceeds limit: {size} > {limit}")]
+    OutputTooLarge { size: usize, limit: usize },
     
     #[error("Unsupported operation: {0}")]
     UnsupportedOperation(String),
+
+    #[error("Browser unavailable: {0}")]
+    BrowserUnavailable(String),
+
+    #[error("Page does not allow previews: {0}")]
+    PreviewNotAllowed(String),
     
     #[error("Configuration error: {0}")]
     InvalidConfiguration(String),
@@ -89,6 +112,14 @@ pub enum PreviewError {
     JsonError(#[from] serde_json::Error),
 }
 
+/// Raised by the fetcher's redirect policy once `max_redirects` is exceeded,
+/// carrying the number of redirects that were followed
+#[derive(Debug, Error)]
+#[error("too many redirects ({count})")]
+pub(crate) struct RedirectLimitExceeded {
+    pub(crate) count: usize,
+}
+
 impl PreviewError {
     pub fn log(&self) {
         #[cfg(feature = "logging")]
@@ -108,6 +139,9 @@ impl PreviewError {
             PreviewError::RateLimitError(e) => {
                 warn!(error = %e, "Rate limit exceeded");
             }
+            PreviewError::RateLimited { retry_after } => {
+                warn!(retry_after = ?retry_after, "Rate limited by server");
+            }
             PreviewError::InvalidContentType(e) => {
                 warn!(error = %e, "Invalid content type received");
             }
@@ -169,12 +203,33 @@ impl PreviewError {
             PreviewError::DownloadTimeExceeded { elapsed, limit } => {
                 warn!(elapsed = %elapsed, limit = %limit, "Download time exceeded");
             }
+            PreviewError::RedirectLoop { url, count } => {
+                warn!(url = %url, count = %count, "Redirect limit exceeded");
+            }
+            PreviewError::RobotsDisallowed(url) => {
+                warn!(url = %url, "Fetch disallowed by robots.txt");
+            }
+            PreviewError::PortNotAllowed(port) => {
+                warn!(port = %port, "Port not allowed");
+            }
             PreviewError::ContentTypeNotAllowed(content_type) => {
                 warn!(content_type = %content_type, "Content type not allowed");
             }
+            PreviewError::OutputTooLarge { size, limit } => {
+                warn!(size = %size, limit = %limit, "LLM output size exceeded");
+            }
             PreviewError::UnsupportedOperation(op) => {
                 warn!(operation = %op, "Unsupported operation");
             }
+            PreviewError::BrowserUnavailable(e) => {
+                error!(error = %e, "Browser automation unavailable");
+            }
+            PreviewError::PreviewNotAllowed(url) => {
+                warn!(url = %url, "Page does not allow previews");
+            }
+            PreviewError::InvalidConfiguration(e) => {
+                warn!(error = %e, "Invalid configuration");
+            }
             PreviewError::JsonError(e) => {
                 error!(error = %e, "JSON parsing error");
             }
@@ -185,9 +240,113 @@ impl PreviewError {
         }
     }
 
+    /// Whether the same request may succeed if tried again later: server errors,
+    /// timeouts, connection failures and rate limiting
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            PreviewError::ServerError { .. }
+                | PreviewError::ClientError { status: 429, .. }
+                | PreviewError::RateLimited { .. }
+                | PreviewError::RateLimitError(_)
+                | PreviewError::TimeoutError(_)
+                | PreviewError::DownloadTimeExceeded { .. }
+                | PreviewError::ConnectionError(_)
+        )
+    }
+
+    /// Whether the URL was refused by the URL validation policy (scheme, domain,
+    /// localhost, private IP or port) before anything was fetched
+    pub fn is_security_block(&self) -> bool {
+        matches!(
+            self,
+            PreviewError::LocalhostBlocked
+                | PreviewError::PrivateIpBlocked(_)
+                | PreviewError::InvalidUrlScheme(_)
+                | PreviewError::DomainBlocked(_)
+                | PreviewError::DomainNotAllowed(_)
+                | PreviewError::PortNotAllowed(_)
+        )
+    }
+
+    /// The HTTP status code behind this error, for errors caused by a response status
+    pub fn status_code(&self) -> Option<u16> {
+        match self {
+            PreviewError::HttpError { status, .. }
+            | PreviewError::ServerError { status, .. }
+            | PreviewError::ClientError { status, .. } => Some(*status),
+            PreviewError::NotFound(_) => Some(404),
+            _ => None,
+        }
+    }
+
+    /// A stable snake_case name for the variant, e.g. `not_found`, for use as a
+    /// metrics label or in structured logs
+    pub fn kind(&self) -> &'static str {
+        match self {
+            PreviewError::UrlParseError(_) => "url_parse_error",
+            PreviewError::FetchError(_) => "fetch_error",
+            PreviewError::ExtractError(_) => "extract_error",
+            PreviewError::CacheError(_) => "cache_error",
+            PreviewError::RateLimitError(_) => "rate_limit_error",
+            PreviewError::RateLimited { .. } => "rate_limited",
+            PreviewError::InvalidContentType(_) => "invalid_content_type",
+            PreviewError::TimeoutError(_) => "timeout",
+            PreviewError::DnsError(_) => "dns_error",
+            PreviewError::ConnectionError(_) => "connection_error",
+            PreviewError::HttpError { .. } => "http_error",
+            PreviewError::ServerError { .. } => "server_error",
+            PreviewError::ClientError { .. } => "client_error",
+            PreviewError::ExternalServiceError { .. } => "external_service_error",
+            PreviewError::ParseError(_) => "parse_error",
+            PreviewError::ConcurrencyLimitError => "concurrency_limit",
+            PreviewError::NotFound(_) => "not_found",
+            PreviewError::InvalidUrlScheme(_) => "invalid_url_scheme",
+            PreviewError::InvalidUrl(_) => "invalid_url",
+            PreviewError::DomainNotAllowed(_) => "domain_not_allowed",
+            PreviewError::DomainBlocked(_) => "domain_blocked",
+            PreviewError::LocalhostBlocked => "localhost_blocked",
+            PreviewError::PrivateIpBlocked(_) => "private_ip_blocked",
+            PreviewError::ContentSizeExceeded { .. } => "content_size_exceeded",
+            PreviewError::DownloadTimeExceeded { .. } => "download_time_exceeded",
+            PreviewError::RedirectLoop { .. } => "redirect_loop",
+            PreviewError::RobotsDisallowed(_) => "robots_disallowed",
+            PreviewError::PortNotAllowed(_) => "port_not_allowed",
+            PreviewError::ContentTypeNotAllowed(_) => "content_type_not_allowed",
+            PreviewError::OutputTooLarge { .. } => "output_too_large",
+            PreviewError::UnsupportedOperation(_) => "unsupported_operation",
+            PreviewError::BrowserUnavailable(_) => "browser_unavailable",
+            PreviewError::PreviewNotAllowed(_) => "preview_not_allowed",
+            PreviewError::InvalidConfiguration(_) => "invalid_configuration",
+            PreviewError::JsonError(_) => "json_error",
+        }
+    }
+
     /// Convert a reqwest error into a more specific PreviewError
     pub fn from_reqwest_error(error: reqwest::Error) -> Self {
-        if error.is_timeout() {
+        // Raised by the checked DNS resolver when a host resolves to a blocked address
+        let blocked_ip =
+            std::iter::successors(std::error::Error::source(&error), |source| source.source())
+                .find_map(|source| match source.downcast_ref::<PreviewError>() {
+                    Some(PreviewError::PrivateIpBlocked(ip)) => Some(ip.clone()),
+                    _ => None,
+                });
+
+        if let Some(ip) = blocked_ip {
+            PreviewError::PrivateIpBlocked(ip)
+        } else if error.is_redirect() {
+            // The fetcher's redirect policy records how many hops it followed
+            let count = std::error::Error::source(&error)
+                .and_then(|source| source.downcast_ref::<RedirectLimitExceeded>())
+                .map_or(0, |limit| limit.count);
+            PreviewError::RedirectLoop {
+                url: error.url().map(|url| url.to_string()).unwrap_or_default(),
+                count,
+            }
+        } else if error.is_connect() && error.is_timeout() {
+            // The connect timeout fired; the server was never reached
+            PreviewError::ConnectionError(format!("connect timed out: {error}"))
+        } else if error.is_timeout() {
             PreviewError::TimeoutError(error.to_string())
         } else if error.is_connect() {
             // Connection errors including DNS
@@ -225,3 +384,124 @@ impl PreviewError {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    fn sample_errors() -> Vec<PreviewError> {
+        vec![
+            PreviewError::ServerError {
+                status: 503,
+                message: String::new(),
+            },
+            PreviewError::ClientError {
+                status: 429,
+                message: String::new(),
+            },
+            PreviewError::ClientError {
+                status: 403,
+                message: String::new(),
+            },
+            PreviewError::HttpError {
+                status: 304,
+                message: String::new(),
+            },
+            PreviewError::NotFound("gone".into()),
+            PreviewError::RateLimited { retry_after: None },
+            PreviewError::TimeoutError("slow".into()),
+            PreviewError::DownloadTimeExceeded {
+                elapsed: 11,
+                limit: 10,
+            },
+            PreviewError::ConnectionError("refused".into()),
+            PreviewError::DnsError("nxdomain".into()),
+            PreviewError::LocalhostBlocked,
+            PreviewError::PrivateIpBlocked("10.0.0.1".into()),
+            PreviewError::InvalidUrlScheme("ftp".into()),
+            PreviewError::DomainBlocked("evil.com".into()),
+            PreviewError::DomainNotAllowed("other.com".into()),
+            PreviewError::PortNotAllowed(22),
+            PreviewError::ContentSizeExceeded { size: 2, limit: 1 },
+            PreviewError::RobotsDisallowed("https://example.com/".into()),
+        ]
+    }
+
+    #[test]
+    fn test_error_classification() {
+        // (retryable, security block, status code) for each sample, in order
+        let expected = [
+            (true, false, Some(503)),
+            (true, false, Some(429)),
+            (false, false, Some(403)),
+            (false, false, Some(304)),
+            (false, false, Some(404)),
+            (true, false, None),
+            (true, false, None),
+            (true, false, None),
+            (true, false, None),
+            (false, false, None),
+            (false, true, None),
+            (false, true, None),
+            (false, true, None),
+            (false, true, None),
+            (false, true, None),
+            (false, true, None),
+            (false, false, None),
+            (false, false, None),
+        ];
+
+        let errors = sample_errors();
+        assert_eq!(errors.len(), expected.len());
+        for (error, expected) in errors.iter().zip(expected) {
+            assert_eq!(
+                (
+                    error.is_retryable(),
+                    error.is_security_block(),
+                    error.status_code()
+                ),
+                expected,
+                "{error:?}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_redirect_limit_maps_to_redirect_loop() {
+        // Every request is redirected back to the same path
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut buf = [0u8; 1024];
+                let _ = stream.read(&mut buf).await;
+                let _ = stream
+                    .write_all(
+                        b"HTTP/1.1 302 Found\r\nLocation: /loop\r\n\
+                          Content-Length: 0\r\nConnection: close\r\n\r\n",
+                    )
+                    .await;
+            }
+        });
+
+        let client = reqwest::Client::builder()
+            .redirect(crate::fetcher::redirect_policy(3))
+            .build()
+            .unwrap();
+        let error = client
+            .get(format!("http://{addr}/start"))
+            .send()
+            .await
+            .unwrap_err();
+
+        match PreviewError::from_reqwest_error(error) {
+            PreviewError::RedirectLoop { url, count } => {
+                assert_eq!(url, format!("http://{addr}/loop"));
+                assert_eq!(count, 3);
+            }
+            other => panic!("expected RedirectLoop, got {other:?}"),
+        }
+    }
+}
@@ -1,14 +1,118 @@
 use super::is_twitter_url;
-use crate::{Preview, PreviewError};
+use crate::fetcher::{ImageInfo, OEmbedResponse, PdfInfo};
+use crate::{ContentLimits, Preview, PreviewError};
 use scraper::{Html, Selector};
+use serde_json::Value;
+use std::collections::HashSet;
 #[cfg(feature = "logging")]
 use tracing::debug;
+use url::Url;
 
 use crate::utils;
 
+/// A single `og:image` entry together with its structured properties
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct OgImage {
+    pub url: String,
+    pub secure_url: Option<String>,
+    pub mime_type: Option<String>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub alt: Option<String>,
+}
+
+/// The page's `og:video` together with its structured properties
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct VideoInfo {
+    /// Absolute URL, preferring `og:video:secure_url`
+    pub url: String,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    /// Length in seconds, from `og:video:duration` or `video:duration`
+    pub duration: Option<u64>,
+    /// `og:video:type`, e.g. `video/mp4` or `text/html` for a player page
+    pub mime: Option<String>,
+}
+
+/// Every metadata value found in a document, see [`MetadataExtractor::extract_all`]
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ExtractedMetadata {
+    /// `og:*` properties as `(property, content)` pairs in document order
+    pub open_graph: Vec<(String, String)>,
+    /// `twitter:*` tags as `(name, content)` pairs in document order
+    pub twitter: Vec<(String, String)>,
+    /// Text of the `<title>` element
+    pub title: Option<String>,
+    /// Content of `<meta name="description">`
+    pub meta_description: Option<String>,
+    /// Absolute URL of `<link rel="canonical">`
+    pub canonical_url: Option<String>,
+    /// Authors from JSON-LD, `article:author` or `meta[name=author]`
+    pub authors: Vec<String>,
+    /// `article:published_time` or JSON-LD `datePublished`, as declared
+    pub published_time: Option<String>,
+}
+
+/// An outbound link of a page, see [`MetadataExtractor::extract_links`]
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct LinkInfo {
+    /// Absolute `http(s)` URL
+    pub href: String,
+    /// Link text with whitespace collapsed
+    pub text: String,
+    /// The `rel` attribute, e.g. `nofollow`
+    pub rel: Option<String>,
+}
+
+/// Options controlling how metadata is extracted
+#[derive(Debug, Clone)]
+pub struct ExtractorConfig {
+    /// Derive `site_name` from the registrable domain when `og:site_name` is absent (default: true)
+    pub derive_site_name: bool,
+    /// Remove a trailing `" | <site_name>"` or `" - <site_name>"` from the title (default: false)
+    pub strip_site_suffix_from_title: bool,
+    /// Honor `<meta name="robots">` directives (default: false). `noindex`, `none` and
+    /// `nopreview` reject the page with `PreviewError::PreviewNotAllowed`; `nosnippet`
+    /// drops the description and images, and `max-image-preview:none` drops the images.
+    pub respect_robots_meta: bool,
+    /// CSS selectors tried in order for the title. A matching element's `content`
+    /// attribute is used, or its text when it has none, e.g. `meta[name='parsely-title']`.
+    pub title_selectors: Vec<String>,
+    /// CSS selectors tried in order for the description
+    pub description_selectors: Vec<String>,
+    /// CSS selectors tried in order for the image URL
+    pub image_selectors: Vec<String>,
+    /// Longer titles are cut at a word boundary and end with `…` (default: 300 characters)
+    pub max_title_len: usize,
+    /// Longer descriptions are cut at a word boundary and end with `…`
+    /// (default: 1000 characters)
+    pub max_description_len: usize,
+}
+
+impl Default for ExtractorConfig {
+    fn default() -> Self {
+        Self {
+            derive_site_name: true,
+            strip_site_suffix_from_title: false,
+            respect_robots_meta: false,
+            title_selectors: vec!["meta[property='og:title']".into(), "title".into()],
+            description_selectors: vec![
+                "meta[property='og:description']".into(),
+                "meta[name='description']".into(),
+            ],
+            image_selectors: vec!["meta[property='og:image'],meta[itemprop='image']".into()],
+            max_title_len: 300,
+            max_description_len: 1000,
+        }
+    }
+}
+
 /// Metadata extractor, responsible for extracting preview information from webpage content
 #[derive(Clone)]
-pub struct MetadataExtractor;
+pub struct MetadataExtractor {
+    config: ExtractorConfig,
+    max_parse_size: usize,
+}
 
 impl Default for MetadataExtractor {
     fn default() -> Self {
@@ -18,18 +122,115 @@ impl Default for MetadataExtractor {
 
 impl MetadataExtractor {
     pub fn new() -> Self {
-        Self
+        Self::with_config(ExtractorConfig::default())
     }
 
+    pub fn with_config(config: ExtractorConfig) -> Self {
+        Self {
+            config,
+            max_parse_size: ContentLimits::default().max_parse_size,
+        }
+    }
+
+    /// Only parse the first `max_parse_size` bytes of a page, or up to its `</head>`
+    /// when that comes first. See [`ContentLimits::max_parse_size`].
+    pub fn with_max_parse_size(mut self, max_parse_size: usize) -> Self {
+        self.max_parse_size = max_parse_size;
+        self
+    }
+
+    /// Build a [`Preview`] from already-fetched HTML, without any network access.
+    ///
+    /// `url` is the page's address: it selects Twitter handling and is used to
+    /// resolve relative links. Fails if `url` cannot be parsed, or with
+    /// `PreviewError::PreviewNotAllowed` when robots meta directives are respected
+    /// and forbid a preview.
+    ///
+    /// When the page's `</head>` falls within the parse cap, only the metadata tags
+    /// of `<head>` are parsed. The rest of the page is parsed only if that leaves
+    /// the preview without a title or image.
     pub fn extract(&self, html: &str, url: &str) -> Result<Preview, PreviewError> {
-        let document = Html::parse_document(html);
-        if is_twitter_url(url) {
-            if let Some(preview) = self.extract_twitter_metadata(&document, url) {
+        let window = capped(html, self.max_parse_size);
+        if let Some(head) = head_metadata_html(window) {
+            let preview = self.extract_document(&Html::parse_document(&head), url)?;
+            if preview.title.is_some() && preview.image_url.is_some() {
                 return Ok(preview);
             }
+            #[cfg(feature = "logging")]
+            debug!(url = %url, "Head metadata incomplete, parsing the full page");
         }
+        self.extract_document(&Html::parse_document(window), url)
+    }
+
+    fn extract_document(&self, document: &Html, url: &str) -> Result<Preview, PreviewError> {
+        let twitter_preview = if is_twitter_url(url) {
+            self.extract_twitter_metadata(document, url)
+        } else {
+            None
+        };
         // If not a Twitter URL or Twitter extraction failed, use generic extraction method
-        self.extract_generic_metadata(&document, url)
+        let mut preview = match twitter_preview {
+            Some(preview) => preview,
+            None => self.extract_generic_metadata(document, url)?,
+        };
+
+        if self.config.respect_robots_meta {
+            self.apply_robots_directives(document, &mut preview)?;
+        }
+        preview.title = preview.title.map(|title| {
+            utils::truncate_at_word(&utils::normalize_text(&title), self.config.max_title_len)
+        });
+        preview.description = preview.description.map(|description| {
+            utils::truncate_at_word(
+                &utils::normalize_text(&description),
+                self.config.max_description_len,
+            )
+        });
+        preview.site_name = preview
+            .site_name
+            .map(|site_name| utils::normalize_text(&site_name));
+        Ok(preview)
+    }
+
+    /// Apply `<meta name="robots">` directives to an extracted preview
+    fn apply_robots_directives(
+        &self,
+        document: &Html,
+        preview: &mut Preview,
+    ) -> Result<(), PreviewError> {
+        let Ok(selector) = Selector::parse("meta[name][content]") else {
+            return Ok(());
+        };
+
+        let directives: Vec<String> = document
+            .select(&selector)
+            .filter(|el| {
+                el.value()
+                    .attr("name")
+                    .is_some_and(|name| name.trim().eq_ignore_ascii_case("robots"))
+            })
+            .filter_map(|el| el.value().attr("content"))
+            .flat_map(|content| content.split(','))
+            .map(|directive| directive.trim().to_ascii_lowercase().replace(' ', ""))
+            .collect();
+
+        if directives
+            .iter()
+            .any(|d| matches!(d.as_str(), "noindex" | "none" | "nopreview"))
+        {
+            return Err(PreviewError::PreviewNotAllowed(preview.url.clone()));
+        }
+
+        let nosnippet = directives.iter().any(|d| d == "nosnippet");
+        if nosnippet {
+            preview.description = None;
+        }
+        if nosnippet || directives.iter().any(|d| d == "max-image-preview:none") {
+            preview.image_url = None;
+            preview.og_images.clear();
+            preview.images.clear();
+        }
+        Ok(())
     }
 
     fn extract_twitter_metadata(&self, document: &Html, url: &str) -> Option<Preview> {
@@ -77,6 +278,7 @@ impl MetadataExtractor {
             image_url: og_image,
             site_name: Some("X (formerly Twitter)".to_string()),
             favicon: Some("https://abs.twimg.com/favicons/twitter.ico".to_string()),
+            ..Default::default()
         })
     }
 
@@ -88,14 +290,50 @@ impl MetadataExtractor {
         let title = self.extract_title(document);
         let description = self.extract_description(document);
         let image_url = self.extract_image(document);
-        let favicon = self.extract_favicon(document);
-        let site_name = self.extract_site_name(document);
+        let site_name = self.extract_site_name(document).or_else(|| {
+            if self.config.derive_site_name {
+                Url::parse(url)
+                    .ok()
+                    .and_then(|u| u.host_str().and_then(utils::site_name_from_host))
+            } else {
+                None
+            }
+        });
 
-        let host = utils::pickup_host_from_url(url)?;
+        let title = match (title, &site_name) {
+            (Some(title), Some(site_name)) if self.config.strip_site_suffix_from_title => {
+                Some(strip_site_suffix(title, site_name))
+            }
+            (title, _) => title,
+        };
 
-        let image_url = format_url(image_url, &host);
+        let page_url = Url::parse(url)?;
+        let base_url = self.extract_base_url(document, &page_url);
+        let image_url = image_url.and_then(|image| utils::resolve_url(&base_url, &image));
+        let favicon = self.extract_favicon(document, &base_url, &page_url);
+        let canonical_url = self.extract_canonical_url(document, &base_url);
+        let (audio_url, audio_type) = self.extract_audio(document, &base_url);
+        let authors = self.extract_authors(document);
+        let mut images = Vec::new();
+        for image in self.extract_images(document) {
+            if let Some(image) = utils::resolve_url(&base_url, &image) {
+                if !images.contains(&image) {
+                    images.push(image);
+                }
+            }
+        }
 
-        let favicon = format_url(favicon, &host);
+        let og_images = self
+            .extract_og_images(document)
+            .into_iter()
+            .filter_map(|mut image| {
+                image.url = utils::resolve_url(&base_url, &image.url)?;
+                image.secure_url = image
+                    .secure_url
+                    .and_then(|secure_url| utils::resolve_url(&base_url, &secure_url));
+                Some(image)
+            })
+            .collect();
 
         Ok(Preview {
             url: url.to_string(),
@@ -104,77 +342,571 @@ impl MetadataExtractor {
             image_url,
             favicon,
             site_name,
+            canonical_url,
+            og_images,
+            audio_url,
+            audio_type,
+            author: authors.first().cloned(),
+            authors,
+            resolved_url: None,
+            published_at: self.extract_published_time(document),
+            modified_at: self.extract_modified_time(document),
+            keywords: self.extract_keywords(document),
+            language: self.extract_language(document),
+            images,
+            theme_color: self.extract_theme_color(document),
+            embed_url: self.extract_embed_url(document, &base_url),
+            media_type: self.extract_media_type(document),
+            video: self.extract_video(document, &base_url),
+            http_metadata: None,
         })
     }
 
     fn extract_title(&self, document: &Html) -> Option<String> {
-        let og_title_selector = Selector::parse("meta[property='og:title']").ok()?;
-        let title_selector = Selector::parse("title").ok()?;
+        select_first(document, &self.config.title_selectors)
+    }
+
+    fn extract_description(&self, document: &Html) -> Option<String> {
+        select_first(document, &self.config.description_selectors)
+    }
+
+    fn extract_image(&self, document: &Html) -> Option<String> {
+        select_first(document, &self.config.image_selectors)
+    }
+
+    /// Pick the declared icon, preferring `rel="icon"`, then `shortcut icon`, then
+    /// `apple-touch-icon`, and within each the largest `sizes`. Relative hrefs resolve
+    /// against `base`; without any icon link, `/favicon.ico` at the site root is used.
+    fn extract_favicon(&self, document: &Html, base: &Url, page: &Url) -> Option<String> {
+        let link_selector = Selector::parse("link[rel][href]").ok()?;
+
+        let declared = document
+            .select(&link_selector)
+            .filter_map(|el| {
+                let rel = el.value().attr("rel")?.to_ascii_lowercase();
+                let tokens: Vec<&str> = rel.split_ascii_whitespace().collect();
+                let rank = if tokens.contains(&"apple-touch-icon")
+                    || tokens.contains(&"apple-touch-icon-precomposed")
+                {
+                    2
+                } else if tokens.contains(&"icon") {
+                    if tokens.contains(&"shortcut") {
+                        1
+                    } else {
+                        0
+                    }
+                } else {
+                    return None;
+                };
+                let href = el.value().attr("href")?.trim();
+                if href.is_empty() {
+                    return None;
+                }
+                let size = el.value().attr("sizes").map_or(0, icon_size);
+                Some((rank, size, href))
+            })
+            // Lowest rank first, then largest size; ties keep document order
+            .min_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)))
+            .and_then(|(_, _, href)| utils::resolve_url(base, href));
+
+        declared.or_else(|| {
+            matches!(page.scheme(), "http" | "https")
+                .then(|| utils::resolve_url(page, "/favicon.ico"))
+                .flatten()
+        })
+    }
+
+    fn extract_site_name(&self, document: &Html) -> Option<String> {
+        let og_site_selector = Selector::parse("meta[property='og:site_name']").ok()?;
 
-        let og_title = document
-            .select(&og_title_selector)
+        document
+            .select(&og_site_selector)
             .next()
             .and_then(|el| el.value().attr("content"))
-            .map(|s| s.to_string());
+            .map(|s| s.trim().to_string())
+    }
 
-        // If there is no Open Graph title, try to get the regular title
-        og_title
-            .or_else(|| {
+    /// Group `og:image` structured properties by document order.
+    ///
+    /// Each `og:image` (or a differing `og:image:url`) starts a new image; the
+    /// following `og:image:*` properties describe the most recent one.
+    fn extract_og_images(&self, document: &Html) -> Vec<OgImage> {
+        let Ok(selector) = Selector::parse("meta[property^='og:image']") else {
+            return Vec::new();
+        };
+
+        let mut images: Vec<OgImage> = Vec::new();
+        for el in document.select(&selector) {
+            let (Some(property), Some(content)) =
+                (el.value().attr("property"), el.value().attr("content"))
+            else {
+                continue;
+            };
+            let content = content.trim();
+            if content.is_empty() {
+                continue;
+            }
+
+            match property.trim() {
+                "og:image" => images.push(OgImage {
+                    url: content.to_string(),
+                    ..Default::default()
+                }),
+                "og:image:url" => {
+                    if images.last().map(|image| image.url.as_str()) != Some(content) {
+                        images.push(OgImage {
+                            url: content.to_string(),
+                            ..Default::default()
+                        });
+                    }
+                }
+                field => {
+                    let Some(image) = images.last_mut() else {
+                        continue;
+                    };
+                    match field {
+                        "og:image:secure_url" => image.secure_url = Some(content.to_string()),
+                        "og:image:type" => image.mime_type = Some(content.to_string()),
+                        "og:image:width" => image.width = content.parse().ok(),
+                        "og:image:height" => image.height = content.parse().ok(),
+                        "og:image:alt" => image.alt = Some(content.to_string()),
+                        _ => {}
+                    }
+                }
+            }
+        }
+        images
+    }
+
+    /// Every `og:image`, `og:image:url`, `twitter:image` and `twitter:image:src`
+    /// value in document order, as declared
+    fn extract_images(&self, document: &Html) -> Vec<String> {
+        let Ok(selector) = Selector::parse("meta[content]") else {
+            return Vec::new();
+        };
+        document
+            .select(&selector)
+            .filter(|el| {
+                let key = el
+                    .value()
+                    .attr("property")
+                    .or_else(|| el.value().attr("name"))
+                    .unwrap_or_default();
+                matches!(
+                    key.trim(),
+                    "og:image" | "og:image:url" | "twitter:image" | "twitter:image:src"
+                )
+            })
+            .filter_map(|el| el.value().attr("content"))
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect()
+    }
+
+    /// Resolve the document base URL, honoring `<base href>` when present
+    fn extract_base_url(&self, document: &Html, url: &Url) -> Url {
+        Selector::parse("base[href]")
+            .ok()
+            .and_then(|selector| {
                 document
-                    .select(&title_selector)
+                    .select(&selector)
                     .next()
-                    .map(|el| el.inner_html())
+                    .and_then(|el| el.value().attr("href"))
+                    .and_then(|href| url.join(href.trim()).ok())
             })
-            .map(|s| s.trim().to_string())
+            .unwrap_or_else(|| url.clone())
     }
 
-    fn extract_description(&self, document: &Html) -> Option<String> {
-        let og_desc_selector = Selector::parse("meta[property='og:description']").ok()?;
-        let meta_desc_selector = Selector::parse("meta[name='description']").ok()?;
+    /// Extract the canonical URL, resolving relative values against the base URL
+    fn extract_canonical_url(&self, document: &Html, base: &Url) -> Option<String> {
+        let canonical_selector = Selector::parse("link[rel='canonical']").ok()?;
+        let og_url_selector = Selector::parse("meta[property='og:url']").ok()?;
 
         document
-            .select(&og_desc_selector)
+            .select(&canonical_selector)
             .next()
-            .and_then(|el| el.value().attr("content"))
+            .and_then(|el| el.value().attr("href"))
             .or_else(|| {
                 document
-                    .select(&meta_desc_selector)
+                    .select(&og_url_selector)
                     .next()
                     .and_then(|el| el.value().attr("content"))
             })
-            .map(|s| s.trim().to_string())
+            .and_then(|s| utils::resolve_url(base, s))
     }
 
-    fn extract_image(&self, document: &Html) -> Option<String> {
-        let og_image_selector =
-            Selector::parse("meta[property='og:image'],meta[itemprop='image']").ok()?;
+    /// Extract the audio URL and type from `og:audio*` properties, falling back to
+    /// `<link rel="alternate" type="audio/...">` enclosures
+    fn extract_audio(&self, document: &Html, base: &Url) -> (Option<String>, Option<String>) {
+        let meta_content = |property: &str| {
+            Selector::parse(&format!("meta[property='{property}']"))
+                .ok()
+                .and_then(|selector| {
+                    document
+                        .select(&selector)
+                        .filter_map(|el| el.value().attr("content"))
+                        .map(|s| s.trim())
+                        .find(|s| !s.is_empty())
+                        .map(String::from)
+                })
+        };
 
+        let og_audio = meta_content("og:audio")
+            .or_else(|| meta_content("og:audio:url"))
+            .or_else(|| meta_content("og:audio:secure_url"))
+            .and_then(|href| utils::resolve_url(base, &href));
+        if let Some(audio_url) = og_audio {
+            return (Some(audio_url), meta_content("og:audio:type"));
+        }
+
+        let Ok(enclosure_selector) = Selector::parse("link[rel~='alternate'][type^='audio/']")
+        else {
+            return (None, None);
+        };
         document
-            .select(&og_image_selector)
-            .next()
-            .and_then(|el| el.value().attr("content"))
-            .map(|s| s.trim().to_string())
+            .select(&enclosure_selector)
+            .find_map(|el| {
+                let audio_url = utils::resolve_url(base, el.value().attr("href")?)?;
+                let audio_type = el.value().attr("type").map(|t| t.trim().to_string());
+                Some((Some(audio_url), audio_type))
+            })
+            .unwrap_or((None, None))
     }
 
-    fn extract_favicon(&self, document: &Html) -> Option<String> {
-        let favicon_selector =
-            Selector::parse("link[rel='icon'], link[rel='shortcut icon']").ok()?;
+    /// Extract an embeddable player URL, preferring `og:video` (secure URL first),
+    /// then `twitter:player`, then `og:audio`
+    fn extract_embed_url(&self, document: &Html, base: &Url) -> Option<String> {
+        let twitter_player = || {
+            let selector = Selector::parse("meta[name='twitter:player'][content]").ok()?;
+            document
+                .select(&selector)
+                .filter_map(|el| el.value().attr("content"))
+                .map(str::trim)
+                .find(|s| !s.is_empty())
+                .map(String::from)
+        };
 
-        document
-            .select(&favicon_selector)
-            .next()
-            .and_then(|el| el.value().attr("href"))
-            .map(|s| s.trim().to_string())
+        meta_property(document, "og:video:secure_url")
+            .or_else(|| meta_property(document, "og:video:url"))
+            .or_else(|| meta_property(document, "og:video"))
+            .or_else(|| twitter_player().or_else(|| meta_property(document, "twitter:player")))
+            .or_else(|| meta_property(document, "og:audio:secure_url"))
+            .or_else(|| meta_property(document, "og:audio:url"))
+            .or_else(|| meta_property(document, "og:audio"))
+            .and_then(|href| utils::resolve_url(base, &href))
     }
 
-    fn extract_site_name(&self, document: &Html) -> Option<String> {
-        let og_site_selector = Selector::parse("meta[property='og:site_name']").ok()?;
+    fn extract_video(&self, document: &Html, base: &Url) -> Option<VideoInfo> {
+        let url = meta_property(document, "og:video:secure_url")
+            .or_else(|| meta_property(document, "og:video:url"))
+            .or_else(|| meta_property(document, "og:video"))
+            .and_then(|href| utils::resolve_url(base, &href))?;
 
+        Some(VideoInfo {
+            url,
+            width: meta_property(document, "og:video:width").and_then(|w| w.parse().ok()),
+            height: meta_property(document, "og:video:height").and_then(|h| h.parse().ok()),
+            duration: meta_property(document, "og:video:duration")
+                .or_else(|| meta_property(document, "video:duration"))
+                .and_then(|d| d.parse().ok()),
+            mime: meta_property(document, "og:video:type"),
+        })
+    }
+
+    /// `og:type` when it names a music or video object
+    fn extract_media_type(&self, document: &Html) -> Option<String> {
+        let og_type = meta_property(document, "og:type")?.to_ascii_lowercase();
+        (og_type.starts_with("music.") || og_type.starts_with("video.")).then_some(og_type)
+    }
+
+    /// Extract all authors, preferring JSON-LD `author`, then `article:author`,
+    /// then `meta[name=author]`. Duplicates are removed, order is preserved.
+    fn extract_authors(&self, document: &Html) -> Vec<String> {
+        let meta_values = |selector: &str| -> Vec<String> {
+            Selector::parse(selector)
+                .map(|selector| {
+                    document
+                        .select(&selector)
+                        .filter_map(|el| el.value().attr("content"))
+                        .map(|s| s.trim().to_string())
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+
+        let candidates = [
+            self.extract_json_ld_authors(document),
+            meta_values("meta[property='article:author']"),
+            meta_values("meta[name='author']"),
+        ];
+
+        let mut authors = Vec::new();
+        if let Some(found) = candidates
+            .into_iter()
+            .find(|found| found.iter().any(|a| !a.is_empty()))
+        {
+            for author in found {
+                if !author.is_empty() && !authors.contains(&author) {
+                    authors.push(author);
+                }
+            }
+        }
+        authors
+    }
+
+    /// Collect `author` names from the first JSON-LD node that declares any
+    fn extract_json_ld_authors(&self, document: &Html) -> Vec<String> {
+        fn names(author: &Value) -> Vec<String> {
+            match author {
+                Value::String(name) => vec![name.trim().to_string()],
+                Value::Object(obj) => obj
+                    .get("name")
+                    .and_then(Value::as_str)
+                    .map(|name| vec![name.trim().to_string()])
+                    .unwrap_or_default(),
+                Value::Array(items) => items.iter().flat_map(names).collect(),
+                _ => Vec::new(),
+            }
+        }
+
+        find_json_ld(document, &|obj| {
+            obj.get("author")
+                .map(names)
+                .filter(|found| !found.is_empty())
+        })
+        .unwrap_or_default()
+    }
+
+    /// The `article:published_time` property, falling back to JSON-LD `datePublished`
+    fn extract_published_time(&self, document: &Html) -> Option<String> {
+        meta_property(document, "article:published_time").or_else(|| {
+            find_json_ld(document, &|obj| {
+                obj.get("datePublished")
+                    .and_then(Value::as_str)
+                    .map(|date| date.trim().to_string())
+                    .filter(|date| !date.is_empty())
+            })
+        })
+    }
+
+    /// The `article:modified_time` property, falling back to JSON-LD `dateModified`
+    fn extract_modified_time(&self, document: &Html) -> Option<String> {
+        meta_property(document, "article:modified_time").or_else(|| {
+            find_json_ld(document, &|obj| {
+                obj.get("dateModified")
+                    .and_then(Value::as_str)
+                    .map(|date| date.trim().to_string())
+                    .filter(|date| !date.is_empty())
+            })
+        })
+    }
+
+    /// Collect keywords from comma-separated `meta[name=keywords]`, repeated
+    /// `article:tag` properties and JSON-LD `keywords` (a string or an array),
+    /// keeping the first spelling of each case-insensitive duplicate
+    fn extract_keywords(&self, document: &Html) -> Vec<String> {
+        let meta_values = |selector: &str| -> Vec<String> {
+            Selector::parse(selector)
+                .map(|selector| {
+                    document
+                        .select(&selector)
+                        .filter_map(|el| el.value().attr("content"))
+                        .map(String::from)
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+        let split = |list: &str| -> Vec<String> { list.split(',').map(String::from).collect() };
+
+        let meta_keywords = meta_values("meta[name='keywords']")
+            .iter()
+            .flat_map(|list| split(list))
+            .collect::<Vec<_>>();
+        let json_ld_keywords = find_json_ld(document, &|obj| match obj.get("keywords")? {
+            Value::String(list) => Some(split(list)),
+            Value::Array(items) => Some(
+                items
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .map(String::from)
+                    .collect(),
+            ),
+            _ => None,
+        })
+        .unwrap_or_default();
+
+        let mut keywords: Vec<String> = Vec::new();
+        for keyword in meta_keywords
+            .into_iter()
+            .chain(meta_values("meta[property='article:tag']"))
+            .chain(json_ld_keywords)
+        {
+            let keyword = keyword.trim();
+            if !keyword.is_empty()
+                && !keywords
+                    .iter()
+                    .any(|seen| seen.to_lowercase() == keyword.to_lowercase())
+            {
+                keywords.push(keyword.to_string());
+            }
+        }
+        keywords
+    }
+
+    /// The content language from `og:locale`, then `<html lang>`, then
+    /// `<meta http-equiv="Content-Language">` (first entry of a list)
+    fn extract_language(&self, document: &Html) -> Option<String> {
+        let html_lang = || {
+            let selector = Selector::parse("html[lang]").ok()?;
+            document
+                .select(&selector)
+                .next()
+                .and_then(|el| el.value().attr("lang"))
+                .map(String::from)
+        };
+        let content_language = || {
+            let selector = Selector::parse("meta[http-equiv][content]").ok()?;
+            document
+                .select(&selector)
+                .find(|el| {
+                    el.value()
+                        .attr("http-equiv")
+                        .is_some_and(|name| name.trim().eq_ignore_ascii_case("content-language"))
+                })
+                .and_then(|el| el.value().attr("content"))
+                .and_then(|list| list.split(',').next())
+                .map(String::from)
+        };
+
+        [meta_property(document, "og:locale"), html_lang(), content_language()]
+            .into_iter()
+            .flatten()
+            .map(|tag| tag.trim().replace('_', "-").to_ascii_lowercase())
+            .find(|tag| !tag.is_empty())
+    }
+
+    /// The first valid `theme-color`, preferring tags without a `media` query over
+    /// light/dark variants
+    fn extract_theme_color(&self, document: &Html) -> Option<String> {
+        let selector = Selector::parse("meta[name][content]").ok()?;
+        let mut colors: Vec<(bool, String)> = document
+            .select(&selector)
+            .filter(|el| {
+                el.value()
+                    .attr("name")
+                    .is_some_and(|name| name.trim().eq_ignore_ascii_case("theme-color"))
+            })
+            .filter_map(|el| {
+                let color = normalize_hex_color(el.value().attr("content")?)?;
+                Some((el.value().attr("media").is_some(), color))
+            })
+            .collect();
+        // Stable, so document order is kept within each group
+        colors.sort_by_key(|(has_media, _)| *has_media);
+        colors.into_iter().next().map(|(_, color)| color)
+    }
+
+    /// Extract every metadata value found in `html` without building a [`Preview`].
+    ///
+    /// Unlike [`extract`](Self::extract), nothing is merged or derived: Open Graph
+    /// and Twitter tags are returned as declared, so callers can apply their own
+    /// precedence. Relative canonical links are resolved against `url`.
+    pub fn extract_all(&self, html: &str, url: &str) -> ExtractedMetadata {
+        let document = Html::parse_document(parse_window(html, self.max_parse_size));
+        let tags = |prefix: &str| -> Vec<(String, String)> {
+            let Ok(selector) = Selector::parse("meta[content]") else {
+                return Vec::new();
+            };
+            document
+                .select(&selector)
+                .filter_map(|el| {
+                    let key = el
+                        .value()
+                        .attr("property")
+                        .or_else(|| el.value().attr("name"))?
+                        .trim();
+                    key.starts_with(prefix).then(|| {
+                        let content = el.value().attr("content").unwrap_or_default();
+                        (key.to_string(), content.trim().to_string())
+                    })
+                })
+                .collect()
+        };
+
+        let title = Selector::parse("title").ok().and_then(|selector| {
+            document
+                .select(&selector)
+                .next()
+                .map(|el| el.text().collect::<String>().trim().to_string())
+        });
+        let meta_description = Selector::parse("meta[name='description']")
+            .ok()
+            .and_then(|selector| {
+                document
+                    .select(&selector)
+                    .next()
+                    .and_then(|el| el.value().attr("content"))
+                    .map(|s| s.trim().to_string())
+            });
+        let canonical_url = Url::parse(url).ok().and_then(|page_url| {
+            let base_url = self.extract_base_url(&document, &page_url);
+            let selector = Selector::parse("link[rel='canonical']").ok()?;
+            let href = document
+                .select(&selector)
+                .next()
+                .and_then(|el| el.value().attr("href"))?;
+            utils::resolve_url(&base_url, href)
+        });
+
+        ExtractedMetadata {
+            open_graph: tags("og:"),
+            twitter: tags("twitter:"),
+            title,
+            meta_description,
+            canonical_url,
+            authors: self.extract_authors(&document),
+            published_time: self.extract_published_time(&document),
+        }
+    }
+
+    /// Extract the `<a href>` links of a page in document order.
+    ///
+    /// Hrefs are resolved against `<base href>` or `base_url`; only `http(s)` links
+    /// are kept, and each resolved URL appears once, with the text and `rel` of its
+    /// first occurrence. The whole body is parsed, up to the extractor's
+    /// `max_parse_size`.
+    pub fn extract_links(&self, html: &str, base_url: &str) -> Vec<LinkInfo> {
+        let Ok(page_url) = Url::parse(base_url) else {
+            return Vec::new();
+        };
+        let Ok(selector) = Selector::parse("a[href]") else {
+            return Vec::new();
+        };
+        let document = Html::parse_document(capped(html, self.max_parse_size));
+        let base = self.extract_base_url(&document, &page_url);
+
+        let mut seen = HashSet::new();
         document
-            .select(&og_site_selector)
-            .next()
-            .and_then(|el| el.value().attr("content"))
-            .map(|s| s.trim().to_string())
+            .select(&selector)
+            .filter_map(|el| {
+                let href = utils::resolve_url(&base, el.value().attr("href")?)?;
+                let is_http = matches!(Url::parse(&href).ok()?.scheme(), "http" | "https");
+                if !is_http || !seen.insert(href.clone()) {
+                    return None;
+                }
+                let text = el.text().collect::<String>();
+                Some(LinkInfo {
+                    href,
+                    text: text.split_whitespace().collect::<Vec<_>>().join(" "),
+                    rel: el
+                        .value()
+                        .attr("rel")
+                        .map(str::trim)
+                        .filter(|rel| !rel.is_empty())
+                        .map(String::from),
+                })
+            })
+            .collect()
     }
 
     /// Create a preview from oEmbed data.
@@ -219,25 +951,1243 @@ impl MetadataExtractor {
             image_url: image_link,
             site_name: Some("X (formerly Twitter)".to_string()),
             favicon: Some("https://abs.twimg.com/favicons/twitter.ico".to_string()),
+            ..Default::default()
         })
     }
+
+    /// Builds a preview from a YouTube oEmbed response, using the channel name as
+    /// `site_name` and the video thumbnail as `image_url`
+    pub fn extract_from_youtube_oembed(&self, oembed: &OEmbedResponse) -> Preview {
+        video_oembed_preview(oembed, "https://www.youtube.com/favicon.ico")
+    }
+
+    /// Builds a preview from a Vimeo oEmbed response, using the uploader as
+    /// `site_name`, the video thumbnail as `image_url` and the video description
+    pub fn extract_from_vimeo_oembed(&self, oembed: &OEmbedResponse) -> Preview {
+        video_oembed_preview(oembed, "https://vimeo.com/favicon.ico")
+    }
+
+    /// Preview of a URL that serves an image directly: the image is its own
+    /// thumbnail and the file name stands in for a title
+    pub fn extract_from_image(&self, image: &ImageInfo, url: &str) -> Preview {
+        Preview {
+            url: url.to_string(),
+            title: file_name(url),
+            image_url: Some(url.to_string()),
+            og_images: vec![OgImage {
+                url: url.to_string(),
+                secure_url: None,
+                mime_type: Some(image.content_type.clone()),
+                width: image.width,
+                height: image.height,
+                alt: None,
+            }],
+            media_type: Some(image.content_type.clone()),
+            ..Default::default()
+        }
+    }
+
+    /// Preview of a PDF from its document info, titled by file name when the
+    /// document has no title
+    pub fn extract_from_pdf(&self, pdf: &PdfInfo, url: &str) -> Preview {
+        Preview {
+            url: url.to_string(),
+            title: pdf.title.clone().or_else(|| file_name(url)),
+            description: pdf.subject.clone(),
+            author: pdf.author.clone(),
+            authors: pdf.author.iter().cloned().collect(),
+            media_type: Some("application/pdf".to_string()),
+            ..Default::default()
+        }
+    }
+}
+
+/// Value of the first element matched by the first selector that yields one: its
+/// `content` attribute, or its text for elements such as `<title>`. Selectors
+/// that fail to parse are skipped.
+fn select_first(document: &Html, selectors: &[String]) -> Option<String> {
+    selectors.iter().find_map(|selector| {
+        let Ok(selector) = Selector::parse(selector) else {
+            #[cfg(feature = "logging")]
+            debug!(selector = %selector, "Skipping invalid extractor selector");
+            return None;
+        };
+        let element = document.select(&selector).next()?;
+        let value = match element.value().attr("content") {
+            Some(content) => content.to_string(),
+            None => element.text().collect(),
+        };
+        Some(value.trim().to_string()).filter(|value| !value.is_empty())
+    })
+}
+
+/// Last non-empty path segment of a URL, e.g. `report.pdf`
+fn file_name(url: &str) -> Option<String> {
+    Url::parse(url).ok().and_then(|url| {
+        url.path_segments()?
+            .rev()
+            .find(|segment| !segment.is_empty())
+            .map(String::from)
+    })
+}
+
+/// Preview of a video from its provider's oEmbed response, attributed to the uploader
+fn video_oembed_preview(oembed: &OEmbedResponse, favicon: &str) -> Preview {
+    let uploader = Some(oembed.author_name.trim())
+        .filter(|name| !name.is_empty())
+        .map(String::from);
+    let description = oembed
+        .description
+        .as_deref()
+        .map(str::trim)
+        .filter(|description| !description.is_empty())
+        .map(String::from);
+
+    Preview {
+        title: oembed.title.clone(),
+        description,
+        image_url: oembed.thumbnail_url.clone(),
+        site_name: uploader
+            .clone()
+            .or_else(|| Some(oembed.provider_name.clone())),
+        favicon: Some(favicon.to_string()),
+        author: uploader.clone(),
+        authors: uploader.into_iter().collect(),
+        ..Default::default()
+    }
 }
 
-// Helper function to check if a URL is absolute and format it accordingly
-fn format_url(url: Option<String>, host: &str) -> Option<String> {
-    fn is_absolute_url(url: &str) -> bool {
-        url.starts_with("http://") || url.starts_with("https://")
+/// Normalizes a CSS hex color (`#rgb`, `#rgba`, `#rrggbb` or `#rrggbbaa`) to
+/// lowercase `#rrggbb`, dropping any alpha channel
+fn normalize_hex_color(value: &str) -> Option<String> {
+    let hex = value.trim().strip_prefix('#')?;
+    if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
     }
+    let rgb: String = match hex.len() {
+        3 | 4 => hex[..3].chars().flat_map(|c| [c, c]).collect(),
+        6 | 8 => hex[..6].to_string(),
+        _ => return None,
+    };
+    Some(format!("#{}", rgb.to_ascii_lowercase()))
+}
 
-    if let Some(url) = url {
-        if is_absolute_url(&url) {
-            Some(url)
-        } else if url.starts_with('/') {
-            Some(format!("{host}{url}"))
-        } else {
-            Some(format!("{host}/{url}"))
+/// The first `max_parse_size` bytes of `html`, backed off to a character boundary
+fn capped(html: &str, max_parse_size: usize) -> &str {
+    let mut end = html.len().min(max_parse_size);
+    while !html.is_char_boundary(end) {
+        end -= 1;
+    }
+    &html[..end]
+}
+
+/// Byte offset of the first `</head>` in `html`, matched case-insensitively
+fn find_head_end(html: &str) -> Option<usize> {
+    html.as_bytes()
+        .windows(HEAD_END.len())
+        .position(|window| window.eq_ignore_ascii_case(HEAD_END.as_bytes()))
+}
+
+const HEAD_END: &str = "</head>";
+
+/// The prefix of `html` worth parsing for metadata: up to and including the first
+/// `</head>` if it occurs within `max_parse_size` bytes, else the first
+/// `max_parse_size` bytes
+fn parse_window(html: &str, max_parse_size: usize) -> &str {
+    let window = capped(html, max_parse_size);
+    match find_head_end(window) {
+        Some(pos) => &window[..pos + HEAD_END.len()],
+        None => window,
+    }
+}
+
+/// A minimal document holding only the metadata-bearing tags of `window`'s
+/// `<head>`: `<html>` (for `lang`), `meta`, `link`, `base`, `title` and JSON-LD
+/// scripts. Other scripts, styles and comments are skipped without being parsed.
+///
+/// Returns `None` when `window` has no `</head>`, or a head element runs past it.
+fn head_metadata_html(window: &str) -> Option<String> {
+    let head = &window[..find_head_end(window)?];
+    let lower = head.to_ascii_lowercase();
+    let mut out = String::new();
+    let mut pos = 0;
+
+    while let Some(offset) = lower[pos..].find('<') {
+        let start = pos + offset;
+        let rest = &lower[start + 1..];
+        if rest.starts_with("!--") {
+            pos = start + rest.find("-->").map_or(lower.len() - start, |end| end + 4);
+            continue;
         }
-    } else {
-        None
+
+        let name_len = rest
+            .find(|c: char| !c.is_ascii_alphanumeric())
+            .unwrap_or(rest.len());
+        let name = &rest[..name_len];
+        let tag_end = start_tag_end(&lower, start)?;
+        match name {
+            "html" | "meta" | "link" | "base" => out.push_str(&head[start..tag_end]),
+            // Raw text elements: jump over their content in one go
+            "title" | "script" | "style" | "noscript" | "template" => {
+                let close = format!("</{name}");
+                let content_end = tag_end + lower[tag_end..].find(&close)?;
+                let keep = name == "title"
+                    || (name == "script" && lower[start..tag_end].contains("ld+json"));
+                if keep {
+                    out.push_str(&head[start..content_end]);
+                    out.push_str(&close);
+                    out.push('>');
+                }
+                pos = content_end + close.len();
+                continue;
+            }
+            _ => {}
+        }
+        pos = tag_end;
+    }
+
+    Some(out)
+}
+
+/// Offset just past the `>` closing the tag that starts at `start`, ignoring any
+/// `>` inside quoted attribute values
+fn start_tag_end(html: &str, start: usize) -> Option<usize> {
+    let mut quote = None;
+    for (index, byte) in html.bytes().enumerate().skip(start) {
+        match (quote, byte) {
+            (Some(open), _) if byte == open => quote = None,
+            (Some(_), _) => {}
+            (None, b'"' | b'\'') => quote = Some(byte),
+            (None, b'>') => return Some(index + 1),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// The trimmed, non-empty content of the first `meta[property=...]` tag
+fn meta_property(document: &Html, property: &str) -> Option<String> {
+    let selector = Selector::parse(&format!("meta[property='{property}']")).ok()?;
+    document
+        .select(&selector)
+        .filter_map(|el| el.value().attr("content"))
+        .map(|s| s.trim())
+        .find(|s| !s.is_empty())
+        .map(String::from)
+}
+
+/// Search every JSON-LD block, including `@graph` nodes, for the first object
+/// that `pick` returns a value for
+fn find_json_ld<T>(
+    document: &Html,
+    pick: &dyn Fn(&serde_json::Map<String, Value>) -> Option<T>,
+) -> Option<T> {
+    fn find<T>(
+        node: &Value,
+        pick: &dyn Fn(&serde_json::Map<String, Value>) -> Option<T>,
+    ) -> Option<T> {
+        match node {
+            Value::Array(items) => items.iter().find_map(|item| find(item, pick)),
+            Value::Object(obj) => pick(obj).or_else(|| find(obj.get("@graph")?, pick)),
+            _ => None,
+        }
+    }
+
+    let selector = Selector::parse("script[type='application/ld+json']").ok()?;
+    document
+        .select(&selector)
+        .filter_map(|el| serde_json::from_str::<Value>(&el.text().collect::<String>()).ok())
+        .find_map(|json| find(&json, pick))
+}
+
+/// Remove an exact trailing `<separator><site_name>` from a title, keeping the
+/// title unchanged when nothing would remain
+fn strip_site_suffix(title: String, site_name: &str) -> String {
+    const SEPARATORS: [&str; 4] = [" | ", " - ", " – ", " — "];
+
+    SEPARATORS
+        .iter()
+        .find_map(|separator| {
+            title
+                .strip_suffix(site_name)?
+                .strip_suffix(separator)
+                .map(str::trim_end)
+                .filter(|stripped| !stripped.is_empty())
+        })
+        .map(String::from)
+        .unwrap_or(title)
+}
+
+/// Largest edge among the `sizes` entries (e.g. `"16x16 32x32"`); `any` (scalable
+/// icons) outranks every fixed size
+fn icon_size(sizes: &str) -> u32 {
+    sizes
+        .split_ascii_whitespace()
+        .filter_map(|size| {
+            if size.eq_ignore_ascii_case("any") {
+                return Some(u32::MAX);
+            }
+            let (width, height) = size.to_ascii_lowercase().split_once('x').map(|(w, h)| {
+                (w.parse::<u32>().ok(), h.parse::<u32>().ok())
+            })?;
+            Some(width?.max(height?))
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_window_stops_at_head_or_cap() {
+        let html = "<html><head><title>T</title></HEAD><body>long body</body></html>";
+        assert_eq!(
+            parse_window(html, 1024),
+            "<html><head><title>T</title></HEAD>"
+        );
+        // `</head>` past the cap is not looked for
+        assert_eq!(parse_window(html, 12), "<html><head>");
+        // Never splits a multi-byte character
+        assert_eq!(parse_window("<title>é</title>", 8), "<title>");
+    }
+
+    #[test]
+    fn test_head_scan_keeps_only_metadata_tags() {
+        let html = r#"<!DOCTYPE html><html lang="en"><head>
+            <!-- <meta property="og:title" content="commented out"> -->
+            <style>body > p { color: red }</style>
+            <script>if (a < b) { document.title = "<title>nope</title>"; }</script>
+            <script type="application/ld+json">{"headline": "a > b"}</script>
+            <title>Fast</title>
+            <meta property="og:image" content="/img.png?a=1&b=>2">
+            <link rel="icon" href="/favicon.ico">
+        </head><body><meta property="og:description" content="body"></body></html>"#;
+
+        let head = head_metadata_html(html).unwrap();
+        assert_eq!(
+            head,
+            concat!(
+                r#"<html lang="en">"#,
+                r#"<script type="application/ld+json">{"headline": "a > b"}</script>"#,
+                "<title>Fast</title>",
+                r#"<meta property="og:image" content="/img.png?a=1&b=>2">"#,
+                r#"<link rel="icon" href="/favicon.ico">"#,
+            )
+        );
+        assert_eq!(head_metadata_html("<html><head><title>Open"), None);
+    }
+
+    #[test]
+    fn test_head_only_page_uses_fast_path() {
+        let html = r#"<html><head>
+            <title>Head only</title>
+            <meta property="og:image" content="https://example.com/head.png">
+        </head><body>
+            <meta property="og:description" content="Declared in body">
+        </body></html>"#;
+        let preview = MetadataExtractor::new()
+            .extract(html, "https://example.com/")
+            .unwrap();
+
+        assert_eq!(preview.title.as_deref(), Some("Head only"));
+        assert_eq!(
+            preview.image_url.as_deref(),
+            Some("https://example.com/head.png")
+        );
+        // Title and image came from the head, so the body was never parsed
+        assert_eq!(preview.description, None);
+    }
+
+    #[test]
+    fn test_body_only_image_found_by_fallback() {
+        let html = r#"<html><head><title>Sloppy</title></head><body>
+            <meta property="og:image" content="https://example.com/body.png">
+        </body></html>"#;
+        let preview = MetadataExtractor::new()
+            .extract(html, "https://example.com/")
+            .unwrap();
+
+        assert_eq!(preview.title.as_deref(), Some("Sloppy"));
+        assert_eq!(
+            preview.image_url.as_deref(),
+            Some("https://example.com/body.png")
+        );
+    }
+
+    #[test]
+    fn test_head_tags_before_parse_cap_are_found() {
+        let html = format!(
+            r#"<html><head><title>Capped</title>
+            <meta property="og:image" content="https://example.com/a.png">
+            <meta property="og:description" content="{}">"#,
+            "d".repeat(200)
+        ) + &format!(
+            "{}<meta property=\"og:site_name\" content=\"Too late\">",
+            " ".repeat(4096)
+        );
+        let preview = MetadataExtractor::new()
+            .with_max_parse_size(1024)
+            .extract(&html, "https://example.com/")
+            .unwrap();
+
+        assert_eq!(preview.title.as_deref(), Some("Capped"));
+        assert_eq!(
+            preview.image_url.as_deref(),
+            Some("https://example.com/a.png")
+        );
+        assert_eq!(preview.description.map(|d| d.len()), Some(200));
+        // Past the cap, so the domain-derived fallback is used instead
+        assert_ne!(preview.site_name.as_deref(), Some("Too late"));
+    }
+
+    #[test]
+    fn test_parse_window_is_capped_without_head_end() {
+        const CAP: usize = 64 * 1024;
+        // No `</head>`, so only the cap bounds parsing
+        let html = format!(
+            "<html><head><title>Big</title><body>{}</body></html>",
+            "<p>filler é</p>".repeat(64 * 1024)
+        );
+
+        let window = parse_window(&html, CAP);
+        assert!(window.len() <= CAP);
+        assert!(html.starts_with(window));
+        assert!(capped(&html, CAP).len() <= CAP);
+
+        let preview = MetadataExtractor::new()
+            .with_max_parse_size(CAP)
+            .extract(&html, "https://example.com/")
+            .unwrap();
+        assert_eq!(preview.title.as_deref(), Some("Big"));
+    }
+
+    #[test]
+    #[ignore = "timing-sensitive; run with --ignored on an idle machine"]
+    fn test_parse_time_does_not_scale_past_cap() {
+        const CAP: usize = 64 * 1024;
+        // No `</head>`, so only the cap bounds parsing
+        let page = |body_size: usize| {
+            format!(
+                "<html><head><title>Big</title><body>{}</body></html>",
+                "<p>filler text</p>".repeat(body_size / 18)
+            )
+        };
+        let extractor = MetadataExtractor::new().with_max_parse_size(CAP);
+        let time = |html: &str| {
+            let started = std::time::Instant::now();
+            let preview = extractor.extract(html, "https://example.com/").unwrap();
+            assert_eq!(preview.title.as_deref(), Some("Big"));
+            started.elapsed()
+        };
+
+        let small = page(256 * 1024);
+        let large = page(16 * 1024 * 1024);
+        time(&small);
+        let small_time = time(&small);
+        let large_time = time(&large);
+        // The large page is 64x bigger; uncapped it would parse roughly 64x slower
+        assert!(
+            large_time < small_time * 8 + std::time::Duration::from_millis(50),
+            "small: {small_time:?}, large: {large_time:?}"
+        );
+    }
+
+    #[test]
+    fn test_relative_og_url_resolves_to_absolute_canonical() {
+        let html = r#"<html><head>
+            <meta property="og:url" content="/article/1">
+        </head></html>"#;
+        let preview = MetadataExtractor::new()
+            .extract(html, "https://example.com/news/latest")
+            .unwrap();
+        assert_eq!(
+            preview.canonical_url.as_deref(),
+            Some("https://example.com/article/1")
+        );
+    }
+
+    #[test]
+    fn test_canonical_link_preferred_and_resolved_against_base() {
+        let html = r#"<html><head>
+            <base href="https://cdn.example.org/site/">
+            <link rel="canonical" href="post?id=7">
+            <meta property="og:url" content="https://example.com/other">
+        </head></html>"#;
+        let preview = MetadataExtractor::new()
+            .extract(html, "https://example.com/")
+            .unwrap();
+        assert_eq!(
+            preview.canonical_url.as_deref(),
+            Some("https://cdn.example.org/site/post?id=7")
+        );
+    }
+
+    #[test]
+    fn test_og_image_properties_grouped_by_document_order() {
+        let html = r#"<html><head>
+            <meta property="og:image" content="https://example.com/a.png">
+            <meta property="og:image:width" content="1200">
+            <meta property="og:image:height" content="630">
+            <meta property="og:image:alt" content="First image">
+            <meta property="og:image" content="/b.jpg">
+            <meta property="og:image:type" content="image/jpeg">
+            <meta property="og:image:width" content="400">
+            <meta property="og:image:height" content="300">
+            <meta property="og:image:alt" content="Second image">
+        </head></html>"#;
+        let preview = MetadataExtractor::new()
+            .extract(html, "https://example.com/post")
+            .unwrap();
+
+        assert_eq!(
+            preview.og_images,
+            vec![
+                OgImage {
+                    url: "https://example.com/a.png".to_string(),
+                    width: Some(1200),
+                    height: Some(630),
+                    alt: Some("First image".to_string()),
+                    ..Default::default()
+                },
+                OgImage {
+                    url: "https://example.com/b.jpg".to_string(),
+                    mime_type: Some("image/jpeg".to_string()),
+                    width: Some(400),
+                    height: Some(300),
+                    alt: Some("Second image".to_string()),
+                    ..Default::default()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_site_name_derived_from_domain_when_missing() {
+        let html = "<html><head><title>Minimal</title></head></html>";
+        let preview = MetadataExtractor::new()
+            .extract(html, "https://www.example.co.uk/page")
+            .unwrap();
+        assert_eq!(preview.site_name.as_deref(), Some("Example"));
+
+        let preview = MetadataExtractor::new()
+            .extract(html, "https://news.the-verge.com/page")
+            .unwrap();
+        assert_eq!(preview.site_name.as_deref(), Some("The-Verge"));
+
+        let extractor = MetadataExtractor::with_config(ExtractorConfig {
+            derive_site_name: false,
+            ..Default::default()
+        });
+        let preview = extractor.extract(html, "https://www.example.co.uk/page").unwrap();
+        assert!(preview.site_name.is_none());
+    }
+
+    #[test]
+    fn test_og_site_name_wins_over_derived_name() {
+        let html = r#"<html><head>
+            <meta property="og:site_name" content="The Example Times">
+        </head></html>"#;
+        let preview = MetadataExtractor::new()
+            .extract(html, "https://example.com/")
+            .unwrap();
+        assert_eq!(preview.site_name.as_deref(), Some("The Example Times"));
+    }
+
+    #[test]
+    fn test_text_fields_have_entities_decoded_and_whitespace_collapsed() {
+        let html = "<html><head>
+            <title>Tom &amp; Jerry&#39;s\n\t  Greatest   Hits</title>
+            <meta name=\"description\" content=\"Fish &amp;amp; Chips &amp;#8212;\n  open late\">
+            <meta property=\"og:site_name\" content=\"  The\tDiner &amp;amp; Co \">
+        </head></html>";
+        let preview = MetadataExtractor::new()
+            .extract(html, "https://example.com/")
+            .unwrap();
+        assert_eq!(
+            preview.title.as_deref(),
+            Some("Tom & Jerry's Greatest Hits")
+        );
+        assert_eq!(
+            preview.description.as_deref(),
+            Some("Fish & Chips — open late")
+        );
+        assert_eq!(preview.site_name.as_deref(), Some("The Diner & Co"));
+    }
+
+    #[test]
+    fn test_long_title_and_description_truncated_at_word_boundary() {
+        let html = format!(
+            r#"<html><head>
+            <title>A fairly long headline about previews</title>
+            <meta name="description" content="{}">
+        </head></html>"#,
+            "word ".repeat(400)
+        );
+        let extractor = MetadataExtractor::with_config(ExtractorConfig {
+            max_title_len: 24,
+            ..Default::default()
+        });
+        let preview = extractor.extract(&html, "https://example.com/").unwrap();
+        assert_eq!(preview.title.as_deref(), Some("A fairly long headline…"));
+        let description = preview.description.unwrap();
+        assert!(description.chars().count() <= 1000);
+        assert!(description.ends_with("word…"));
+
+        let preview = MetadataExtractor::new()
+            .extract("<title>Short</title>", "https://example.com/")
+            .unwrap();
+        assert_eq!(preview.title.as_deref(), Some("Short"));
+    }
+
+    #[test]
+    fn test_custom_title_selector_consulted_in_order() {
+        let html = r#"<html><head>
+            <meta name="parsely-title" content="Parsely Headline">
+            <meta name="sailthru.description" content="From Sailthru">
+        </head><body></body></html>"#;
+        let preview = MetadataExtractor::new()
+            .extract(html, "https://example.com/")
+            .unwrap();
+        assert!(preview.title.is_none());
+
+        let mut config = ExtractorConfig::default();
+        config
+            .title_selectors
+            .push("meta[name='parsely-title']".into());
+        config
+            .description_selectors
+            .insert(0, "meta[name='sailthru.description']".into());
+        config.image_selectors.push("not a [selector".into());
+        let preview = MetadataExtractor::with_config(config)
+            .extract(html, "https://example.com/")
+            .unwrap();
+        assert_eq!(preview.title.as_deref(), Some("Parsely Headline"));
+        assert_eq!(preview.description.as_deref(), Some("From Sailthru"));
+        assert!(preview.image_url.is_none());
+    }
+
+    #[test]
+    fn test_og_audio_resolved_against_page_url() {
+        let html = r#"<html><head>
+            <meta property="og:audio" content="/episodes/42.mp3">
+            <meta property="og:audio:type" content="audio/mpeg">
+        </head></html>"#;
+        let preview = MetadataExtractor::new()
+            .extract(html, "https://podcast.example.com/show/42")
+            .unwrap();
+        assert_eq!(
+            preview.audio_url.as_deref(),
+            Some("https://podcast.example.com/episodes/42.mp3")
+        );
+        assert_eq!(preview.audio_type.as_deref(), Some("audio/mpeg"));
+    }
+
+    #[test]
+    fn test_audio_enclosure_link() {
+        let html = r#"<html><head>
+            <link rel="alternate" type="application/rss+xml" href="/feed.xml">
+            <link rel="alternate" type="audio/ogg" href="media/ep1.ogg">
+        </head></html>"#;
+        let preview = MetadataExtractor::new()
+            .extract(html, "https://example.com/show/")
+            .unwrap();
+        assert_eq!(
+            preview.audio_url.as_deref(),
+            Some("https://example.com/show/media/ep1.ogg")
+        );
+        assert_eq!(preview.audio_type.as_deref(), Some("audio/ogg"));
+
+        let preview = MetadataExtractor::new()
+            .extract("<html></html>", "https://example.com/")
+            .unwrap();
+        assert!(preview.audio_url.is_none());
+        assert!(preview.audio_type.is_none());
+    }
+
+    #[test]
+    fn test_spotify_track_embed() {
+        // Trimmed from https://open.spotify.com/track/4cOdK2wGLETKBW3PvgPWqT
+        let html = r#"<html><head>
+            <title>Never Gonna Give You Up - song by Rick Astley | Spotify</title>
+            <meta property="og:title" content="Never Gonna Give You Up">
+            <meta property="og:type" content="music.song">
+            <meta property="og:image" content="https://i.scdn.co/image/ab67616d0000b27315ebbedaacef61af244262a8">
+            <meta property="og:audio" content="https://p.scdn.co/mp3-preview/b4c682084c3fd05538726d0a126b7e14b6e92c83">
+            <meta property="og:audio:type" content="audio/mpeg">
+            <meta property="music:duration" content="213">
+            <meta name="twitter:card" content="summary">
+            <meta name="twitter:player" content="https://open.spotify.com/embed/track/4cOdK2wGLETKBW3PvgPWqT">
+        </head></html>"#;
+        let preview = MetadataExtractor::new()
+            .extract(
+                html,
+                "https://open.spotify.com/track/4cOdK2wGLETKBW3PvgPWqT",
+            )
+            .unwrap();
+
+        assert_eq!(
+            preview.embed_url.as_deref(),
+            Some("https://open.spotify.com/embed/track/4cOdK2wGLETKBW3PvgPWqT")
+        );
+        assert_eq!(preview.media_type.as_deref(), Some("music.song"));
+        assert_eq!(
+            preview.audio_url.as_deref(),
+            Some("https://p.scdn.co/mp3-preview/b4c682084c3fd05538726d0a126b7e14b6e92c83")
+        );
+    }
+
+    #[test]
+    fn test_youtube_watch_page_embed() {
+        // Trimmed from https://www.youtube.com/watch?v=5C_HPTJg5ek
+        let html = r#"<html><head>
+            <meta property="og:title" content="Rust in 100 Seconds">
+            <meta property="og:type" content="video.other">
+            <meta property="og:video:url" content="https://www.youtube.com/embed/5C_HPTJg5ek">
+            <meta property="og:video:secure_url" content="https://www.youtube.com/embed/5C_HPTJg5ek">
+            <meta property="og:video:type" content="text/html">
+            <meta name="twitter:player" content="https://www.youtube.com/embed/5C_HPTJg5ek?start=0">
+        </head></html>"#;
+        let preview = MetadataExtractor::new()
+            .extract(html, "https://www.youtube.com/watch?v=5C_HPTJg5ek")
+            .unwrap();
+
+        assert_eq!(
+            preview.embed_url.as_deref(),
+            Some("https://www.youtube.com/embed/5C_HPTJg5ek")
+        );
+        assert_eq!(preview.media_type.as_deref(), Some("video.other"));
+
+        let article = r#"<meta property="og:type" content="article">"#;
+        let preview = MetadataExtractor::new()
+            .extract(article, "https://example.com/")
+            .unwrap();
+        assert_eq!(preview.embed_url, None);
+        assert_eq!(preview.media_type, None);
+    }
+
+    #[test]
+    fn test_facebook_video_info() {
+        let html = r#"<html><head>
+            <meta property="og:type" content="video.movie">
+            <meta property="og:title" content="Launch day">
+            <meta property="og:video" content="http://video.example.com/v/1234.mp4">
+            <meta property="og:video:secure_url" content="/v/1234.mp4">
+            <meta property="og:video:type" content="video/mp4">
+            <meta property="og:video:width" content="1280">
+            <meta property="og:video:height" content="720">
+            <meta property="video:duration" content="95">
+        </head></html>"#;
+        let preview = MetadataExtractor::new()
+            .extract(html, "https://www.example.com/watch/?v=1234")
+            .unwrap();
+
+        assert_eq!(
+            preview.video,
+            Some(VideoInfo {
+                url: "https://www.example.com/v/1234.mp4".into(),
+                width: Some(1280),
+                height: Some(720),
+                duration: Some(95),
+                mime: Some("video/mp4".into()),
+            })
+        );
+
+        let no_video = r#"<meta property="og:video:width" content="1280">"#;
+        let preview = MetadataExtractor::new()
+            .extract(no_video, "https://example.com/")
+            .unwrap();
+        assert_eq!(preview.video, None);
+    }
+
+    #[test]
+    fn test_strip_site_suffix_from_title() {
+        let html = r#"<html><head>
+            <title>Article Headline | Example</title>
+            <meta property="og:site_name" content="Example">
+        </head></html>"#;
+        let url = "https://example.com/a";
+
+        let preview = MetadataExtractor::new().extract(html, url).unwrap();
+        assert_eq!(preview.title.as_deref(), Some("Article Headline | Example"));
+
+        let extractor = MetadataExtractor::with_config(ExtractorConfig {
+            strip_site_suffix_from_title: true,
+            ..Default::default()
+        });
+        let preview = extractor.extract(html, url).unwrap();
+        assert_eq!(preview.title.as_deref(), Some("Article Headline"));
+
+        assert_eq!(strip_site_suffix("News - Example".into(), "Example"), "News");
+        assert_eq!(strip_site_suffix("Counter-Example".into(), "Example"), "Counter-Example");
+        assert_eq!(strip_site_suffix("Example".into(), "Example"), "Example");
+        assert_eq!(strip_site_suffix("Why Example | Examples".into(), "Example"), "Why Example | Examples");
+    }
+
+    #[test]
+    fn test_robots_meta_is_ignored_by_default() {
+        let html = r#"<html><head>
+            <meta name="robots" content="noindex, nosnippet">
+            <meta property="og:description" content="Hidden">
+        </head></html>"#;
+        let preview = MetadataExtractor::new()
+            .extract(html, "https://example.com/")
+            .unwrap();
+        assert_eq!(preview.description.as_deref(), Some("Hidden"));
+    }
+
+    #[test]
+    fn test_respect_robots_meta() {
+        let extractor = MetadataExtractor::with_config(ExtractorConfig {
+            respect_robots_meta: true,
+            ..Default::default()
+        });
+
+        let html = r#"<html><head>
+            <meta name="ROBOTS" content="index, NoSnippet">
+            <meta property="og:title" content="Title">
+            <meta property="og:description" content="Hidden">
+            <meta property="og:image" content="https://example.com/a.png">
+        </head></html>"#;
+        let preview = extractor.extract(html, "https://example.com/").unwrap();
+        assert_eq!(preview.title.as_deref(), Some("Title"));
+        assert!(preview.description.is_none());
+        assert!(preview.image_url.is_none());
+        assert!(preview.og_images.is_empty());
+
+        let html = r#"<html><head><meta name="robots" content="noindex"></head></html>"#;
+        assert!(matches!(
+            extractor.extract(html, "https://example.com/private"),
+            Err(PreviewError::PreviewNotAllowed(url)) if url == "https://example.com/private"
+        ));
+    }
+
+    #[test]
+    fn test_multiple_article_authors() {
+        let html = r#"<html><head>
+            <meta property="article:author" content="Ada Lovelace">
+            <meta property="article:author" content="Charles Babbage">
+            <meta property="article:author" content="Ada Lovelace">
+            <meta name="author" content="Editorial Team">
+        </head></html>"#;
+        let preview = MetadataExtractor::new()
+            .extract(html, "https://example.com/")
+            .unwrap();
+        assert_eq!(preview.authors, vec!["Ada Lovelace", "Charles Babbage"]);
+        assert_eq!(preview.author.as_deref(), Some("Ada Lovelace"));
+    }
+
+    #[test]
+    fn test_json_ld_author_array() {
+        let html = r#"<html><head>
+            <meta name="author" content="Fallback">
+            <script type="application/ld+json">
+            {"@context": "https://schema.org", "@graph": [
+                {"@type": "WebSite", "name": "Example"},
+                {"@type": "NewsArticle", "author": [
+                    {"@type": "Person", "name": "Grace Hopper"},
+                    "Alan Turing"
+                ]}
+            ]}
+            </script>
+        </head></html>"#;
+        let preview = MetadataExtractor::new()
+            .extract(html, "https://example.com/")
+            .unwrap();
+        assert_eq!(preview.authors, vec!["Grace Hopper", "Alan Turing"]);
+        assert_eq!(preview.author.as_deref(), Some("Grace Hopper"));
+
+        let preview = MetadataExtractor::new()
+            .extract("<html></html>", "https://example.com/")
+            .unwrap();
+        assert!(preview.author.is_none());
+        assert!(preview.authors.is_empty());
+    }
+
+    #[test]
+    fn test_article_dates_from_og_article_tags() {
+        let html = r#"<html><head>
+            <meta property="og:type" content="article">
+            <meta property="article:author" content="Ada Lovelace">
+            <meta property="article:published_time" content="2024-03-01T09:00:00Z">
+            <meta property="article:modified_time" content="2024-03-02T10:30:00Z">
+            <script type="application/ld+json">
+            {"@type": "NewsArticle", "datePublished": "2000-01-01", "dateModified": "2000-01-02"}
+            </script>
+        </head></html>"#;
+        let preview = MetadataExtractor::new()
+            .extract(html, "https://example.com/")
+            .unwrap();
+        assert_eq!(preview.author.as_deref(), Some("Ada Lovelace"));
+        assert_eq!(preview.published_at.as_deref(), Some("2024-03-01T09:00:00Z"));
+        assert_eq!(preview.modified_at.as_deref(), Some("2024-03-02T10:30:00Z"));
+    }
+
+    #[test]
+    fn test_article_dates_fall_back_to_json_ld() {
+        let html = r#"<html><head>
+            <script type="application/ld+json">
+            {"@context": "https://schema.org", "@graph": [
+                {"@type": "WebSite", "name": "Example"},
+                {"@type": "BlogPosting", "datePublished": "2024-05-06", "dateModified": "2024-05-07"}
+            ]}
+            </script>
+        </head></html>"#;
+        let preview = MetadataExtractor::new()
+            .extract(html, "https://example.com/")
+            .unwrap();
+        assert_eq!(preview.published_at.as_deref(), Some("2024-05-06"));
+        assert_eq!(preview.modified_at.as_deref(), Some("2024-05-07"));
+
+        let preview = MetadataExtractor::new()
+            .extract("<html></html>", "https://example.com/")
+            .unwrap();
+        assert!(preview.published_at.is_none());
+        assert!(preview.modified_at.is_none());
+    }
+
+    #[test]
+    fn test_keywords_merged_and_deduplicated() {
+        let html = r#"<html><head>
+            <meta name="keywords" content="Rust, async ,web, ">
+            <meta property="article:tag" content="rust">
+            <meta property="article:tag" content="Tokio">
+            <meta property="article:tag" content="WEB">
+            <script type="application/ld+json">
+            {"@type": "Article", "keywords": ["tokio", "Networking"]}
+            </script>
+        </head></html>"#;
+        let preview = MetadataExtractor::new()
+            .extract(html, "https://example.com/")
+            .unwrap();
+        assert_eq!(
+            preview.keywords,
+            vec!["Rust", "async", "web", "Tokio", "Networking"]
+        );
+
+        let html = r#"<script type="application/ld+json">
+            {"@type": "Article", "keywords": "one, two"}
+        </script>"#;
+        let preview = MetadataExtractor::new()
+            .extract(html, "https://example.com/")
+            .unwrap();
+        assert_eq!(preview.keywords, vec!["one", "two"]);
+    }
+
+    #[test]
+    fn test_language_sources_in_priority_order() {
+        let language = |html: &str| {
+            MetadataExtractor::new()
+                .extract(html, "https://example.com/")
+                .unwrap()
+                .language
+        };
+
+        assert_eq!(
+            language(
+                r#"<html lang="fr"><head><meta property="og:locale" content="en_US"></head></html>"#
+            )
+            .as_deref(),
+            Some("en-us")
+        );
+        assert_eq!(
+            language(r#"<html lang="pt-BR"><head></head></html>"#).as_deref(),
+            Some("pt-br")
+        );
+        assert_eq!(
+            language(
+                r#"<html><head><meta http-equiv="Content-Language" content="de-AT, en"></head></html>"#
+            )
+            .as_deref(),
+            Some("de-at")
+        );
+        assert_eq!(language("<html><head></head></html>"), None);
+    }
+
+    #[test]
+    fn test_all_declared_images_collected() {
+        let html = r#"<html><head>
+            <meta property="og:image" content="/img/wide.png">
+            <meta property="og:image:width" content="1200">
+            <meta property="og:image:height" content="630">
+            <meta property="og:image" content="https://cdn.example.com/square.png">
+            <meta property="og:image:width" content="600">
+            <meta property="og:image:height" content="600">
+            <meta name="twitter:image" content="https://cdn.example.com/square.png">
+            <meta property="og:image" content="https://cdn.example.com/tall.png">
+            <meta name="twitter:image:src" content="https://cdn.example.com/card.png">
+        </head></html>"#;
+        let preview = MetadataExtractor::new()
+            .extract(html, "https://example.com/post")
+            .unwrap();
+
+        assert_eq!(
+            preview.images,
+            vec![
+                "https://example.com/img/wide.png",
+                "https://cdn.example.com/square.png",
+                "https://cdn.example.com/tall.png",
+                "https://cdn.example.com/card.png",
+            ]
+        );
+        assert_eq!(preview.image_url.as_deref(), Some("https://example.com/img/wide.png"));
+        let dimensions: Vec<_> = preview
+            .og_images
+            .iter()
+            .map(|image| (image.width, image.height))
+            .collect();
+        assert_eq!(
+            dimensions,
+            vec![(Some(1200), Some(630)), (Some(600), Some(600)), (None, None)]
+        );
+    }
+
+    #[test]
+    fn test_theme_color() {
+        let theme_color = |html: &str| {
+            MetadataExtractor::new()
+                .extract(html, "https://example.com/")
+                .unwrap()
+                .theme_color
+        };
+
+        assert_eq!(
+            theme_color(r##"<meta name="theme-color" content="#4285F4">"##).as_deref(),
+            Some("#4285f4")
+        );
+        assert_eq!(
+            theme_color(
+                r##"<meta name="theme-color" media="(prefers-color-scheme: light)" content="#ffffff">
+                <meta name="theme-color" media="(prefers-color-scheme: dark)" content="#000000">
+                <meta name="theme-color" content="#F0A">"##
+            )
+            .as_deref(),
+            Some("#ff00aa")
+        );
+        assert_eq!(
+            theme_color(
+                r##"<meta name="theme-color" content="rebeccapurple">
+                <meta name="theme-color" media="(prefers-color-scheme: dark)" content="#123456">"##
+            )
+            .as_deref(),
+            Some("#123456")
+        );
+        assert_eq!(theme_color(r##"<meta name="theme-color" content="#12345">"##), None);
+    }
+
+    #[test]
+    fn test_extract_all_returns_every_declared_field() {
+        let html = r#"<html><head>
+            <title> Launch day | Example News </title>
+            <base href="https://example.com/news/">
+            <meta name="description" content="Plain description">
+            <meta property="og:title" content="Launch day">
+            <meta property="og:image" content="https://example.com/a.png">
+            <meta property="og:image" content="https://example.com/b.png">
+            <meta name="twitter:card" content="summary_large_image">
+            <meta property="twitter:site" content="@example">
+            <meta property="article:author" content="Ada Lovelace">
+            <link rel="canonical" href="launch-day">
+            <script type="application/ld+json">
+            {"@type": "NewsArticle", "datePublished": "2024-03-01T09:00:00Z"}
+            </script>
+        </head></html>"#;
+        let metadata = MetadataExtractor::new().extract_all(html, "https://example.com/x?ref=1");
+
+        let pair = |key: &str, value: &str| (key.to_string(), value.to_string());
+        assert_eq!(
+            metadata.open_graph,
+            vec![
+                pair("og:title", "Launch day"),
+                pair("og:image", "https://example.com/a.png"),
+                pair("og:image", "https://example.com/b.png"),
+            ]
+        );
+        assert_eq!(
+            metadata.twitter,
+            vec![
+                pair("twitter:card", "summary_large_image"),
+                pair("twitter:site", "@example"),
+            ]
+        );
+        assert_eq!(metadata.title.as_deref(), Some("Launch day | Example News"));
+        assert_eq!(metadata.meta_description.as_deref(), Some("Plain description"));
+        assert_eq!(
+            metadata.canonical_url.as_deref(),
+            Some("https://example.com/news/launch-day")
+        );
+        assert_eq!(metadata.authors, vec!["Ada Lovelace"]);
+        assert_eq!(metadata.published_time.as_deref(), Some("2024-03-01T09:00:00Z"));
+
+        let empty = MetadataExtractor::new().extract_all("<html></html>", "not a url");
+        assert_eq!(empty, ExtractedMetadata::default());
+    }
+
+    #[test]
+    fn test_extract_links() {
+        let html = r##"<html><head><title>Links</title></head><body>
+            <nav><a href="/docs/">Docs</a> <a href="#top">Top</a></nav>
+            <p>See <a href="guide.html">the
+                <em>guide</em></a>, our <a href="https://blog.example.org/post">blog</a>
+            and the <a href="//cdn.example.net/lib.js">CDN</a>.</p>
+            <a href="https://spam.example.com/" rel=" nofollow ">Sponsored</a>
+            <a href="https://example.com/docs/">Docs again</a>
+            <a href="mailto:team@example.com">Mail us</a>
+            <a href="javascript:void(0)">Menu</a>
+        </body></html>"##;
+        let links = MetadataExtractor::new().extract_links(html, "https://example.com/articles/1");
+
+        let link = |href: &str, text: &str, rel: Option<&str>| LinkInfo {
+            href: href.to_string(),
+            text: text.to_string(),
+            rel: rel.map(String::from),
+        };
+        assert_eq!(
+            links,
+            vec![
+                link("https://example.com/docs/", "Docs", None),
+                link("https://example.com/articles/1#top", "Top", None),
+                link("https://example.com/articles/guide.html", "the guide", None),
+                link("https://blog.example.org/post", "blog", None),
+                link("https://cdn.example.net/lib.js", "CDN", None),
+                link("https://spam.example.com/", "Sponsored", Some("nofollow")),
+            ]
+        );
+
+        assert!(MetadataExtractor::new()
+            .extract_links(html, "not a url")
+            .is_empty());
+    }
+
+    #[test]
+    fn test_favicon_prefers_icon_rel_and_largest_size() {
+        let html = r#"<html><head>
+            <base href="https://cdn.example.com/static/">
+            <link rel="apple-touch-icon" sizes="180x180" href="/apple.png">
+            <link rel="shortcut icon" href="/favicon.ico">
+            <link rel="icon" type="image/png" sizes="16x16" href="icons/16.png">
+            <link rel="icon" type="image/png" sizes="32x32 64x64" href="icons/64.png">
+            <link rel="icon" type="image/png" sizes="48x48" href="icons/48.png">
+        </head></html>"#;
+        let preview = MetadataExtractor::new()
+            .extract(html, "https://example.com/post")
+            .unwrap();
+        assert_eq!(
+            preview.favicon.as_deref(),
+            Some("https://cdn.example.com/static/icons/64.png")
+        );
+    }
+
+    #[test]
+    fn test_favicon_falls_back_through_rel_kinds() {
+        let html = r#"<html><head>
+            <link rel="apple-touch-icon" href="/apple-touch-icon.png">
+            <link rel="Shortcut Icon" href="/legacy.ico">
+        </head></html>"#;
+        let preview = MetadataExtractor::new()
+            .extract(html, "https://example.com/a/b")
+            .unwrap();
+        assert_eq!(
+            preview.favicon.as_deref(),
+            Some("https://example.com/legacy.ico")
+        );
+
+        let html = r#"<html><head>
+            <link rel="apple-touch-icon" href="touch.png">
+        </head></html>"#;
+        let preview = MetadataExtractor::new()
+            .extract(html, "https://example.com/a/b")
+            .unwrap();
+        assert_eq!(
+            preview.favicon.as_deref(),
+            Some("https://example.com/a/touch.png")
+        );
+    }
+
+    #[test]
+    fn test_favicon_defaults_to_site_root() {
+        let preview = MetadataExtractor::new()
+            .extract("<html><head></head></html>", "https://example.com/deep/page?x=1")
+            .unwrap();
+        assert_eq!(
+            preview.favicon.as_deref(),
+            Some("https://example.com/favicon.ico")
+        );
+    }
+
+    #[test]
+    fn test_icon_size() {
+        assert_eq!(icon_size("16x16"), 16);
+        assert_eq!(icon_size("16x16 192X192"), 192);
+        assert_eq!(icon_size("any"), u32::MAX);
+        assert_eq!(icon_size("bogus"), 0);
+    }
+
+    #[test]
+    fn test_missing_canonical_is_none() {
+        let html = "<html><head><title>No canonical</title></head></html>";
+        let preview = MetadataExtractor::new()
+            .extract(html, "https://example.com/")
+            .unwrap();
+        assert!(preview.canonical_url.is_none());
+    }
+
+    #[test]
+    fn test_youtube_oembed_preview() {
+        // Saved response from https://www.youtube.com/oembed?url=...&format=json
+        let oembed: OEmbedResponse = serde_json::from_str(
+            r#"{
+                "title": "Rust in 100 Seconds",
+                "author_name": "Fireship",
+                "author_url": "https://www.youtube.com/@Fireship",
+                "type": "video",
+                "height": 113,
+                "width": 200,
+                "version": "1.0",
+                "provider_name": "YouTube",
+                "provider_url": "https://www.youtube.com/",
+                "thumbnail_height": 360,
+                "thumbnail_width": 480,
+                "thumbnail_url": "https://i.ytimg.com/vi/5C_HPTJg5ek/hqdefault.jpg",
+                "html": "<iframe width=\"200\" height=\"113\" src=\"https://www.youtube.com/embed/5C_HPTJg5ek?feature=oembed\"></iframe>"
+            }"#,
+        )
+        .unwrap();
+
+        let preview = MetadataExtractor::new().extract_from_youtube_oembed(&oembed);
+        assert_eq!(preview.title.as_deref(), Some("Rust in 100 Seconds"));
+        assert_eq!(preview.site_name.as_deref(), Some("Fireship"));
+        assert_eq!(
+            preview.image_url.as_deref(),
+            Some("https://i.ytimg.com/vi/5C_HPTJg5ek/hqdefault.jpg")
+        );
+    }
+
+    #[test]
+    fn test_vimeo_oembed_preview() {
+        // Saved response from https://vimeo.com/api/oembed.json?url=https://vimeo.com/76979871
+        let path = format!(
+            "{}/tests/fixtures/vimeo/oembed.json",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        let oembed: OEmbedResponse =
+            serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+
+        let preview = MetadataExtractor::new().extract_from_vimeo_oembed(&oembed);
+        assert_eq!(
+            preview.title.as_deref(),
+            Some("The New Vimeo Player (You Know, For Videos)")
+        );
+        assert_eq!(preview.site_name.as_deref(), Some("Vimeo Staff"));
+        assert_eq!(preview.author.as_deref(), Some("Vimeo Staff"));
+        assert!(preview
+            .image_url
+            .as_deref()
+            .is_some_and(|image| image.starts_with("https://i.vimeocdn.com/video/452001751-")));
+        assert!(preview
+            .description
+            .as_deref()
+            .is_some_and(|description| description.starts_with("It may look (mostly) the same")));
     }
 }
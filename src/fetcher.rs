@@ -1,12 +1,35 @@
-use super::is_twitter_url;
+use super::{is_twitter_url, is_vimeo_url, is_youtube_url};
 #[cfg(feature = "github")]
-use crate::github_types::{GitHubBasicPreview, GitHubDetailedInfo, GitHubRepository};
+use crate::github_types::{
+    readme_summary, GitHubBasicPreview, GitHubDetailedInfo, GitHubRepository,
+};
+#[cfg(feature = "gitlab")]
+use crate::gitlab_types::{gitlab_api_url, GitLabProject};
+#[cfg(feature = "mastodon")]
+use crate::mastodon_types::{
+    is_mastodon_compatible, nodeinfo_url, parse_mastodon_status_url, MastodonStatus,
+};
+#[cfg(feature = "reddit")]
+use crate::reddit_types::{reddit_json_url, RedditPost};
+use crate::utils;
+use crate::error::RedirectLimitExceeded;
+use crate::robots::RobotsChecker;
+use crate::security::CheckedResolver;
 use crate::{ContentLimits, PreviewError, UrlValidationConfig, UrlValidator};
-#[cfg(any(feature = "twitter", feature = "github"))]
-use reqwest::header::HeaderMap;
-use reqwest::{Client, Response};
+use futures::StreamExt;
+use reqwest::header::{HeaderMap, HeaderValue, ACCEPT_LANGUAGE, ETAG, LAST_MODIFIED};
+use reqwest::header::{CONTENT_LENGTH, CONTENT_RANGE, CONTENT_TYPE, RANGE, RETRY_AFTER, USER_AGENT};
+use reqwest::header::{IF_MODIFIED_SINCE, IF_NONE_MATCH};
+use reqwest::redirect::Policy;
+use reqwest::{Client, RequestBuilder, Response};
 use scraper::{Html, Selector};
 use serde::Deserialize;
+use std::collections::hash_map::RandomState;
+use std::collections::{HashMap, HashSet};
+use std::hash::{BuildHasher, Hasher};
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::time::timeout;
 #[cfg(feature = "logging")]
@@ -21,6 +44,14 @@ pub struct OEmbedResponse {
     pub author_url: String,
     pub provider_name: String,
     pub provider_url: String,
+    /// Set by rich/video providers such as YouTube
+    #[serde(default)]
+    pub title: Option<String>,
+    /// Set by some video providers, e.g. Vimeo
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub thumbnail_url: Option<String>,
 }
 
 #[derive(Clone)]
@@ -28,29 +59,426 @@ pub struct Fetcher {
     client: Client,
     url_validator: UrlValidator,
     content_limits: ContentLimits,
+    user_agents: Arc<Vec<String>>,
+    user_agent_cursor: Arc<AtomicUsize>,
+    user_agent_overrides: Arc<HashMap<String, String>>,
+    success_statuses: Option<HashSet<u16>>,
+    /// Present when `respect_robots` is enabled
+    robots: Option<RobotsChecker>,
+    retry: RetryConfig,
+    head_precheck: bool,
+    detect_soft_404: bool,
+    capture_http_metadata: bool,
+    /// Resolve and check target hosts before requesting them, set when
+    /// `resolve_and_check` is combined with a proxy
+    check_target_dns: bool,
 }
 
 #[derive(Debug, Clone)]
 pub enum FetchResult {
     Html(String),
     OEmbed(OEmbedResponse),
+    /// The URL points directly at an image
+    Image(ImageInfo),
+    /// The URL points at a PDF document (with the `pdf` feature)
+    Pdf(PdfInfo),
+    /// The page is unchanged since the response described by the validators sent
+    /// with [`Fetcher::revalidate`]; no body was read
+    NotModified,
+}
+
+/// What is known about an image response without downloading it in full
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImageInfo {
+    /// Declared MIME type, e.g. `image/png`
+    pub content_type: String,
+    /// Read from the image header with the `image-dims` feature
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+}
+
+/// Entries of a PDF's document information dictionary
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PdfInfo {
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub subject: Option<String>,
+}
+
+/// Caching headers of a page response, see [`FetcherConfig::capture_http_metadata`]
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct HttpMeta {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub content_type: Option<String>,
+}
+
+impl HttpMeta {
+    fn from_headers(headers: &HeaderMap) -> Self {
+        let header = |name| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(String::from)
+        };
+        Self {
+            etag: header(ETAG),
+            last_modified: header(LAST_MODIFIED),
+            content_type: header(CONTENT_TYPE),
+        }
+    }
+
+    /// Whether the page can be revalidated with a conditional request
+    pub fn has_validators(&self) -> bool {
+        self.etag.is_some() || self.last_modified.is_some()
+    }
+
+    /// Overwrite fields with those a later response (e.g. a 304) sent again,
+    /// so rotated validators replace the cached ones
+    #[cfg(feature = "cache")]
+    pub(crate) fn update(&mut self, newer: HttpMeta) {
+        if newer.etag.is_some() {
+            self.etag = newer.etag;
+        }
+        if newer.last_modified.is_some() {
+            self.last_modified = newer.last_modified;
+        }
+        if newer.content_type.is_some() {
+            self.content_type = newer.content_type;
+        }
+    }
+
+    fn conditional_headers(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        let validators = [
+            (IF_NONE_MATCH, &self.etag),
+            (IF_MODIFIED_SINCE, &self.last_modified),
+        ];
+        for (name, value) in validators {
+            if let Some(value) = value.as_deref().and_then(|v| HeaderValue::from_str(v).ok()) {
+                headers.insert(name, value);
+            }
+        }
+        headers
+    }
+}
+
+/// A [`FetchResult`] together with details of the HTTP response it came from
+#[derive(Debug, Clone)]
+pub struct FetchResponse {
+    pub result: FetchResult,
+    /// HTTP status code of the response
+    pub status: u16,
+    /// URL of the response after any redirects
+    pub final_url: String,
+    /// Set for pages fetched with `capture_http_metadata` enabled
+    pub http_metadata: Option<HttpMeta>,
+}
+
+/// An unprocessed HTTP response returned by [`Fetcher::fetch_raw`]
+#[derive(Debug, Clone)]
+pub struct RawResponse {
+    /// HTTP status code; non-success statuses are returned rather than turned into errors
+    pub status: u16,
+    pub headers: HeaderMap,
+    /// Response body exactly as received, without charset decoding
+    pub body: Vec<u8>,
+    /// URL of the response after any redirects
+    pub final_url: String,
+}
+
+/// Result of a lightweight HEAD/ranged-GET probe
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProbeResult {
+    pub content_type: Option<String>,
+    pub content_length: Option<u64>,
+    pub likely_html: bool,
 }
 
 #[derive(Debug, Clone)]
 pub struct FetcherConfig {
+    /// Limit for a whole request, from connecting until the body is read
     pub timeout: Duration,
+    /// Limit for establishing a connection (default: 5s). Failures are reported as
+    /// `PreviewError::ConnectionError` rather than `TimeoutError`.
+    pub connect_timeout: Duration,
     pub user_agent: String,
+    /// User-Agent pool rotated round-robin per request (empty uses `user_agent`)
+    pub user_agents: Vec<String>,
+    /// User-Agent per host suffix, e.g. `"twitter.com"` also matches `mobile.twitter.com`.
+    /// A matching override takes precedence over `user_agents` and `user_agent`;
+    /// the longest matching suffix wins.
+    pub user_agent_overrides: HashMap<String, String>,
+    /// `Accept-Language` sent with every request, e.g. `"fr-FR,fr;q=0.9"`
+    /// (default: none). Must be a valid header value, see [`Fetcher::try_with_config`].
+    pub accept_language: Option<String>,
     pub url_validation: UrlValidationConfig,
     pub content_limits: ContentLimits,
+    /// Status codes treated as success (`None` uses the standard 2xx check)
+    pub success_statuses: Option<HashSet<u16>>,
+    /// Whether redirects are followed at all (default: true)
+    pub follow_redirects: bool,
+    /// Maximum number of redirects followed before giving up (default: 10)
+    pub max_redirects: usize,
+    /// Fetch and honor each origin's `/robots.txt` for `user_agent` before fetching
+    /// a page (default: false). Rules are cached per origin.
+    pub respect_robots: bool,
+    /// Retries for page fetches that fail with a 5xx, timeout or connection error
+    pub retry: RetryConfig,
+    /// Send a HEAD request before each page GET and fail early when its declared
+    /// content type or length is outside `content_limits` (default: false).
+    /// Servers that reject HEAD are fetched with GET as usual.
+    pub head_precheck: bool,
+    /// Report pages served with a success status but titled like a "not found" page,
+    /// e.g. "404 Not Found" or "Page Not Found | Example", as `PreviewError::NotFound`
+    /// (default: false). Only the title is checked.
+    pub detect_soft_404: bool,
+    /// Record the `ETag`, `Last-Modified` and `Content-Type` headers of each page in
+    /// `Preview::http_metadata` (default: false). Expired cached previews that carry
+    /// these validators are revalidated with a conditional request while the cache
+    /// still holds them, see `CacheConfig::stale_retention`.
+    pub capture_http_metadata: bool,
+    /// Send every request through this proxy (default: none). URL validation still
+    /// applies to the target URL, never to the proxy address.
+    pub proxy: Option<ProxyConfig>,
+    /// Hosts pinned to fixed addresses, bypassing DNS (and `resolve_and_check`).
+    /// The port of each address is ignored; requests use the URL's port.
+    pub resolved_hosts: Vec<(String, SocketAddr)>,
+}
+
+impl FetcherConfig {
+    /// Pins `host` to `addr`, e.g. an address returned by
+    /// [`UrlValidator::resolve_checked`], so the validated address is the one
+    /// connected to
+    pub fn with_resolved_host(mut self, host: impl Into<String>, addr: SocketAddr) -> Self {
+        self.resolved_hosts.push((host.into(), addr));
+        self
+    }
+}
+
+/// An HTTP or SOCKS5 proxy for [`FetcherConfig::proxy`]
+#[derive(Debug, Clone)]
+pub struct ProxyConfig {
+    /// Proxy URL, e.g. `http://proxy.corp:3128` or `socks5h://127.0.0.1:1080`
+    pub url: String,
+    /// Username and password to authenticate with
+    pub credentials: Option<(String, String)>,
+}
+
+impl ProxyConfig {
+    /// HTTP proxy at `addr`, given as `host:port` or a full URL
+    pub fn http(addr: &str) -> Self {
+        Self::with_scheme("http", addr)
+    }
+
+    /// SOCKS5 proxy at `addr`, given as `host:port` or a full URL. Host names are
+    /// resolved by the proxy.
+    pub fn socks5(addr: &str) -> Self {
+        Self::with_scheme("socks5h", addr)
+    }
+
+    fn with_scheme(scheme: &str, addr: &str) -> Self {
+        let url = if addr.contains("://") {
+            addr.to_string()
+        } else {
+            format!("{scheme}://{addr}")
+        };
+        Self {
+            url,
+            credentials: None,
+        }
+    }
+
+    pub fn with_credentials(mut self, username: &str, password: &str) -> Self {
+        self.credentials = Some((username.to_string(), password.to_string()));
+        self
+    }
+
+    fn to_reqwest(&self) -> Result<reqwest::Proxy, PreviewError> {
+        let proxy = reqwest::Proxy::all(&self.url).map_err(|e| {
+            PreviewError::InvalidConfiguration(format!("Invalid proxy {:?}: {e}", self.url))
+        })?;
+        Ok(match &self.credentials {
+            // Basic auth joins the two with a colon, so the username cannot contain one
+            Some((username, _)) if username.contains(':') => {
+                return Err(PreviewError::InvalidConfiguration(format!(
+                    "Invalid proxy username {username:?}"
+                )))
+            }
+            Some((username, password)) => proxy.basic_auth(username, password),
+            None => proxy,
+        })
+    }
+}
+
+/// Exponential backoff for transient page fetch failures
+#[derive(Debug, Clone)]
+pub struct RetryConfig {
+    /// Retries after the first attempt; 0 disables retrying
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled for each later one
+    pub initial_delay: Duration,
+    /// Upper bound for any delay, including one requested via `Retry-After`
+    /// (also applied by [`Fetcher::fetch_with_backoff`])
+    pub max_delay: Duration,
+    /// Randomize each backoff delay between half and all of its value
+    pub jitter: bool,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 2,
+            initial_delay: Duration::from_millis(250),
+            max_delay: Duration::from_secs(5),
+            jitter: true,
+        }
+    }
+}
+
+impl RetryConfig {
+    /// No retries: every failure is returned immediately
+    pub fn disabled() -> Self {
+        Self {
+            max_retries: 0,
+            ..Default::default()
+        }
+    }
+
+    /// Delay before retry number `retry` (0-based), preferring the server's `Retry-After`
+    fn delay(&self, retry: u32, retry_after: Option<Duration>) -> Duration {
+        if let Some(retry_after) = retry_after {
+            return retry_after.min(self.max_delay);
+        }
+        let backoff = self
+            .initial_delay
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_delay);
+        if self.jitter {
+            let fraction = RandomState::new().build_hasher().finish() as f64 / u64::MAX as f64;
+            backoff.mul_f64(0.5 + fraction / 2.0)
+        } else {
+            backoff
+        }
+    }
+}
+
+/// A failed fetch attempt, with the delay the server asked for via `Retry-After`
+struct AttemptError {
+    error: PreviewError,
+    retry_after: Option<Duration>,
+}
+
+impl AttemptError {
+    /// The error reported once no further retry will be made: rate-limit responses
+    /// (429, or 503 with `Retry-After`) become [`PreviewError::RateLimited`]
+    fn into_error(self) -> PreviewError {
+        match self.error {
+            PreviewError::ClientError { status: 429, .. } => PreviewError::RateLimited {
+                retry_after: self.retry_after,
+            },
+            PreviewError::ServerError { status: 503, .. } if self.retry_after.is_some() => {
+                PreviewError::RateLimited {
+                    retry_after: self.retry_after,
+                }
+            }
+            error => error,
+        }
+    }
+}
+
+impl From<PreviewError> for AttemptError {
+    fn from(error: PreviewError) -> Self {
+        Self {
+            error,
+            retry_after: None,
+        }
+    }
+}
+
+/// Bytes read from an image response when looking for its dimensions
+#[cfg(feature = "image-dims")]
+const IMAGE_HEADER_BYTES: usize = 64 * 1024;
+
+/// Declared MIME type of a response, lowercased and without parameters
+fn base_content_type(response: &Response) -> Option<String> {
+    let content_type = response.headers().get(CONTENT_TYPE)?.to_str().ok()?;
+    Some(content_type.split(';').next()?.trim().to_ascii_lowercase())
+}
+
+/// Base MIME type of a response that declares itself an image
+fn image_content_type(response: &Response) -> Option<String> {
+    base_content_type(response).filter(|base_type| base_type.starts_with("image/"))
+}
+
+/// PDFs are read for their document info rather than parsed as HTML, so they
+/// pass the content type check with the `pdf` feature
+fn is_pdf_type(base_type: &str) -> bool {
+    cfg!(feature = "pdf") && base_type.eq_ignore_ascii_case("application/pdf")
+}
+
+#[cfg(feature = "pdf")]
+fn is_pdf(response: &Response) -> bool {
+    base_content_type(response).is_some_and(|base_type| is_pdf_type(&base_type))
+}
+
+/// Decodes width and height from the leading bytes of an image, if its format is
+/// recognised and the header fits in them
+#[cfg(feature = "image-dims")]
+fn image_dimensions(header: &[u8]) -> Option<(u32, u32)> {
+    image::io::Reader::new(std::io::Cursor::new(header))
+        .with_guessed_format()
+        .ok()?
+        .into_dimensions()
+        .ok()
+}
+
+/// Accepts a body whose declared content type was not allowed only if it turns out
+/// to be HTML
+fn check_sniffed_type(unverified_type: Option<String>, body: &[u8]) -> Result<(), PreviewError> {
+    match unverified_type {
+        Some(declared) if !utils::looks_like_html(body) => {
+            Err(PreviewError::ContentTypeNotAllowed(declared))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Parses `Retry-After` as either delay-seconds or an HTTP date. Dates in the
+/// past yield a zero delay.
+fn parse_retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = httpdate::parse_http_date(value).ok()?;
+    Some(
+        date.duration_since(std::time::SystemTime::now())
+            .unwrap_or(Duration::ZERO),
+    )
 }
 
 impl Default for FetcherConfig {
     fn default() -> Self {
         Self {
             timeout: Duration::from_secs(10),
+            connect_timeout: Duration::from_secs(5),
             user_agent: "url_preview/0.1.0".to_string(),
+            user_agents: Vec::new(),
+            user_agent_overrides: HashMap::new(),
+            accept_language: None,
             url_validation: UrlValidationConfig::default(),
             content_limits: ContentLimits::default(),
+            success_statuses: None,
+            follow_redirects: true,
+            max_redirects: 10,
+            respect_robots: false,
+            retry: RetryConfig::default(),
+            head_precheck: false,
+            detect_soft_404: false,
+            capture_http_metadata: false,
+            proxy: None,
+            resolved_hosts: Vec::new(),
         }
     }
 }
@@ -61,31 +489,105 @@ impl Default for Fetcher {
     }
 }
 
+/// Follows up to `max_redirects` redirects, failing with [`RedirectLimitExceeded`]
+/// so [`PreviewError::from_reqwest_error`] can report the hop count
+pub(crate) fn redirect_policy(max_redirects: usize) -> Policy {
+    Policy::custom(move |attempt| {
+        let followed = attempt.previous().len();
+        if followed > max_redirects {
+            attempt.error(RedirectLimitExceeded {
+                count: max_redirects,
+            })
+        } else {
+            attempt.follow()
+        }
+    })
+}
+
 impl Fetcher {
     pub fn new() -> Self {
         Self::with_config(FetcherConfig::default())
     }
 
+    /// Builds a fetcher from `config`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the configuration is invalid; use
+    /// [`try_with_config`](Self::try_with_config) to get an error instead.
     pub fn with_config(config: FetcherConfig) -> Self {
-        let client = Client::builder()
+        Self::try_with_config(config).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Builds a fetcher from `config`, failing with
+    /// [`PreviewError::InvalidConfiguration`] if `accept_language` is not a valid
+    /// header value, the proxy URL or credentials are malformed, or the HTTP client
+    /// cannot be created
+    pub fn try_with_config(config: FetcherConfig) -> Result<Self, PreviewError> {
+        let redirect_policy = if config.follow_redirects {
+            redirect_policy(config.max_redirects)
+        } else {
+            Policy::none()
+        };
+
+        let url_validator = UrlValidator::new(config.url_validation);
+
+        let mut builder = Client::builder()
             .timeout(config.timeout)
+            .connect_timeout(config.connect_timeout)
             .user_agent(&config.user_agent)
-            .pool_max_idle_per_host(10)
-            .build()
-            .unwrap_or_else(|e| {
-                #[cfg(feature = "logging")]
-                error!(error = %e, "Failed to create HTTP client");
-                panic!("Failed to initialize HTTP client: {e}");
-            });
+            .redirect(redirect_policy)
+            .gzip(true)
+            .brotli(true)
+            .deflate(true)
+            .pool_max_idle_per_host(10);
+        if let Some(accept_language) = &config.accept_language {
+            let value = HeaderValue::from_str(accept_language).map_err(|e| {
+                PreviewError::InvalidConfiguration(format!(
+                    "Invalid accept_language {accept_language:?}: {e}"
+                ))
+            })?;
+            builder = builder.default_headers(HeaderMap::from_iter([(ACCEPT_LANGUAGE, value)]));
+        }
+        // Behind a proxy the client only ever resolves the proxy itself, so target
+        // addresses are checked before each request instead
+        let check_target_dns = config.proxy.is_some() && url_validator.resolves_and_checks();
+        if let Some(proxy) = &config.proxy {
+            builder = builder.proxy(proxy.to_reqwest()?);
+        } else if url_validator.resolves_and_checks() {
+            builder = builder.dns_resolver(Arc::new(CheckedResolver::new(url_validator.clone())));
+        }
+        for (host, addr) in &config.resolved_hosts {
+            builder = builder.resolve(host, *addr);
+        }
+        let client = builder.build().map_err(|e| {
+            #[cfg(feature = "logging")]
+            error!(error = %e, "Failed to create HTTP client");
+            PreviewError::InvalidConfiguration(format!("Failed to initialize HTTP client: {e}"))
+        })?;
 
         #[cfg(feature = "logging")]
         debug!("Fetcher initialized with custom configuration");
 
-        Fetcher {
+        let robots = config
+            .respect_robots
+            .then(|| RobotsChecker::new(&config.user_agent));
+
+        Ok(Fetcher {
             client,
-            url_validator: UrlValidator::new(config.url_validation),
+            url_validator,
             content_limits: config.content_limits,
-        }
+            user_agents: Arc::new(config.user_agents),
+            user_agent_cursor: Arc::new(AtomicUsize::new(0)),
+            user_agent_overrides: Arc::new(config.user_agent_overrides),
+            success_statuses: config.success_statuses,
+            robots,
+            retry: config.retry,
+            head_precheck: config.head_precheck,
+            detect_soft_404: config.detect_soft_404,
+            capture_http_metadata: config.capture_http_metadata,
+            check_target_dns,
+        })
     }
 
     pub fn new_with_custom_config(timeout: Duration, user_agent: &str) -> Self {
@@ -102,22 +604,126 @@ impl Fetcher {
             client,
             url_validator: UrlValidator::with_default_config(),
             content_limits: ContentLimits::default(),
+            user_agents: Arc::new(Vec::new()),
+            user_agent_cursor: Arc::new(AtomicUsize::new(0)),
+            user_agent_overrides: Arc::new(HashMap::new()),
+            success_statuses: None,
+            robots: None,
+            retry: RetryConfig::default(),
+            head_precheck: false,
+            detect_soft_404: false,
+            capture_http_metadata: false,
+            check_target_dns: false,
         }
     }
 
-    pub async fn fetch_batch(&self, urls: Vec<&str>) -> Result<Vec<FetchResult>, PreviewError> {
-        let futures: Vec<_> = urls.into_iter().map(|url| self.fetch(url)).collect();
-        let results = futures::future::join_all(futures).await;
+    /// Replaces the URL validation policy and content limits, keeping the HTTP client.
+    /// The client has no checking resolver, so with `resolve_and_check` enabled target
+    /// addresses are checked before each request instead.
+    #[cfg(any(feature = "twitter", feature = "github"))]
+    pub(crate) fn with_policies(
+        mut self,
+        url_validation: UrlValidationConfig,
+        content_limits: ContentLimits,
+    ) -> Self {
+        self.url_validator = UrlValidator::new(url_validation);
+        self.content_limits = content_limits;
+        self.check_target_dns = self.url_validator.resolves_and_checks();
+        self
+    }
 
-        let mut responses = Vec::new();
-        for result in results {
-            match result {
-                Ok(response) => responses.push(response),
-                Err(e) => return Err(e),
-            }
+    /// The validator applied to every URL before it is fetched
+    pub fn url_validator(&self) -> &UrlValidator {
+        &self.url_validator
+    }
+
+    /// Size and time limits applied to every response
+    pub fn content_limits(&self) -> &ContentLimits {
+        &self.content_limits
+    }
+
+    /// Whether a response status should be parsed as a successful page
+    fn is_success_status(&self, status: reqwest::StatusCode) -> bool {
+        match &self.success_statuses {
+            Some(statuses) => statuses.contains(&status.as_u16()),
+            None => status.is_success(),
+        }
+    }
+
+    /// Picks the next User-Agent from the rotation pool, if one is configured
+    fn next_user_agent(&self) -> Option<&str> {
+        if self.user_agents.is_empty() {
+            return None;
         }
+        let index =
+            self.user_agent_cursor.fetch_add(1, Ordering::Relaxed) % self.user_agents.len();
+        Some(&self.user_agents[index])
+    }
 
-        Ok(responses)
+    /// The configured User-Agent override for `url`'s host, if any
+    fn user_agent_override(&self, url: &str) -> Option<&str> {
+        if self.user_agent_overrides.is_empty() {
+            return None;
+        }
+        let host = url::Url::parse(url).ok()?.host_str()?.to_ascii_lowercase();
+        self.user_agent_overrides
+            .iter()
+            .filter(|(suffix, _)| {
+                let suffix = suffix.to_ascii_lowercase();
+                host == suffix || host.ends_with(&format!(".{suffix}"))
+            })
+            .max_by_key(|(suffix, _)| suffix.len())
+            .map(|(_, user_agent)| user_agent.as_str())
+    }
+
+    /// Builds a GET request, applying a per-host override or the rotated
+    /// User-Agent when configured
+    fn get(&self, url: &str) -> RequestBuilder {
+        self.with_user_agent(self.client.get(url), url)
+    }
+
+    /// Builds a HEAD request with the same User-Agent handling as [`get`](Self::get)
+    fn head(&self, url: &str) -> RequestBuilder {
+        self.with_user_agent(self.client.head(url), url)
+    }
+
+    fn with_user_agent(&self, request: RequestBuilder, url: &str) -> RequestBuilder {
+        match self
+            .user_agent_override(url)
+            .or_else(|| self.next_user_agent())
+        {
+            Some(user_agent) => request.header(USER_AGENT, user_agent),
+            None => request,
+        }
+    }
+
+    /// Fetches all `urls` concurrently. Fails with the first error in input order,
+    /// discarding the other results; see [`fetch_batch_all`](Self::fetch_batch_all)
+    /// to keep them.
+    pub async fn fetch_batch(&self, urls: Vec<&str>) -> Result<Vec<FetchResult>, PreviewError> {
+        self.fetch_batch_all(urls).await.into_iter().collect()
+    }
+
+    /// Fetches all `urls` concurrently, returning one result per URL in input order
+    pub async fn fetch_batch_all(&self, urls: Vec<&str>) -> Vec<Result<FetchResult, PreviewError>> {
+        let futures: Vec<_> = urls.into_iter().map(|url| self.fetch(url)).collect();
+        futures::future::join_all(futures).await
+    }
+
+    /// Like [`fetch_batch_all`](Self::fetch_batch_all), but with at most `limit`
+    /// fetches in flight at once. Results are still returned in input order.
+    pub async fn fetch_batch_with_concurrency(
+        &self,
+        urls: Vec<&str>,
+        limit: usize,
+    ) -> Vec<Result<FetchResult, PreviewError>> {
+        let mut results: Vec<_> = futures::stream::iter(urls.into_iter().enumerate())
+            .map(|(index, url)| async move { (index, self.fetch(url).await) })
+            .buffer_unordered(limit.max(1))
+            .collect()
+            .await;
+        results.sort_unstable_by_key(|(index, _)| *index);
+        results.into_iter().map(|(_, result)| result).collect()
     }
 
     #[cfg_attr(feature = "logging", instrument(level = "debug", skip(self), err))]
@@ -129,14 +735,9 @@ impl Fetcher {
             #[cfg(feature = "logging")]
             debug!(attempt = attempt + 1, "Attempting to fetch URL");
 
-            match self.client.get(url).send().await {
+            match self.get(url).send().await {
                 Ok(response) => {
-                    // Check for 404 first
-                    if response.status() == 404 {
-                        return Err(PreviewError::NotFound(format!("Resource not found: {url}")));
-                    }
-
-                    if response.status().is_success() {
+                    if self.is_success_status(response.status()) {
                         #[cfg(feature = "logging")]
                         debug!(url = %url, "Successfully fetched URL");
                         return response.text().await.map_err(|e| {
@@ -146,27 +747,39 @@ impl Fetcher {
                         });
                     }
 
-                    // For server errors (5xx), retry
-                    if response.status().is_server_error() && attempt < max_retries - 1 {
+                    let retry_after = parse_retry_after(response.headers());
+
+                    // For server errors (5xx) and 429, retry, waiting as long as
+                    // `Retry-After` asks (up to `max_delay`) when it is present
+                    let retryable = response.status().is_server_error()
+                        || response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS;
+                    if retryable && attempt < max_retries - 1 {
+                        let wait = retry_after.map_or(delay, |d| d.min(self.retry.max_delay));
                         #[cfg(feature = "logging")]
                         warn!(
                             status = %response.status(),
                             attempt = attempt + 1,
+                            delay_ms = wait.as_millis() as u64,
                             "Server error, retrying after delay"
                         );
-                        tokio::time::sleep(delay).await;
+                        tokio::time::sleep(wait).await;
                         delay *= 2;
                         continue;
                     }
 
+                    if response.status() == 404 {
+                        return Err(PreviewError::NotFound(format!("Resource not found: {url}")));
+                    }
+
                     // For client errors (4xx except 404) or final attempt, return error
                     let status = response.status().as_u16();
                     let message = format!("Server returned status: {}", response.status());
-                    return Err(match status {
+                    let error = match status {
                         400..=499 => PreviewError::ClientError { status, message },
                         500..=599 => PreviewError::ServerError { status, message },
                         _ => PreviewError::HttpError { status, message },
-                    });
+                    };
+                    return Err(AttemptError { error, retry_after }.into_error());
                 }
                 Err(e) => {
                     let preview_error = PreviewError::from_reqwest_error(e);
@@ -202,12 +815,109 @@ impl Fetcher {
         Err(PreviewError::FetchError("Max retries exceeded".to_string()))
     }
 
-    #[cfg_attr(feature = "logging", instrument(level = "debug", skip(self), err))]
     pub async fn fetch(&self, url: &str) -> Result<FetchResult, PreviewError> {
+        self.fetch_with_headers(url, HeaderMap::new()).await
+    }
+
+    /// Like [`fetch`](Self::fetch), but sends `headers` (e.g. `Referer`, `Cookie`,
+    /// `Authorization`) with the page request, overriding client defaults of the
+    /// same name. URL validation and content limits apply as usual.
+    pub async fn fetch_with_headers(
+        &self,
+        url: &str,
+        headers: HeaderMap,
+    ) -> Result<FetchResult, PreviewError> {
+        self.fetch_detailed_with_headers(url, headers)
+            .await
+            .map(|response| response.result)
+    }
+
+    /// Like [`fetch`](Self::fetch), but also returns the status code and final URL
+    pub async fn fetch_detailed(&self, url: &str) -> Result<FetchResponse, PreviewError> {
+        self.fetch_detailed_with_headers(url, HeaderMap::new()).await
+    }
+
+    /// Like [`fetch_detailed`](Self::fetch_detailed), but sends `If-None-Match` and
+    /// `If-Modified-Since` from the validators of an earlier response. A `304 Not
+    /// Modified` answer is returned as [`FetchResult::NotModified`].
+    pub async fn revalidate(
+        &self,
+        url: &str,
+        validators: &HttpMeta,
+    ) -> Result<FetchResponse, PreviewError> {
+        self.fetch_detailed_with_headers(url, validators.conditional_headers())
+            .await
+    }
+
+    // Field names follow the OpenTelemetry HTTP conventions so exported spans
+    // line up with other instrumented clients
+    #[cfg_attr(
+        feature = "logging",
+        instrument(
+            level = "debug",
+            skip(self, url, headers),
+            fields(
+                http.url = %url,
+                http.status_code = tracing::field::Empty,
+                net.peer.name = tracing::field::Empty,
+            ),
+            err
+        )
+    )]
+    async fn fetch_detailed_with_headers(
+        &self,
+        url: &str,
+        headers: HeaderMap,
+    ) -> Result<FetchResponse, PreviewError> {
+        #[cfg(feature = "metrics")]
+        let started = Instant::now();
+        let result = self.fetch_page(url, headers).await;
+        #[cfg(feature = "metrics")]
+        crate::metrics::record_fetch(&result, started.elapsed());
+        #[cfg(feature = "logging")]
+        if let Ok(response) = &result {
+            let span = tracing::Span::current();
+            span.record("http.status_code", response.status);
+            if let Some(host) = url::Url::parse(&response.final_url)
+                .ok()
+                .and_then(|url| url.host_str().map(String::from))
+            {
+                span.record("net.peer.name", host.as_str());
+            }
+        }
+        result
+    }
+
+    /// Validates a URL before it is requested, also resolving and checking its host
+    /// when requests go through a proxy with `resolve_and_check` enabled
+    async fn validate_target(&self, url: &str) -> Result<url::Url, PreviewError> {
+        let url = self.url_validator.validate(url)?;
+        if self.check_target_dns {
+            if let Some(host) = url.host_str() {
+                let port = url.port_or_known_default().unwrap_or(0);
+                self.url_validator.resolve_checked(host, port).await?;
+            }
+        }
+        Ok(url)
+    }
+
+    async fn fetch_page(
+        &self,
+        url: &str,
+        headers: HeaderMap,
+    ) -> Result<FetchResponse, PreviewError> {
         // Validate URL first
-        let validated_url = self.url_validator.validate(url)?;
+        let validated_url = self.validate_target(url).await?;
         let url_str = validated_url.as_str();
 
+        if let Some(robots) = &self.robots {
+            robots
+                .check(&validated_url, &self.url_validator, |robots_url| {
+                    self.get(robots_url)
+                })
+                .await?;
+        }
+
         #[cfg(feature = "logging")]
         debug!(url = %url_str, "Starting fetch request after validation");
 
@@ -216,31 +926,253 @@ impl Fetcher {
             debug!(url = %url, "Detected Twitter URL, using oEmbed API");
             #[cfg(feature = "twitter")]
             {
-                let oembed = self.fetch_twitter_oembed(url_str).await?;
-                Ok(FetchResult::OEmbed(oembed))
+                let (oembed, status) = self.fetch_twitter_oembed(url_str).await?;
+                Ok(FetchResponse {
+                    result: FetchResult::OEmbed(oembed),
+                    status,
+                    final_url: url_str.to_string(),
+                    http_metadata: None,
+                })
             }
             #[cfg(not(feature = "twitter"))]
             {
                 // Fall back to regular HTML fetching
-                self.fetch_html(url_str).await.map(FetchResult::Html)
+                self.fetch_html(url_str, headers).await
+            }
+        } else if is_youtube_url(url_str) {
+            #[cfg(feature = "logging")]
+            debug!(url = %url, "Detected YouTube URL, using oEmbed API");
+            #[cfg(feature = "youtube")]
+            {
+                let (oembed, status) = self.fetch_youtube_oembed(url_str).await?;
+                Ok(FetchResponse {
+                    result: FetchResult::OEmbed(oembed),
+                    status,
+                    final_url: url_str.to_string(),
+                    http_metadata: None,
+                })
+            }
+            #[cfg(not(feature = "youtube"))]
+            {
+                self.fetch_html(url_str, headers).await
+            }
+        } else if is_vimeo_url(url_str) {
+            #[cfg(feature = "logging")]
+            debug!(url = %url, "Detected Vimeo URL, using oEmbed API");
+            #[cfg(feature = "vimeo")]
+            {
+                let (oembed, status) = self.fetch_vimeo_oembed(url_str).await?;
+                Ok(FetchResponse {
+                    result: FetchResult::OEmbed(oembed),
+                    status,
+                    final_url: url_str.to_string(),
+                    http_metadata: None,
+                })
+            }
+            #[cfg(not(feature = "vimeo"))]
+            {
+                self.fetch_html(url_str, headers).await
             }
         } else {
             #[cfg(feature = "logging")]
             debug!(url = %url, "Fetching regular webpage");
-            self.fetch_html(url).await.map(FetchResult::Html)
+            self.fetch_html(url, headers).await
         }
     }
 
-    async fn fetch_html(&self, url: &str) -> Result<String, PreviewError> {
-        self.fetch_html_with_limits(url).await
+    /// Cheaply checks whether a URL is likely to produce a preview.
+    ///
+    /// Runs URL validation and issues a HEAD request, falling back to a ranged GET
+    /// when the server rejects HEAD. The response body is never read.
+    #[cfg_attr(feature = "logging", instrument(level = "debug", skip(self), err))]
+    pub async fn probe(&self, url: &str) -> Result<ProbeResult, PreviewError> {
+        let validated_url = self.validate_target(url).await?;
+        let url_str = validated_url.as_str();
+
+        let mut response = self
+            .head(url_str)
+            .send()
+            .await
+            .map_err(PreviewError::from_reqwest_error)?;
+
+        if matches!(response.status().as_u16(), 405 | 501) {
+            #[cfg(feature = "logging")]
+            debug!(url = %url_str, "HEAD not supported, falling back to ranged GET");
+            response = self
+                .get(url_str)
+                .header(RANGE, "bytes=0-0")
+                .send()
+                .await
+                .map_err(PreviewError::from_reqwest_error)?;
+        }
+
+        let status = response.status();
+        if !self.is_success_status(status) && status != reqwest::StatusCode::PARTIAL_CONTENT {
+            if status == 404 {
+                return Err(PreviewError::NotFound(format!("Resource not found: {url}")));
+            }
+            let code = status.as_u16();
+            let message = format!("Server returned status: {status}");
+            return Err(match code {
+                400..=499 => PreviewError::ClientError { status: code, message },
+                500..=599 => PreviewError::ServerError { status: code, message },
+                _ => PreviewError::HttpError { status: code, message },
+            });
+        }
+
+        let content_type = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.split(';').next().unwrap_or("").trim().to_lowercase());
+        // A ranged response reports the chunk size, so prefer the total from Content-Range
+        let content_length = response
+            .headers()
+            .get(CONTENT_RANGE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.rsplit('/').next())
+            .and_then(|total| total.parse::<u64>().ok())
+            .or_else(|| {
+                response
+                    .headers()
+                    .get(CONTENT_LENGTH)
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(|value| value.parse::<u64>().ok())
+            });
+
+        let html_type = content_type
+            .as_deref()
+            .is_none_or(|ct| ct == "text/html" || ct == "application/xhtml+xml");
+        let within_limit =
+            content_length.is_none_or(|len| len <= self.content_limits.max_content_size as u64);
+
+        Ok(ProbeResult {
+            content_type,
+            content_length,
+            likely_html: html_type && within_limit,
+        })
     }
 
-    async fn fetch_html_with_limits(&self, url: &str) -> Result<String, PreviewError> {
+    /// Fetches a URL and returns the status, headers and body without any HTML
+    /// processing, for callers that run their own extraction.
+    ///
+    /// URL validation, robots.txt and content limits apply as for [`Fetcher::fetch`],
+    /// but non-success statuses are returned as-is and no retries are attempted.
+    #[cfg_attr(feature = "logging", instrument(level = "debug", skip(self), err))]
+    pub async fn fetch_raw(&self, url: &str) -> Result<RawResponse, PreviewError> {
+        let validated_url = self.validate_target(url).await?;
+        let url_str = validated_url.as_str();
+
+        if let Some(robots) = &self.robots {
+            robots
+                .check(&validated_url, &self.url_validator, |robots_url| {
+                    self.get(robots_url)
+                })
+                .await?;
+        }
+
+        let start_time = Instant::now();
+        let response = timeout(
+            Duration::from_secs(self.content_limits.max_download_time),
+            self.get(url_str).send(),
+        )
+        .await
+        .map_err(|_| PreviewError::DownloadTimeExceeded {
+            elapsed: start_time.elapsed().as_secs(),
+            limit: self.content_limits.max_download_time,
+        })?
+        .map_err(PreviewError::from_reqwest_error)?;
+
+        let unverified_type = self.check_content_headers(&response)?;
+
+        let status = response.status().as_u16();
+        let headers = response.headers().clone();
+        let final_url = response.url().to_string();
+        let body = self.read_body_with_limit(response, start_time).await?;
+        check_sniffed_type(unverified_type, &body)?;
+
+        Ok(RawResponse {
+            status,
+            headers,
+            body,
+            final_url,
+        })
+    }
+
+    async fn fetch_html(
+        &self,
+        url: &str,
+        headers: HeaderMap,
+    ) -> Result<FetchResponse, PreviewError> {
+        self.fetch_html_with_limits(url, headers).await
+    }
+
+    /// Fetches a page, retrying transient failures according to the retry config
+    async fn fetch_html_with_limits(
+        &self,
+        url: &str,
+        headers: HeaderMap,
+    ) -> Result<FetchResponse, PreviewError> {
+        if self.head_precheck {
+            self.head_precheck(url).await?;
+        }
+
+        let mut retry = 0;
+        loop {
+            let attempt = match self.fetch_html_once(url, headers.clone()).await {
+                Ok(response) => return Ok(response),
+                Err(attempt) => attempt,
+            };
+            if retry >= self.retry.max_retries || !attempt.error.is_retryable() {
+                return Err(attempt.into_error());
+            }
+
+            let delay = self.retry.delay(retry, attempt.retry_after);
+            #[cfg(feature = "logging")]
+            warn!(
+                url = %url,
+                error = %attempt.error,
+                retry = retry + 1,
+                delay_ms = delay.as_millis() as u64,
+                "Transient fetch failure, retrying"
+            );
+            tokio::time::sleep(delay).await;
+            retry += 1;
+        }
+    }
+
+    /// Rejects a page whose HEAD response already declares a disallowed content type
+    /// or an oversized body. When HEAD fails or is answered with an error status
+    /// (e.g. 405), the decision is left to the GET.
+    async fn head_precheck(&self, url: &str) -> Result<(), PreviewError> {
+        let download_timeout = Duration::from_secs(self.content_limits.max_download_time);
+        let response = match timeout(download_timeout, self.head(url).send()).await {
+            Ok(Ok(response)) => response,
+            _ => return Ok(()),
+        };
+        if !self.is_success_status(response.status()) {
+            #[cfg(feature = "logging")]
+            debug!(url = %url, status = %response.status(), "HEAD precheck skipped");
+            return Ok(());
+        }
+        // Images are previewed without downloading their body
+        if image_content_type(&response).is_some() {
+            return Ok(());
+        }
+        // A type that still needs sniffing is confirmed once the body is read
+        self.check_content_headers(&response).map(|_| ())
+    }
+
+    async fn fetch_html_once(
+        &self,
+        url: &str,
+        headers: HeaderMap,
+    ) -> Result<FetchResponse, AttemptError> {
         let start_time = Instant::now();
         let download_timeout = Duration::from_secs(self.content_limits.max_download_time);
 
         // Send request with timeout
-        let response = timeout(download_timeout, self.client.get(url).send())
+        let response = timeout(download_timeout, self.get(url).headers(headers).send())
             .await
             .map_err(|_| PreviewError::DownloadTimeExceeded {
                 elapsed: start_time.elapsed().as_secs(),
@@ -252,22 +1184,127 @@ impl Fetcher {
                 PreviewError::from_reqwest_error(e)
             })?;
 
-        // Check for 404 or other error status codes
-        if response.status() == 404 {
-            return Err(PreviewError::NotFound(format!("Resource not found: {url}")));
+        self.read_page(url, response, start_time).await
+    }
+
+    /// Reads a page response obtained elsewhere, e.g. from a client with the
+    /// caller's own authentication. Unsuccessful statuses are rejected and the
+    /// content limits apply as for [`fetch`](Self::fetch), with the download time
+    /// counted from this call.
+    pub async fn read_response(&self, response: Response) -> Result<FetchResponse, PreviewError> {
+        let url = response.url().to_string();
+        self.read_page(&url, response, Instant::now())
+            .await
+            .map_err(AttemptError::into_error)
+    }
+
+    /// Checks the status and content headers of a page response, then reads its body
+    /// within the content limits
+    async fn read_page(
+        &self,
+        url: &str,
+        response: Response,
+        start_time: Instant,
+    ) -> Result<FetchResponse, AttemptError> {
+        // Only sent in answer to a conditional request
+        if response.status() == 304 {
+            #[cfg(feature = "logging")]
+            debug!(url = %url, "Page not modified");
+            return Ok(FetchResponse {
+                result: FetchResult::NotModified,
+                status: 304,
+                final_url: response.url().to_string(),
+                http_metadata: self
+                    .capture_http_metadata
+                    .then(|| HttpMeta::from_headers(response.headers())),
+            });
         }
 
-        if !response.status().is_success() {
+        // Check for 404 or other error status codes
+        if !self.is_success_status(response.status()) {
+            if response.status() == 404 {
+                return Err(PreviewError::NotFound(format!("Resource not found: {url}")).into());
+            }
+
             let status = response.status().as_u16();
             let message = format!("Server returned status: {}", response.status());
 
-            return Err(match status {
-                400..=499 => PreviewError::ClientError { status, message },
-                500..=599 => PreviewError::ServerError { status, message },
-                _ => PreviewError::HttpError { status, message },
+            return Err(AttemptError {
+                error: match status {
+                    400..=499 => PreviewError::ClientError { status, message },
+                    500..=599 => PreviewError::ServerError { status, message },
+                    _ => PreviewError::HttpError { status, message },
+                },
+                retry_after: parse_retry_after(response.headers()),
             });
         }
 
+        let status = response.status().as_u16();
+        let final_url = response.url().to_string();
+        let http_metadata = self
+            .capture_http_metadata
+            .then(|| HttpMeta::from_headers(response.headers()));
+
+        // Images are previewed from their headers, whatever the content limits allow
+        if let Some(content_type) = image_content_type(&response) {
+            let dimensions = self.read_image_dimensions(response, start_time).await?;
+            #[cfg(feature = "logging")]
+            debug!(url = %url, content_type = %content_type, "Fetched image metadata");
+            return Ok(FetchResponse {
+                result: FetchResult::Image(ImageInfo {
+                    content_type,
+                    width: dimensions.map(|(width, _)| width),
+                    height: dimensions.map(|(_, height)| height),
+                }),
+                status,
+                final_url,
+                http_metadata,
+            });
+        }
+
+        let unverified_type = self.check_content_headers(&response)?;
+
+        // PDFs are read whole, within the size limit, for their document info
+        #[cfg(feature = "pdf")]
+        if is_pdf(&response) {
+            let bytes = self.read_body_with_limit(response, start_time).await?;
+            #[cfg(feature = "logging")]
+            debug!(url = %url, content_length = bytes.len(), "Fetched PDF document");
+            return Ok(FetchResponse {
+                result: FetchResult::Pdf(crate::pdf::read_info(&bytes)),
+                status,
+                final_url,
+                http_metadata,
+            });
+        }
+
+        // Read content with size limit
+        let content = self.read_response_with_limit(response, start_time).await?;
+        check_sniffed_type(unverified_type, content.as_bytes())?;
+        if self.detect_soft_404 && utils::looks_like_soft_404(&content) {
+            #[cfg(feature = "logging")]
+            debug!(url = %url, "Page looks like a soft 404");
+            return Err(PreviewError::NotFound(format!("Soft 404 page: {url}")).into());
+        }
+
+        #[cfg(feature = "logging")]
+        debug!(url = %url, content_length = content.len(), "Successfully fetched webpage");
+        Ok(FetchResponse {
+            result: FetchResult::Html(content),
+            status,
+            final_url,
+            http_metadata,
+        })
+    }
+
+    /// Rejects a response whose declared content type or length falls outside the
+    /// configured content limits.
+    ///
+    /// With `sniff_content_type` set, a disallowed type is returned instead of
+    /// rejected, to be confirmed against the body by [`check_sniffed_type`].
+    fn check_content_headers(&self, response: &Response) -> Result<Option<String>, PreviewError> {
+        let mut unverified_type = None;
+
         // Check content type if configured
         if !self.content_limits.allowed_content_types.is_empty() {
             if let Some(content_type) = response.headers().get("content-type") {
@@ -277,8 +1314,12 @@ impl Fetcher {
                         .content_limits
                         .allowed_content_types
                         .contains(base_type)
+                        && !is_pdf_type(base_type)
                     {
-                        return Err(PreviewError::ContentTypeNotAllowed(base_type.to_string()));
+                        if !self.content_limits.sniff_content_type {
+                            return Err(PreviewError::ContentTypeNotAllowed(base_type.to_string()));
+                        }
+                        unverified_type = Some(base_type.to_string());
                     }
                 }
             }
@@ -298,12 +1339,49 @@ impl Fetcher {
             }
         }
 
-        // Read content with size limit
-        let content = self.read_response_with_limit(response, start_time).await?;
+        Ok(unverified_type)
+    }
 
-        #[cfg(feature = "logging")]
-        debug!(url = %url, content_length = content.len(), "Successfully fetched webpage");
-        Ok(content)
+    /// Reads just enough of an image body to decode its dimensions
+    #[cfg(feature = "image-dims")]
+    async fn read_image_dimensions(
+        &self,
+        mut response: Response,
+        start_time: Instant,
+    ) -> Result<Option<(u32, u32)>, PreviewError> {
+        let max_time = Duration::from_secs(self.content_limits.max_download_time);
+        let limit = IMAGE_HEADER_BYTES.min(self.content_limits.max_content_size);
+
+        let mut header = Vec::new();
+        while header.len() < limit {
+            let chunk = tokio::time::timeout(
+                max_time.saturating_sub(start_time.elapsed()),
+                response.chunk(),
+            )
+            .await
+            .map_err(|_| PreviewError::DownloadTimeExceeded {
+                elapsed: start_time.elapsed().as_secs(),
+                limit: self.content_limits.max_download_time,
+            })?
+            .map_err(|e| PreviewError::FetchError(e.to_string()))?;
+
+            let Some(chunk) = chunk else {
+                break;
+            };
+            header.extend_from_slice(&chunk);
+        }
+        header.truncate(limit);
+
+        Ok(image_dimensions(&header))
+    }
+
+    #[cfg(not(feature = "image-dims"))]
+    async fn read_image_dimensions(
+        &self,
+        _response: Response,
+        _start_time: Instant,
+    ) -> Result<Option<(u32, u32)>, PreviewError> {
+        Ok(None)
     }
 
     async fn read_response_with_limit(
@@ -311,44 +1389,68 @@ impl Fetcher {
         response: Response,
         start_time: Instant,
     ) -> Result<String, PreviewError> {
+        let content_type = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(String::from);
+        let bytes = self.read_body_with_limit(response, start_time).await?;
+
+        // Decode using the declared charset, falling back to lossy UTF-8
+        Ok(utils::decode_html(&bytes, content_type.as_deref()))
+    }
+
+    async fn read_body_with_limit(
+        &self,
+        mut response: Response,
+        start_time: Instant,
+    ) -> Result<Vec<u8>, PreviewError> {
         let max_size = self.content_limits.max_content_size;
         let max_time = Duration::from_secs(self.content_limits.max_download_time);
 
-        // Read response with timeout
-        let bytes = tokio::time::timeout(
-            max_time.saturating_sub(start_time.elapsed()),
-            response.bytes(),
-        )
-        .await
-        .map_err(|_| PreviewError::DownloadTimeExceeded {
-            elapsed: start_time.elapsed().as_secs(),
-            limit: self.content_limits.max_download_time,
-        })?
-        .map_err(|e| {
-            #[cfg(feature = "logging")]
-            error!(error = %e, "Failed to read response body");
-            PreviewError::FetchError(e.to_string())
-        })?;
+        // Stream the body so an oversized response is rejected before it is fully buffered.
+        // Compressed bodies are decoded as they stream, so the limit bounds the
+        // decompressed size rather than the bytes on the wire.
+        let mut bytes = Vec::new();
+        loop {
+            let chunk = tokio::time::timeout(
+                max_time.saturating_sub(start_time.elapsed()),
+                response.chunk(),
+            )
+            .await
+            .map_err(|_| PreviewError::DownloadTimeExceeded {
+                elapsed: start_time.elapsed().as_secs(),
+                limit: self.content_limits.max_download_time,
+            })?
+            .map_err(|e| {
+                #[cfg(feature = "logging")]
+                error!(error = %e, "Failed to read response body");
+                PreviewError::FetchError(e.to_string())
+            })?;
 
-        // Check size limit
-        if bytes.len() > max_size {
-            return Err(PreviewError::ContentSizeExceeded {
-                size: bytes.len(),
-                limit: max_size,
-            });
+            let Some(chunk) = chunk else {
+                break;
+            };
+
+            // Check size limit
+            if bytes.len() + chunk.len() > max_size {
+                return Err(PreviewError::ContentSizeExceeded {
+                    size: bytes.len() + chunk.len(),
+                    limit: max_size,
+                });
+            }
+            bytes.extend_from_slice(&chunk);
         }
 
-        // Convert bytes to string
-        String::from_utf8(bytes.to_vec()).map_err(|_e| {
-            #[cfg(feature = "logging")]
-            error!(error = %_e, "Response is not valid UTF-8");
-            PreviewError::FetchError("Invalid UTF-8 in response".to_string())
-        })
+        Ok(bytes)
     }
 
     #[cfg(feature = "twitter")]
     #[cfg_attr(feature = "logging", instrument(level = "debug", skip(self), err))]
-    async fn fetch_twitter_oembed(&self, tweet_url: &str) -> Result<OEmbedResponse, PreviewError> {
+    async fn fetch_twitter_oembed(
+        &self,
+        tweet_url: &str,
+    ) -> Result<(OEmbedResponse, u16), PreviewError> {
         let oembed_url =
             format!("https://publish.twitter.com/oembed?url={tweet_url}&omit_script=1&lang=en");
 
@@ -402,6 +1504,7 @@ impl Fetcher {
             });
         }
 
+        let status = response.status().as_u16();
         let oembed: OEmbedResponse = response.json().await.map_err(|e| {
             #[cfg(feature = "logging")]
             error!(error = %e, url = %tweet_url, "Failed to parse Twitter oEmbed response");
@@ -413,7 +1516,278 @@ impl Fetcher {
 
         #[cfg(feature = "logging")]
         debug!(tweet_url = %tweet_url, "Successfully fetched Twitter oEmbed data");
-        Ok(oembed)
+        Ok((oembed, status))
+    }
+}
+
+/// The oEmbed endpoint URL for a YouTube video link
+#[cfg(feature = "youtube")]
+fn youtube_oembed_url(video_url: &str) -> String {
+    url::Url::parse_with_params(
+        "https://www.youtube.com/oembed",
+        &[("url", video_url), ("format", "json")],
+    )
+    .map(String::from)
+    .unwrap_or_default()
+}
+
+/// The oEmbed endpoint URL for a Vimeo video link. Player links are rewritten to
+/// the `vimeo.com/{id}` form; other links keep any private-link hash.
+#[cfg(feature = "vimeo")]
+fn vimeo_oembed_url(video_url: &str) -> String {
+    let canonical = match crate::vimeo_video_id(video_url) {
+        Some(id) if video_url.contains("player.vimeo.com") => format!("https://vimeo.com/{id}"),
+        _ => video_url.to_string(),
+    };
+    url::Url::parse_with_params(
+        "https://vimeo.com/api/oembed.json",
+        &[("url", canonical.as_str())],
+    )
+    .map(String::from)
+    .unwrap_or_default()
+}
+
+// for YouTube
+#[cfg(feature = "youtube")]
+impl Fetcher {
+    #[cfg_attr(feature = "logging", instrument(level = "debug", skip(self), err))]
+    async fn fetch_youtube_oembed(
+        &self,
+        video_url: &str,
+    ) -> Result<(OEmbedResponse, u16), PreviewError> {
+        self.fetch_video_oembed("YouTube", &youtube_oembed_url(video_url), video_url)
+            .await
+    }
+}
+
+// for Vimeo
+#[cfg(feature = "vimeo")]
+impl Fetcher {
+    #[cfg_attr(feature = "logging", instrument(level = "debug", skip(self), err))]
+    async fn fetch_vimeo_oembed(
+        &self,
+        video_url: &str,
+    ) -> Result<(OEmbedResponse, u16), PreviewError> {
+        self.fetch_video_oembed("Vimeo", &vimeo_oembed_url(video_url), video_url)
+            .await
+    }
+}
+
+#[cfg(any(feature = "youtube", feature = "vimeo"))]
+impl Fetcher {
+    /// Fetches a video provider's oEmbed response for `video_url` from `oembed_url`
+    async fn fetch_video_oembed(
+        &self,
+        service: &str,
+        oembed_url: &str,
+        video_url: &str,
+    ) -> Result<(OEmbedResponse, u16), PreviewError> {
+        let service_error = |message: String| PreviewError::ExternalServiceError {
+            service: service.to_string(),
+            message,
+        };
+
+        let response = self.get(oembed_url).send().await.map_err(|e| {
+            #[cfg(feature = "logging")]
+            error!(error = %e, url = %video_url, "Failed to fetch {service} oEmbed");
+            service_error(PreviewError::from_reqwest_error(e).to_string())
+        })?;
+
+        let status = response.status().as_u16();
+        match status {
+            404 => {
+                return Err(PreviewError::NotFound(format!(
+                    "{service} video not found: {video_url}"
+                )))
+            }
+            // Private videos and videos with embedding disabled
+            401 | 403 => {
+                return Err(service_error(format!(
+                    "Video is private or not embeddable ({status})"
+                )))
+            }
+            _ if !response.status().is_success() => {
+                return Err(service_error(format!(
+                    "{service} oEmbed returned status: {}",
+                    response.status()
+                )))
+            }
+            _ => {}
+        }
+
+        let oembed: OEmbedResponse = response.json().await.map_err(|e| {
+            #[cfg(feature = "logging")]
+            error!(error = %e, url = %video_url, "Failed to parse {service} oEmbed response");
+            service_error(e.to_string())
+        })?;
+
+        Ok((oembed, status))
+    }
+}
+
+// for Reddit
+#[cfg(feature = "reddit")]
+impl Fetcher {
+    /// Builds a preview for a Reddit post from its `.json` endpoint.
+    ///
+    /// Galleries and crossposts are reported as `PreviewError::UnsupportedOperation`
+    /// so callers can fall back to generic HTML extraction.
+    #[cfg_attr(feature = "logging", instrument(level = "debug", skip(self), err))]
+    pub async fn fetch_reddit_post(&self, url: &str) -> Result<crate::Preview, PreviewError> {
+        let json_url = reddit_json_url(url)
+            .ok_or_else(|| PreviewError::InvalidUrl(format!("Not a Reddit post URL: {url}")))?;
+        let json_url = self.validate_target(&json_url).await?;
+
+        let start_time = Instant::now();
+        let response = self
+            .get(json_url.as_str())
+            .send()
+            .await
+            .map_err(PreviewError::from_reqwest_error)?;
+
+        if response.status() == 404 {
+            return Err(PreviewError::NotFound(format!("Reddit post not found: {url}")));
+        }
+
+        if !response.status().is_success() {
+            let status = response.status().as_u16();
+            let message = format!("Reddit returned status: {}", response.status());
+
+            return Err(match status {
+                400..=499 => PreviewError::ClientError { status, message },
+                500..=599 => PreviewError::ServerError { status, message },
+                _ => PreviewError::HttpError { status, message },
+            });
+        }
+
+        let body = self.read_response_with_limit(response, start_time).await?;
+        let payload: serde_json::Value = serde_json::from_str(&body)?;
+        let post = RedditPost::from_json(&payload).ok_or_else(|| {
+            PreviewError::UnsupportedOperation(format!(
+                "Reddit post is a gallery, a crosspost or not a post: {url}"
+            ))
+        })?;
+
+        Ok(post.into_preview(url))
+    }
+}
+
+// for GitLab
+#[cfg(feature = "gitlab")]
+impl Fetcher {
+    /// Fetches a project from the GitLab API. `namespace` may include subgroups,
+    /// e.g. `gitlab-org/charts`.
+    #[cfg_attr(feature = "logging", instrument(level = "debug", skip(self), err))]
+    pub async fn fetch_gitlab_project(
+        &self,
+        namespace: &str,
+        project: &str,
+    ) -> Result<GitLabProject, PreviewError> {
+        let api_url = self
+            .validate_target(&gitlab_api_url(namespace, project))
+            .await?;
+
+        let start_time = Instant::now();
+        let response = self
+            .get(api_url.as_str())
+            .send()
+            .await
+            .map_err(PreviewError::from_reqwest_error)?;
+
+        if response.status() == 404 {
+            return Err(PreviewError::NotFound(format!(
+                "GitLab project {namespace}/{project} not found"
+            )));
+        }
+
+        if !response.status().is_success() {
+            let status = response.status().as_u16();
+            let message = format!("GitLab API returned status: {}", response.status());
+
+            return Err(match status {
+                400..=499 => PreviewError::ClientError { status, message },
+                500..=599 => PreviewError::ServerError { status, message },
+                _ => PreviewError::HttpError { status, message },
+            });
+        }
+
+        let body = self.read_body_with_limit(response, start_time).await?;
+        Ok(serde_json::from_slice(&body)?)
+    }
+}
+
+// for Mastodon
+#[cfg(feature = "mastodon")]
+impl Fetcher {
+    /// Builds a preview for a Mastodon status via the instance's
+    /// `/api/v1/statuses/:id` endpoint.
+    ///
+    /// The host is first checked through NodeInfo; hosts that aren't running
+    /// Mastodon-compatible software are reported as `PreviewError::UnsupportedOperation`
+    /// so callers can fall back to generic HTML extraction.
+    #[cfg_attr(feature = "logging", instrument(level = "debug", skip(self), err))]
+    pub async fn fetch_mastodon_status(&self, url: &str) -> Result<crate::Preview, PreviewError> {
+        let (origin, status_id) = parse_mastodon_status_url(url)
+            .ok_or_else(|| PreviewError::InvalidUrl(format!("Not a Mastodon status URL: {url}")))?;
+
+        if !self.is_mastodon_instance(&origin).await? {
+            return Err(PreviewError::UnsupportedOperation(format!(
+                "{origin} is not a Mastodon-compatible instance"
+            )));
+        }
+
+        let status = self
+            .fetch_json(&format!("{origin}/api/v1/statuses/{status_id}"))
+            .await?;
+        let status: MastodonStatus = serde_json::from_value(status)?;
+        Ok(status.into_preview(url))
+    }
+
+    /// Whether `origin` advertises Mastodon-compatible software through NodeInfo.
+    /// Hosts without NodeInfo are not instances.
+    pub async fn is_mastodon_instance(&self, origin: &str) -> Result<bool, PreviewError> {
+        let links = match self
+            .fetch_json(&format!("{origin}/.well-known/nodeinfo"))
+            .await
+        {
+            Ok(links) => links,
+            Err(PreviewError::NotFound(_)) => return Ok(false),
+            Err(e) => return Err(e),
+        };
+        let Some(document_url) = nodeinfo_url(&links, origin) else {
+            return Ok(false);
+        };
+        let nodeinfo = self.fetch_json(&document_url).await?;
+        Ok(is_mastodon_compatible(&nodeinfo))
+    }
+
+    async fn fetch_json(&self, url: &str) -> Result<serde_json::Value, PreviewError> {
+        let url = self.validate_target(url).await?;
+
+        let start_time = Instant::now();
+        let response = self
+            .get(url.as_str())
+            .send()
+            .await
+            .map_err(PreviewError::from_reqwest_error)?;
+
+        if response.status() == 404 {
+            return Err(PreviewError::NotFound(format!("Resource not found: {url}")));
+        }
+
+        if !response.status().is_success() {
+            let status = response.status().as_u16();
+            let message = format!("Server returned status: {}", response.status());
+
+            return Err(match status {
+                400..=499 => PreviewError::ClientError { status, message },
+                500..=599 => PreviewError::ServerError { status, message },
+                _ => PreviewError::HttpError { status, message },
+            });
+        }
+
+        let body = self.read_body_with_limit(response, start_time).await?;
+        Ok(serde_json::from_slice(&body)?)
     }
 }
 
@@ -422,6 +1796,11 @@ impl Fetcher {
 impl Fetcher {
     #[cfg_attr(feature = "logging", instrument(level = "debug"))]
     pub fn new_twitter_client() -> Self {
+        Self::with_client(Self::twitter_http_client(Duration::from_secs(30)))
+    }
+
+    /// The browser-like HTTP client used for Twitter/X pages
+    pub(crate) fn twitter_http_client(timeout: Duration) -> Client {
         #[cfg(feature = "logging")]
         debug!("Creating Twitter-specific fetcher");
 
@@ -450,19 +1829,15 @@ impl Fetcher {
                 AppleWebKit/537.36 (KHTML, like Gecko) \
                 Chrome/119.0.0.0 Safari/537.36",
             )
-            .timeout(Duration::from_secs(30))
-            .redirect(reqwest::redirect::Policy::limited(10))
+            .timeout(timeout)
+            .redirect(redirect_policy(10))
             .default_headers(headers)
             .build()
             .expect("Failed to create Twitter HTTP client");
 
         #[cfg(feature = "logging")]
         debug!("Twitter-specific fetcher created successfully");
-        Self {
-            client,
-            url_validator: UrlValidator::with_default_config(),
-            content_limits: ContentLimits::default(),
-        }
+        client
     }
 
     /// Creates a Fetcher with custom configuration
@@ -476,6 +1851,12 @@ impl Fetcher {
 #[cfg(feature = "github")]
 impl Fetcher {
     pub fn new_github_client() -> Self {
+        Self::with_client(Self::github_http_client(Duration::from_secs(10)))
+    }
+
+    /// The HTTP client used for GitHub API requests, authenticated with
+    /// `GITHUB_TOKEN` when it is set
+    pub(crate) fn github_http_client(timeout: Duration) -> Client {
         #[cfg(feature = "logging")]
         debug!("Creating GitHub-specific client");
 
@@ -488,18 +1869,12 @@ impl Fetcher {
             headers.insert("Authorization", format!("Bearer {token}").parse().unwrap());
         }
 
-        let client = Client::builder()
+        Client::builder()
             .user_agent("url_preview/1.0")
             .default_headers(headers)
-            .timeout(Duration::from_secs(10))
+            .timeout(timeout)
             .build()
-            .expect("Failed to create GitHub HTTP client");
-
-        Self {
-            client,
-            url_validator: UrlValidator::with_default_config(),
-            content_limits: ContentLimits::default(),
-        }
+            .expect("Failed to create GitHub HTTP client")
     }
 
     pub async fn fetch_github_repo(
@@ -673,6 +2048,18 @@ impl Fetcher {
             .await
             .map_err(|e| PreviewError::ParseError(e.to_string()))?;
 
+        let default_branch = data["default_branch"].as_str().unwrap_or("main");
+        // The README is a nice-to-have; failing to fetch it doesn't fail the lookup
+        let readme_summary = self
+            .fetch_github_readme(owner, repo, default_branch)
+            .await
+            .inspect_err(|_e| {
+                #[cfg(feature = "logging")]
+                warn!(error = %_e, "Failed to fetch GitHub README");
+            })
+            .ok()
+            .flatten();
+
         Ok(GitHubDetailedInfo {
             full_name: data["full_name"].as_str().unwrap_or("").to_string(),
             description: data["description"]
@@ -683,10 +2070,7 @@ impl Fetcher {
             forks_count: data["forks_count"].as_u64().unwrap_or(0) as u32,
             open_issues_count: data["open_issues_count"].as_u64().unwrap_or(0) as u32,
             language: data["language"].as_str().map(|s| s.to_string()),
-            default_branch: data["default_branch"]
-                .as_str()
-                .unwrap_or("main")
-                .to_string(),
+            default_branch: default_branch.to_string(),
             topics: data["topics"]
                 .as_array()
                 .map(|arr| {
@@ -700,9 +2084,49 @@ impl Fetcher {
                 .as_str()
                 .filter(|s| !s.is_empty())
                 .map(|s| s.to_string()),
+            readme_summary,
         })
     }
 
+    /// Fetches `README.md` from `branch` and returns its first paragraph as plain
+    /// text. A missing README (404) yields `Ok(None)`.
+    pub async fn fetch_github_readme(
+        &self,
+        owner: &str,
+        repo: &str,
+        branch: &str,
+    ) -> Result<Option<String>, PreviewError> {
+        let url = format!("https://raw.githubusercontent.com/{owner}/{repo}/{branch}/README.md");
+        #[cfg(feature = "logging")]
+        debug!(url = %url, "Fetching GitHub README");
+
+        let start_time = Instant::now();
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .map_err(PreviewError::from_reqwest_error)?;
+
+        if response.status() == 404 {
+            return Ok(None);
+        }
+
+        if !response.status().is_success() {
+            let status = response.status().as_u16();
+            let message = format!("README request returned status: {}", response.status());
+
+            return Err(match status {
+                400..=499 => PreviewError::ClientError { status, message },
+                500..=599 => PreviewError::ServerError { status, message },
+                _ => PreviewError::HttpError { status, message },
+            });
+        }
+
+        let body = self.read_body_with_limit(response, start_time).await?;
+        Ok(readme_summary(&String::from_utf8_lossy(&body)))
+    }
+
     fn extract_meta_content(document: &Html, selector_str: &str) -> Option<String> {
         let selector = Selector::parse(selector_str).ok()?;
         document
@@ -741,3 +2165,89 @@ impl Fetcher {
             })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_retry_after() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(parse_retry_after(&headers), None);
+
+        headers.insert(RETRY_AFTER, "120".parse().unwrap());
+        assert_eq!(parse_retry_after(&headers), Some(Duration::from_secs(120)));
+
+        let later = std::time::SystemTime::now() + Duration::from_secs(30);
+        headers.insert(RETRY_AFTER, httpdate::fmt_http_date(later).parse().unwrap());
+        let delay = parse_retry_after(&headers).unwrap();
+        assert!(delay > Duration::from_secs(28) && delay <= Duration::from_secs(30));
+
+        headers.insert(RETRY_AFTER, "soon".parse().unwrap());
+        assert_eq!(parse_retry_after(&headers), None);
+    }
+
+    #[test]
+    fn test_user_agent_rotation_round_robin() {
+        let fetcher = Fetcher::with_config(FetcherConfig {
+            user_agents: vec!["agent-a".to_string(), "agent-b".to_string()],
+            ..Default::default()
+        });
+
+        assert_eq!(fetcher.next_user_agent(), Some("agent-a"));
+        assert_eq!(fetcher.next_user_agent(), Some("agent-b"));
+        assert_eq!(fetcher.next_user_agent(), Some("agent-a"));
+    }
+
+    #[cfg(feature = "youtube")]
+    #[test]
+    fn test_youtube_oembed_url_encodes_video_url() {
+        assert_eq!(
+            youtube_oembed_url("https://www.youtube.com/watch?v=dQw4w9WgXcQ&t=42"),
+            "https://www.youtube.com/oembed?url=https%3A%2F%2Fwww.youtube.com%2Fwatch%3Fv%3DdQw4w9WgXcQ%26t%3D42&format=json"
+        );
+        assert_eq!(
+            youtube_oembed_url("https://youtu.be/dQw4w9WgXcQ"),
+            "https://www.youtube.com/oembed?url=https%3A%2F%2Fyoutu.be%2FdQw4w9WgXcQ&format=json"
+        );
+    }
+
+    #[cfg(feature = "vimeo")]
+    #[test]
+    fn test_vimeo_oembed_url_uses_canonical_video_url() {
+        let expected = "https://vimeo.com/api/oembed.json?url=https%3A%2F%2Fvimeo.com%2F76979871";
+        assert_eq!(vimeo_oembed_url("https://vimeo.com/76979871"), expected);
+        assert_eq!(
+            vimeo_oembed_url("https://player.vimeo.com/video/76979871?h=8272103f6e"),
+            expected
+        );
+        assert_eq!(
+            vimeo_oembed_url("https://vimeo.com/76979871/8272103f6e"),
+            "https://vimeo.com/api/oembed.json?url=https%3A%2F%2Fvimeo.com%2F76979871%2F8272103f6e"
+        );
+    }
+
+    #[test]
+    fn test_user_agent_override_matches_host_suffix() {
+        let fetcher = Fetcher::with_config(FetcherConfig {
+            user_agent_overrides: HashMap::from([
+                ("example.com".to_string(), "generic".to_string()),
+                ("news.example.com".to_string(), "news".to_string()),
+            ]),
+            ..Default::default()
+        });
+
+        let agent = |url: &str| fetcher.user_agent_override(url);
+        assert_eq!(agent("https://example.com/"), Some("generic"));
+        assert_eq!(agent("https://WWW.Example.com/a"), Some("generic"));
+        assert_eq!(agent("https://eu.news.example.com/"), Some("news"));
+        assert_eq!(agent("https://notexample.com/"), None);
+        assert_eq!(agent("not a url"), None);
+    }
+
+    #[test]
+    fn test_empty_user_agent_pool_uses_client_default() {
+        let fetcher = Fetcher::new();
+        assert_eq!(fetcher.next_user_agent(), None);
+    }
+}
@@ -1,6 +1,6 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct GitHubRepository {
     pub name: String,
     pub full_name: String,
@@ -16,7 +16,7 @@ pub struct GitHubRepository {
     pub html_url: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct GitHubOwner {
     pub login: String,
     pub avatar_url: String,
@@ -26,14 +26,15 @@ pub fn is_github_url(url: &str) -> bool {
     url.contains("github.com")
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct GitHubBasicPreview {
     pub title: Option<String>,
     pub description: Option<String>,
     pub image_url: Option<String>,
 }
 
-#[derive(Debug, Clone)]
+/// Serialized field names are part of the public format and stay snake_case
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct GitHubDetailedInfo {
     pub full_name: String,
     pub description: String,
@@ -45,4 +46,212 @@ pub struct GitHubDetailedInfo {
     pub topics: Vec<String>,
     pub html_url: String,
     pub homepage: Option<String>,
+    /// First paragraph of the README on `default_branch` as plain text, when there is one
+    #[serde(default)]
+    pub readme_summary: Option<String>,
+}
+
+/// Reduces README markdown to its first paragraph of prose as plain text.
+///
+/// Headings, HTML blocks, badges, tables, rules and code blocks are skipped;
+/// links keep their text and emphasis/code markers are removed.
+pub fn readme_summary(markdown: &str) -> Option<String> {
+    let mut paragraph: Vec<String> = Vec::new();
+    let mut in_fence = false;
+
+    for line in markdown.lines() {
+        let line = line.trim();
+        if line.starts_with("```") || line.starts_with("~~~") {
+            if !paragraph.is_empty() {
+                break;
+            }
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+        // A setext underline turns the lines above it into a heading
+        if is_setext_underline(line) {
+            paragraph.clear();
+            continue;
+        }
+        if line.is_empty() || is_block_markup(line) {
+            if !paragraph.is_empty() {
+                break;
+            }
+            continue;
+        }
+
+        // Badge-only lines strip to nothing and don't start a paragraph
+        let text = strip_inline_markdown(line);
+        if !text.is_empty() {
+            paragraph.push(text);
+        }
+    }
+
+    let summary = paragraph.join(" ");
+    (!summary.is_empty()).then_some(summary)
+}
+
+fn is_setext_underline(line: &str) -> bool {
+    line.len() >= 2 && (line.chars().all(|c| c == '=') || line.chars().all(|c| c == '-'))
+}
+
+/// Lines that never contribute prose: headings, HTML, tables, rules and link
+/// reference definitions
+fn is_block_markup(line: &str) -> bool {
+    let is_rule = line.len() >= 3
+        && ['*', '_', '-']
+            .iter()
+            .any(|&marker| line.chars().all(|c| c == marker || c == ' '));
+    line.starts_with('#')
+        || line.starts_with('<')
+        || line.starts_with('|')
+        || is_rule
+        || (line.starts_with('[') && line.contains("]:"))
+}
+
+fn strip_inline_markdown(line: &str) -> String {
+    let chars: Vec<char> = line.trim_start_matches('>').chars().collect();
+    let mut out = String::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        match c {
+            // Images are dropped entirely
+            '!' if next == Some('[') => {
+                if let Some((_, end)) = parse_link(&chars, i + 1) {
+                    i = end;
+                    continue;
+                }
+            }
+            '[' => {
+                if let Some((text, end)) = parse_link(&chars, i) {
+                    out.push_str(&strip_inline_markdown(&text));
+                    i = end;
+                    continue;
+                }
+            }
+            '<' if next.is_some_and(|n| n.is_ascii_alphabetic() || n == '/' || n == '!') => {
+                if let Some(len) = chars[i..].iter().position(|&c| c == '>') {
+                    i += len + 1;
+                    continue;
+                }
+            }
+            '`' | '*' | '~' => {
+                i += 1;
+                continue;
+            }
+            '_' if next == Some('_') => {
+                i += 2;
+                continue;
+            }
+            _ => {}
+        }
+        out.push(c);
+        i += 1;
+    }
+
+    out.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Parses `[text](target)` or `[text][ref]` starting at the `[`, returning the
+/// link text and the index just past the link
+fn parse_link(chars: &[char], start: usize) -> Option<(String, usize)> {
+    let mut depth = 0;
+    let mut close = None;
+    for (offset, &c) in chars[start..].iter().enumerate() {
+        match c {
+            '[' => depth += 1,
+            ']' => {
+                depth -= 1;
+                if depth == 0 {
+                    close = Some(start + offset);
+                    break;
+                }
+            }
+            _ => {}
+        }
+    }
+    let close = close?;
+
+    let terminator = match chars.get(close + 1) {
+        Some('(') => ')',
+        Some('[') => ']',
+        _ => return None,
+    };
+    let target_end = close + 2 + chars[close + 2..].iter().position(|&c| c == terminator)?;
+    Some((chars[start + 1..close].iter().collect(), target_end + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detailed_info_json_round_trip() {
+        let info = GitHubDetailedInfo {
+            full_name: "rust-lang/rust".to_string(),
+            description: "Empowering everyone to build reliable and efficient software."
+                .to_string(),
+            stars_count: 100_000,
+            forks_count: 12_000,
+            open_issues_count: 9_000,
+            language: Some("Rust".to_string()),
+            default_branch: "master".to_string(),
+            topics: vec!["compiler".to_string(), "language".to_string()],
+            html_url: "https://github.com/rust-lang/rust".to_string(),
+            homepage: Some("https://www.rust-lang.org".to_string()),
+            readme_summary: Some("This is the main source code repository for Rust.".to_string()),
+        };
+
+        let json = serde_json::to_value(&info).unwrap();
+        assert_eq!(json["full_name"], "rust-lang/rust");
+        assert_eq!(json["stars_count"], 100_000);
+        assert_eq!(json["open_issues_count"], 9_000);
+        assert_eq!(json["default_branch"], "master");
+
+        let decoded: GitHubDetailedInfo = serde_json::from_value(json).unwrap();
+        assert_eq!(decoded, info);
+    }
+
+    #[test]
+    fn test_readme_summary_skips_markup() {
+        let readme = r#"<h1 align="center">url-preview</h1>
+
+[![Crates.io](https://img.shields.io/crates/v/url-preview.svg)](https://crates.io/crates/url-preview)
+[![Docs](https://docs.rs/url-preview/badge.svg)](https://docs.rs/url-preview)
+
+# url-preview
+
+A **high-performance** Rust library for generating
+[URL previews][docs] with `tokio` and <em>snake_case</em> names.
+
+## Features
+
+- Fast
+
+[docs]: https://docs.rs/url-preview
+"#;
+        assert_eq!(
+            readme_summary(readme).as_deref(),
+            Some(
+                "A high-performance Rust library for generating URL previews with tokio \
+                 and snake_case names."
+            )
+        );
+    }
+
+    #[test]
+    fn test_readme_summary_setext_heading_and_code() {
+        let readme = "Project\n=======\n\n```sh\ncargo add project\n```\n\nDoes one thing well.\n";
+        assert_eq!(
+            readme_summary(readme).as_deref(),
+            Some("Does one thing well.")
+        );
+        assert_eq!(readme_summary("# Title\n\n![logo](logo.png)\n"), None);
+    }
 }
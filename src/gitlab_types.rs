@@ -0,0 +1,146 @@
+use crate::Preview;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// Top-level GitLab paths that are not namespaces
+const RESERVED_PATHS: &[&str] = &["api", "dashboard", "explore", "help", "search", "users"];
+
+/// A project as returned by `GET /api/v4/projects/:id`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GitLabProject {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(rename = "star_count", default)]
+    pub stars: u32,
+    #[serde(rename = "forks_count", default)]
+    pub forks: u32,
+    #[serde(default)]
+    pub topics: Vec<String>,
+    #[serde(default)]
+    pub default_branch: Option<String>,
+    #[serde(default)]
+    pub web_url: Option<String>,
+    #[serde(default)]
+    pub avatar_url: Option<String>,
+}
+
+/// Matches GitLab project URLs, including subgroups such as
+/// `gitlab.com/group/subgroup/project` and sub-pages under `/-/`
+pub fn is_gitlab_url(url: &str) -> bool {
+    parse_gitlab_url(url).is_some()
+}
+
+/// Splits a GitLab project URL into its namespace (which may contain subgroups)
+/// and project name, e.g. `https://gitlab.com/group/sub/project/-/issues` ->
+/// `("group/sub", "project")`
+pub fn parse_gitlab_url(url: &str) -> Option<(String, String)> {
+    let parsed = Url::parse(url).ok()?;
+    if !matches!(parsed.host_str()?, "gitlab.com" | "www.gitlab.com") {
+        return None;
+    }
+
+    // Everything after `/-/` is a page within the project
+    let segments: Vec<&str> = parsed
+        .path_segments()?
+        .take_while(|segment| *segment != "-")
+        .filter(|segment| !segment.is_empty())
+        .collect();
+    let (project, namespace) = segments.split_last()?;
+    if namespace.is_empty() || RESERVED_PATHS.contains(&namespace[0]) {
+        return None;
+    }
+
+    let project = project.trim_end_matches(".git");
+    Some((namespace.join("/"), project.to_string()))
+}
+
+/// The API endpoint for a project, with its full path URL-encoded as GitLab
+/// requires (`group/project` -> `group%2Fproject`)
+pub fn gitlab_api_url(namespace: &str, project: &str) -> String {
+    let path: String =
+        url::form_urlencoded::byte_serialize(format!("{namespace}/{project}").as_bytes()).collect();
+    format!("https://gitlab.com/api/v4/projects/{path}")
+}
+
+impl GitLabProject {
+    pub fn into_preview(self, url: &str) -> Preview {
+        let description = self
+            .description
+            .map(|description| description.trim().to_string())
+            .filter(|description| !description.is_empty());
+
+        Preview {
+            url: url.to_string(),
+            title: Some(self.name),
+            description,
+            image_url: self.avatar_url,
+            favicon: Some("https://gitlab.com/favicon.ico".to_string()),
+            site_name: Some("GitLab".to_string()),
+            canonical_url: self.web_url,
+            keywords: self.topics,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> GitLabProject {
+        let path = format!(
+            "{}/tests/fixtures/gitlab/project.json",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_parse_gitlab_url() {
+        assert_eq!(
+            parse_gitlab_url("https://gitlab.com/gitlab-org/gitlab"),
+            Some(("gitlab-org".to_string(), "gitlab".to_string()))
+        );
+        assert_eq!(
+            parse_gitlab_url("https://gitlab.com/gitlab-org/charts/gitlab-runner/-/issues/1"),
+            Some(("gitlab-org/charts".to_string(), "gitlab-runner".to_string()))
+        );
+        assert_eq!(
+            parse_gitlab_url("https://gitlab.com/group/project.git"),
+            Some(("group".to_string(), "project".to_string()))
+        );
+        assert!(!is_gitlab_url("https://gitlab.com/gitlab-org"));
+        assert!(!is_gitlab_url("https://gitlab.com/explore/projects"));
+        assert!(!is_gitlab_url("https://gitlab.example.com/group/project"));
+    }
+
+    #[test]
+    fn test_gitlab_api_url_encodes_path() {
+        assert_eq!(
+            gitlab_api_url("gitlab-org/charts", "gitlab-runner"),
+            "https://gitlab.com/api/v4/projects/gitlab-org%2Fcharts%2Fgitlab-runner"
+        );
+    }
+
+    #[test]
+    fn test_project_json_and_preview() {
+        let project = sample();
+        assert_eq!(project.name, "GitLab Runner");
+        assert_eq!(project.stars, 2431);
+        assert_eq!(project.forks, 4120);
+        assert_eq!(project.default_branch.as_deref(), Some("main"));
+        assert_eq!(project.topics, vec!["ci", "go"]);
+
+        let url = "https://gitlab.com/gitlab-org/gitlab-runner";
+        let preview = project.into_preview(url);
+        assert_eq!(preview.title.as_deref(), Some("GitLab Runner"));
+        assert_eq!(
+            preview.description.as_deref(),
+            Some("GitLab Runner is the open source project used to run your CI/CD jobs.")
+        );
+        assert_eq!(preview.site_name.as_deref(), Some("GitLab"));
+        assert_eq!(preview.canonical_url.as_deref(), Some(url));
+        assert_eq!(preview.keywords, vec!["ci", "go"]);
+    }
+}
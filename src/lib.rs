@@ -82,10 +82,24 @@ mod extractor;
 mod fetcher;
 #[cfg(feature = "github")]
 mod github_types;
+#[cfg(feature = "gitlab")]
+mod gitlab_types;
 #[cfg(feature = "logging")]
 mod logging;
+#[cfg(feature = "mastodon")]
+mod mastodon_types;
+#[cfg(feature = "metrics")]
+mod metrics;
+mod observer;
+#[cfg(feature = "pdf")]
+mod pdf;
 mod preview_generator;
 mod preview_service;
+#[cfg(feature = "prometheus")]
+mod prometheus;
+#[cfg(feature = "reddit")]
+mod reddit_types;
+mod robots;
 mod security;
 mod utils;
 
@@ -102,25 +116,42 @@ mod llm_providers;
 mod llm_config;
 
 #[cfg(feature = "cache")]
-pub use cache::Cache;
+pub use cache::{Cache, CacheConfig, CacheStats, PreviewCache};
 pub use error::PreviewError;
-pub use extractor::MetadataExtractor;
-pub use fetcher::{FetchResult, Fetcher, FetcherConfig};
+pub use extractor::{
+    ExtractedMetadata, ExtractorConfig, LinkInfo, MetadataExtractor, OgImage, VideoInfo,
+};
+pub use fetcher::{
+    FetchResponse, FetchResult, Fetcher, FetcherConfig, HttpMeta, ImageInfo, PdfInfo, ProbeResult,
+    ProxyConfig, RawResponse, RetryConfig,
+};
 #[cfg(feature = "github")]
 pub use github_types::{is_github_url, GitHubBasicPreview, GitHubDetailedInfo, GitHubRepository};
+#[cfg(feature = "gitlab")]
+pub use gitlab_types::{is_gitlab_url, GitLabProject};
 #[cfg(feature = "logging")]
 pub use logging::{log_error_card, log_preview_card, setup_logging, LogConfig, LogLevelGuard};
+#[cfg(feature = "mastodon")]
+pub use mastodon_types::{is_mastodon_url, MastodonStatus};
+pub use observer::{FetchOutcome, MetricsObserver};
 pub use preview_generator::{CacheStrategy, UrlPreviewGenerator};
-pub use preview_service::{PreviewService, PreviewServiceConfig, MAX_CONCURRENT_REQUESTS};
+pub use preview_service::{
+    PreviewService, PreviewServiceBuilder, PreviewServiceConfig, MAX_CONCURRENT_REQUESTS,
+};
+#[cfg(feature = "prometheus")]
+pub use prometheus::PrometheusMetrics;
+#[cfg(feature = "reddit")]
+pub use reddit_types::{is_reddit_url, RedditPost};
 pub use security::{ContentLimits, UrlValidationConfig, UrlValidator};
+pub use utils::decode_html;
 
 #[cfg(feature = "browser")]
-pub use mcp_client::{McpClient, McpConfig, McpTransport, BrowserUsagePolicy};
+pub use mcp_client::{McpClient, McpConfig, McpTransport, BrowserUsagePolicy, WaitStrategy};
 #[cfg(feature = "browser")]
 pub use browser_fetcher::{BrowserFetcher, BrowserPreviewService};
 
 #[cfg(feature = "llm")]
-pub use llm_extractor::{LLMExtractor, LLMExtractorConfig, ContentFormat, ProcessedContent, ExtractionResult, LLMProvider, TokenUsage};
+pub use llm_extractor::{LLMExtractor, LLMExtractorConfig, ContentFormat, ContentPreprocessor, ProcessedContent, ExtractionResult, LLMProvider, TokenUsage};
 #[cfg(feature = "llm")]
 pub use llm_providers::MockProvider;
 #[cfg(feature = "llm")]
@@ -136,7 +167,7 @@ pub use llm_providers::LocalProvider;
 #[cfg(feature = "llm")]
 pub use llm_config::{ApiKeyValidator, LLMConfig};
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
 pub struct Preview {
     pub url: String,
     pub title: Option<String>,
@@ -144,6 +175,96 @@ pub struct Preview {
     pub image_url: Option<String>,
     pub favicon: Option<String>,
     pub site_name: Option<String>,
+    /// Absolute canonical URL from `rel=canonical` or `og:url`
+    #[serde(default)]
+    pub canonical_url: Option<String>,
+    /// All `og:image` entries with their width/height/alt, in document order
+    #[serde(default)]
+    pub og_images: Vec<OgImage>,
+    /// Absolute audio URL from `og:audio` or a `rel=alternate` audio enclosure
+    #[serde(default)]
+    pub audio_url: Option<String>,
+    /// MIME type of `audio_url`, e.g. `audio/mpeg`
+    #[serde(default)]
+    pub audio_type: Option<String>,
+    /// First entry of `authors`
+    #[serde(default)]
+    pub author: Option<String>,
+    /// All authors in document order, from JSON-LD, `article:author` or `meta[name=author]`
+    #[serde(default)]
+    pub authors: Vec<String>,
+    /// URL the page was actually served from after following redirects
+    #[serde(default)]
+    pub resolved_url: Option<String>,
+    /// Publication time as declared by `article:published_time` or JSON-LD `datePublished`
+    #[serde(default)]
+    pub published_at: Option<String>,
+    /// Last modification time from `article:modified_time` or JSON-LD `dateModified`
+    #[serde(default)]
+    pub modified_at: Option<String>,
+    /// Declared keywords from `meta[name=keywords]`, `article:tag` and JSON-LD
+    /// `keywords`, deduplicated case-insensitively in first-seen order
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    /// Content language from `og:locale`, `<html lang>` or `Content-Language`,
+    /// lowercased with `-` separators (e.g. `en-us`)
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Absolute URLs of every `og:image` and `twitter:image` in document order,
+    /// without duplicates. Dimensions of the Open Graph ones are in `og_images`.
+    #[serde(default)]
+    pub images: Vec<String>,
+    /// `<meta name="theme-color">` normalized to lowercase `#rrggbb`
+    #[serde(default)]
+    pub theme_color: Option<String>,
+    /// Absolute URL of an embeddable player, from `og:video`, `twitter:player` or
+    /// `og:audio`, in that order of preference
+    #[serde(default)]
+    pub embed_url: Option<String>,
+    /// `og:type` of media pages (`music.*` and `video.*`), e.g. `music.song`, or
+    /// the MIME type when the URL is an image itself
+    #[serde(default)]
+    pub media_type: Option<String>,
+    /// Structured `og:video` properties, see [`VideoInfo`]
+    #[serde(default)]
+    pub video: Option<VideoInfo>,
+    /// Caching headers of the page response, when the fetcher was configured with
+    /// `capture_http_metadata`
+    #[serde(default)]
+    pub http_metadata: Option<HttpMeta>,
+}
+
+impl Preview {
+    /// Whether none of the title, description and image were found
+    pub fn is_empty(&self) -> bool {
+        self.title.is_none() && self.description.is_none() && self.image_url.is_none()
+    }
+
+    /// Fraction of the key preview fields that are populated, from 0.0 to 1.0.
+    /// The key fields are the title, description, image, site name and favicon.
+    pub fn completeness(&self) -> f32 {
+        let key_fields = [
+            self.title.is_some(),
+            self.description.is_some(),
+            self.image_url.is_some(),
+            self.site_name.is_some(),
+            self.favicon.is_some(),
+        ];
+        let populated = key_fields.iter().filter(|&&present| present).count();
+        populated as f32 / key_fields.len() as f32
+    }
+}
+
+/// A [`Preview`] together with details about how it was obtained
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct PreviewWithMeta {
+    pub preview: Preview,
+    /// HTTP status of the page fetch; `None` for cache hits and previews that did
+    /// not come from a single page fetch (GitHub API, browser rendering)
+    pub status: Option<u16>,
+    /// URL the preview was fetched from after redirects
+    pub final_url: String,
+    pub from_cache: bool,
 }
 
 #[async_trait]
@@ -151,6 +272,20 @@ pub trait PreviewGenerator {
     async fn generate_preview(&self, url: &str) -> Result<Preview, PreviewError>;
 }
 
+/// Custom preview generation for the URLs of a particular site, registered with
+/// [`PreviewServiceConfig::with_handler`]. Registered handlers are tried in order,
+/// before the built-in Twitter, GitHub and other platform handling. Their previews
+/// are cached and reported to the metrics observer like the default generator's,
+/// with no HTTP status and the requested URL as the final URL.
+#[async_trait]
+pub trait PlatformHandler: Send + Sync {
+    fn matches(&self, url: &url::Url) -> bool;
+
+    /// `fetcher` is the service's default fetcher, with its URL validation and
+    /// content limits
+    async fn generate(&self, url: &str, fetcher: &Fetcher) -> Result<Preview, PreviewError>;
+}
+
 #[cfg(feature = "twitter")]
 pub fn is_twitter_url(url: &str) -> bool {
     url.contains("twitter.com") || url.contains("x.com")
@@ -160,3 +295,117 @@ pub fn is_twitter_url(url: &str) -> bool {
 pub fn is_twitter_url(_url: &str) -> bool {
     false
 }
+
+/// Matches YouTube video links: `youtube.com/watch?v=`, `/shorts/`, `/embed/`,
+/// `/live/` and `youtu.be/` short links
+#[cfg(feature = "youtube")]
+pub fn is_youtube_url(url: &str) -> bool {
+    let Ok(parsed) = url::Url::parse(url) else {
+        return false;
+    };
+    let host = parsed.host_str().unwrap_or("").trim_start_matches("www.");
+    let mut segments = parsed.path_segments().into_iter().flatten();
+    match host {
+        "youtu.be" => segments.next().is_some_and(|id| !id.is_empty()),
+        "youtube.com" | "m.youtube.com" | "music.youtube.com" => match segments.next() {
+            Some("watch") => parsed.query_pairs().any(|(key, value)| key == "v" && !value.is_empty()),
+            Some("shorts" | "embed" | "live") => segments.next().is_some_and(|id| !id.is_empty()),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+#[cfg(not(feature = "youtube"))]
+pub fn is_youtube_url(_url: &str) -> bool {
+    false
+}
+
+/// Matches Vimeo video links: `vimeo.com/{id}` (optionally followed by a private
+/// link hash) and `player.vimeo.com/video/{id}`
+#[cfg(feature = "vimeo")]
+pub fn is_vimeo_url(url: &str) -> bool {
+    vimeo_video_id(url).is_some()
+}
+
+#[cfg(not(feature = "vimeo"))]
+pub fn is_vimeo_url(_url: &str) -> bool {
+    false
+}
+
+/// The numeric video id of a Vimeo link accepted by [`is_vimeo_url`]
+#[cfg(feature = "vimeo")]
+pub(crate) fn vimeo_video_id(url: &str) -> Option<String> {
+    let parsed = url::Url::parse(url).ok()?;
+    let host = parsed.host_str()?.trim_start_matches("www.");
+    let mut segments = parsed.path_segments()?;
+    let id = match host {
+        "vimeo.com" => segments.next()?,
+        "player.vimeo.com" if segments.next() == Some("video") => segments.next()?,
+        _ => return None,
+    };
+    (!id.is_empty() && id.bytes().all(|b| b.is_ascii_digit())).then(|| id.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preview_completeness() {
+        let empty = Preview::default();
+        assert!(empty.is_empty());
+        assert_eq!(empty.completeness(), 0.0);
+
+        let partial = Preview {
+            title: Some("Title".into()),
+            site_name: Some("Example".into()),
+            ..Default::default()
+        };
+        assert!(!partial.is_empty());
+        assert_eq!(partial.completeness(), 0.4);
+
+        let full = Preview {
+            title: Some("Title".into()),
+            description: Some("Description".into()),
+            image_url: Some("https://example.com/card.png".into()),
+            site_name: Some("Example".into()),
+            favicon: Some("https://example.com/favicon.ico".into()),
+            ..Default::default()
+        };
+        assert!(!full.is_empty());
+        assert_eq!(full.completeness(), 1.0);
+    }
+
+    #[cfg(feature = "youtube")]
+    #[test]
+    fn test_is_youtube_url() {
+        assert!(is_youtube_url("https://www.youtube.com/watch?v=5C_HPTJg5ek"));
+        assert!(is_youtube_url("https://m.youtube.com/watch?feature=share&v=5C_HPTJg5ek"));
+        assert!(is_youtube_url("https://youtu.be/5C_HPTJg5ek"));
+        assert!(is_youtube_url("https://youtu.be/5C_HPTJg5ek?t=30"));
+        assert!(is_youtube_url("https://www.youtube.com/shorts/abc123"));
+        assert!(!is_youtube_url("https://www.youtube.com/@Fireship"));
+        assert!(!is_youtube_url("https://www.youtube.com/watch"));
+        assert!(!is_youtube_url("https://youtu.be/"));
+        assert!(!is_youtube_url("https://notyoutube.com/watch?v=5C_HPTJg5ek"));
+    }
+
+    #[cfg(feature = "vimeo")]
+    #[test]
+    fn test_is_vimeo_url() {
+        assert_eq!(
+            vimeo_video_id("https://vimeo.com/76979871").as_deref(),
+            Some("76979871")
+        );
+        assert_eq!(
+            vimeo_video_id("https://player.vimeo.com/video/76979871?h=8272103f6e").as_deref(),
+            Some("76979871")
+        );
+        assert!(is_vimeo_url("https://www.vimeo.com/76979871/8272103f6e"));
+        assert!(!is_vimeo_url("https://vimeo.com/staff"));
+        assert!(!is_vimeo_url("https://vimeo.com/channels/staffpicks"));
+        assert!(!is_vimeo_url("https://player.vimeo.com/api/player.js"));
+        assert!(!is_vimeo_url("https://notvimeo.com/76979871"));
+    }
+}
@@ -8,11 +8,14 @@ use crate::{PreviewError, Fetcher};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::HashMap;
+use futures::{Stream, StreamExt};
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
 
 #[cfg(feature = "cache")]
 use crate::Cache;
+#[cfg(feature = "browser")]
+use crate::BrowserFetcher;
 
 /// Configuration for LLM extraction
 #[derive(Clone, Debug)]
@@ -21,8 +24,16 @@ pub struct LLMExtractorConfig {
     pub format: ContentFormat,
     /// Whether to clean HTML before processing
     pub clean_html: bool,
+    /// Scope preprocessing to `[itemprop=articleBody]` or `<article>` when present
+    pub prefer_article_body: bool,
     /// Maximum content length to send to LLM
     pub max_content_length: usize,
+    /// Maximum size in bytes of the serialized LLM output
+    pub max_output_bytes: usize,
+    /// Maximum tokens the model may generate; `None` uses the provider's default
+    pub max_tokens: Option<u32>,
+    /// Sampling temperature; `None` uses the provider's default
+    pub temperature: Option<f32>,
     /// Model-specific parameters
     pub model_params: HashMap<String, Value>,
 }
@@ -32,7 +43,11 @@ impl Default for LLMExtractorConfig {
         Self {
             format: ContentFormat::Html,
             clean_html: true,
+            prefer_article_body: true,
             max_content_length: 50_000, // 50KB default
+            max_output_bytes: 256 * 1024, // 256KB default
+            max_tokens: None,
+            temperature: None,
             model_params: HashMap::new(),
         }
     }
@@ -95,7 +110,21 @@ pub trait LLMProvider: Send + Sync {
         config: &LLMExtractorConfig,
     ) -> Result<Value, PreviewError>;
     
+    /// Like [`generate`](Self::generate), also returning the token usage of this
+    /// call. Providers that track usage override this; the default reports none.
+    async fn generate_with_usage(
+        &self,
+        prompt: String,
+        schema: Value,
+        config: &LLMExtractorConfig,
+    ) -> Result<(Value, Option<TokenUsage>), PreviewError> {
+        Ok((self.generate(prompt, schema, config).await?, None))
+    }
+    
     /// Stream structured data from content (optional)
+    ///
+    /// Items are either `Value::String` fragments of the JSON text as the model
+    /// produces it, or already-complete JSON values.
     async fn stream(
         &self,
         _prompt: String,
@@ -104,6 +133,17 @@ pub trait LLMProvider: Send + Sync {
     ) -> Result<Box<dyn futures::Stream<Item = Result<Value, PreviewError>> + Send + Unpin>, PreviewError> {
         Err(PreviewError::UnsupportedOperation("Streaming not supported by this provider".into()))
     }
+    
+    /// Generate structured data from a chat message mixing text and image parts
+    /// (optional, for vision-capable models)
+    async fn generate_multimodal(
+        &self,
+        _message: Value,
+        _schema: Value,
+        _config: &LLMExtractorConfig,
+    ) -> Result<Value, PreviewError> {
+        Err(PreviewError::UnsupportedOperation("Image input not supported by this provider".into()))
+    }
 }
 
 /// Main LLM extractor
@@ -142,6 +182,13 @@ impl LLMExtractor {
         }
     }
     
+    /// Capture screenshots with `browser` for `ContentFormat::Image`
+    #[cfg(feature = "browser")]
+    pub fn with_browser(mut self, browser: Arc<BrowserFetcher>) -> Self {
+        self.preprocessor = self.preprocessor.with_browser(browser);
+        self
+    }
+    
     /// Set cache
     #[cfg(feature = "cache")]
     pub fn with_cache(mut self, cache: Arc<Cache>) -> Self {
@@ -177,17 +224,24 @@ impl LLMExtractor {
         };
         
         // Preprocess content
-        let processed = self.preprocessor.preprocess(&html, &self.config).await?;
+        let processed = self.preprocessor.preprocess_url(url, &html, &self.config).await?;
         
         // Generate schema
         let schema = schemars::schema_for!(T);
         let schema_json = serde_json::to_value(&schema)?;
         
-        // Build prompt
-        let prompt = self.build_prompt(&processed, &schema_json)?;
+        // Call LLM, attaching screenshots as image parts
+        let (result, usage) = if processed.format == ContentFormat::Image {
+            let message = self.build_multimodal_message(&processed, &schema_json)?;
+            let result = self.provider.generate_multimodal(message, schema_json, &self.config).await?;
+            (result, None)
+        } else {
+            let prompt = self.build_prompt(&processed, &schema_json)?;
+            self.provider.generate_with_usage(prompt, schema_json, &self.config).await?
+        };
         
-        // Call LLM
-        let result = self.provider.generate(prompt, schema_json, &self.config).await?;
+        // Reject oversized output before deserializing it
+        self.check_output_size(&result)?;
         
         // Parse result
         let extracted: T = serde_json::from_value(result)?;
@@ -203,6 +257,7 @@ impl LLMExtractor {
                 image_url: None,
                 site_name: None,
                 favicon: None,
+                ..Default::default()
             };
             cache.set(cache_key, preview).await;
         }
@@ -210,10 +265,68 @@ impl LLMExtractor {
         Ok(ExtractionResult {
             data: extracted,
             model: self.provider.name().to_string(),
-            usage: None, // TODO: Get from provider
+            usage,
         })
     }
     
+    /// Extract structured data from a URL, yielding each object as soon as the
+    /// provider has streamed it completely.
+    ///
+    /// Providers without streaming support yield a single
+    /// `PreviewError::UnsupportedOperation`.
+    pub fn extract_stream<'a, T>(
+        &'a self,
+        url: &'a str,
+        fetcher: &'a Fetcher,
+    ) -> impl Stream<Item = Result<T, PreviewError>> + 'a
+    where
+        T: serde::de::DeserializeOwned + schemars::JsonSchema + 'a,
+    {
+        let max_output_bytes = self.config.max_output_bytes;
+        futures::stream::once(self.open_stream::<T>(url, fetcher))
+            .map(move |opened| match opened {
+                Ok(chunks) => decode_stream::<T>(chunks, max_output_bytes).left_stream(),
+                Err(e) => futures::stream::once(futures::future::ready(Err(e))).right_stream(),
+            })
+            .flatten()
+    }
+
+    /// Fetch and preprocess `url`, then start the provider's stream
+    async fn open_stream<T>(
+        &self,
+        url: &str,
+        fetcher: &Fetcher,
+    ) -> Result<Box<dyn Stream<Item = Result<Value, PreviewError>> + Send + Unpin>, PreviewError>
+    where
+        T: schemars::JsonSchema,
+    {
+        let html = match fetcher.fetch(url).await? {
+            crate::FetchResult::Html(h) => h,
+            _ => return Err(PreviewError::InvalidContentType("Expected HTML".to_string())),
+        };
+
+        if self.config.format == ContentFormat::Image {
+            return Err(PreviewError::UnsupportedOperation("Streaming image extraction".into()));
+        }
+        let processed = self.preprocessor.preprocess(&html, &self.config).await?;
+        let schema_json = serde_json::to_value(schemars::schema_for!(T))?;
+        let prompt = self.build_prompt(&processed, &schema_json)?;
+
+        self.provider.stream(prompt, schema_json, &self.config).await
+    }
+
+    /// Ensure the raw LLM output stays within `max_output_bytes`
+    fn check_output_size(&self, value: &Value) -> Result<(), PreviewError> {
+        let size = serde_json::to_string(value)?.len();
+        if size > self.config.max_output_bytes {
+            return Err(PreviewError::OutputTooLarge {
+                size,
+                limit: self.config.max_output_bytes,
+            });
+        }
+        Ok(())
+    }
+    
     /// Build prompt for LLM
     fn build_prompt(&self, content: &ProcessedContent, schema: &Value) -> Result<String, PreviewError> {
         let schema_str = serde_json::to_string_pretty(schema)?;
@@ -225,6 +338,16 @@ impl LLMExtractor {
             ContentFormat::Image => "image",
         };
         
+        // The screenshot travels as a separate message part
+        if content.format == ContentFormat::Image {
+            return Ok(format!(
+                "Extract structured data from the attached screenshot of a web page according to this schema:\n\n\
+                Schema:\n```json\n{}\n```\n\n\
+                Extract the data and return it as a valid JSON object matching the schema.",
+                schema_str
+            ));
+        }
+        
         Ok(format!(
             "Extract structured data from the following {} content according to this schema:\n\n\
             Schema:\n```json\n{}\n```\n\n\
@@ -235,23 +358,181 @@ impl LLMExtractor {
             content.content
         ))
     }
+    
+    /// Build an OpenAI-style user message carrying the prompt and the screenshot
+    fn build_multimodal_message(&self, content: &ProcessedContent, schema: &Value) -> Result<Value, PreviewError> {
+        let mime_type = content
+            .metadata
+            .get("mime_type")
+            .map(String::as_str)
+            .unwrap_or("image/png");
+        
+        Ok(serde_json::json!({
+            "role": "user",
+            "content": [
+                { "type": "text", "text": self.build_prompt(content, schema)? },
+                {
+                    "type": "image_url",
+                    "image_url": { "url": format!("data:{};base64,{}", mime_type, content.content) }
+                }
+            ]
+        }))
+    }
+}
+
+/// Turn a provider's chunk stream into a stream of deserialized objects.
+///
+/// Text fragments are buffered until they form a complete JSON object; the
+/// buffered remainder may not grow beyond `max_output_bytes`.
+fn decode_stream<T>(
+    chunks: Box<dyn Stream<Item = Result<Value, PreviewError>> + Send + Unpin>,
+    max_output_bytes: usize,
+) -> impl Stream<Item = Result<T, PreviewError>>
+where
+    T: serde::de::DeserializeOwned,
+{
+    let state = (chunks, String::new(), VecDeque::<Result<T, PreviewError>>::new(), false);
+    futures::stream::unfold(state, move |(mut chunks, mut buffer, mut pending, mut finished)| async move {
+        loop {
+            if let Some(item) = pending.pop_front() {
+                return Some((item, (chunks, buffer, pending, finished)));
+            }
+            if finished {
+                return None;
+            }
+
+            match chunks.next().await {
+                Some(Ok(Value::String(text))) => {
+                    buffer.push_str(&text);
+                    match drain_json_objects(&mut buffer) {
+                        Ok(values) => pending.extend(
+                            values.into_iter().map(|v| serde_json::from_value::<T>(v).map_err(Into::into)),
+                        ),
+                        Err(e) => {
+                            pending.push_back(Err(e.into()));
+                            finished = true;
+                        }
+                    }
+                    if buffer.len() > max_output_bytes {
+                        pending.push_back(Err(PreviewError::OutputTooLarge {
+                            size: buffer.len(),
+                            limit: max_output_bytes,
+                        }));
+                        finished = true;
+                    }
+                }
+                Some(Ok(value)) => pending.push_back(serde_json::from_value(value).map_err(Into::into)),
+                Some(Err(e)) => {
+                    pending.push_back(Err(e));
+                    finished = true;
+                }
+                None => {
+                    if !buffer.is_empty() {
+                        pending.push_back(Err(PreviewError::ParseError(
+                            "LLM stream ended inside a JSON object".to_string(),
+                        )));
+                    }
+                    finished = true;
+                }
+            }
+        }
+    })
+}
+
+/// Remove and return every complete JSON object at the front of `buffer`.
+///
+/// Text between objects (code fences, commas, prose) is discarded; an
+/// incomplete trailing object is left in place for the next chunk.
+fn drain_json_objects(buffer: &mut String) -> Result<Vec<Value>, serde_json::Error> {
+    let mut values = Vec::new();
+    loop {
+        let Some(start) = buffer.find('{') else {
+            buffer.clear();
+            break;
+        };
+        buffer.drain(..start);
+
+        let (next, consumed) = {
+            let mut iter = serde_json::Deserializer::from_str(buffer).into_iter::<Value>();
+            let next = iter.next();
+            (next, iter.byte_offset())
+        };
+        match next {
+            Some(Ok(value)) => {
+                buffer.drain(..consumed);
+                values.push(value);
+            }
+            Some(Err(e)) if e.is_eof() => break,
+            Some(Err(e)) => return Err(e),
+            None => break,
+        }
+    }
+    Ok(values)
 }
 
 /// Content preprocessor
 pub struct ContentPreprocessor {
     html_cleaner: HtmlCleaner,
+    /// Screenshot source for `ContentFormat::Image`
+    #[cfg(feature = "browser")]
+    browser: Option<Arc<BrowserFetcher>>,
 }
 
 impl ContentPreprocessor {
     pub fn new() -> Self {
         Self {
             html_cleaner: HtmlCleaner::new(),
+            #[cfg(feature = "browser")]
+            browser: None,
         }
     }
     
+    /// Capture screenshots with `browser` for `ContentFormat::Image`
+    #[cfg(feature = "browser")]
+    pub fn with_browser(mut self, browser: Arc<BrowserFetcher>) -> Self {
+        self.browser = Some(browser);
+        self
+    }
+    
     /// Preprocess HTML content
     pub async fn preprocess(&self, html: &str, config: &LLMExtractorConfig) -> Result<ProcessedContent, PreviewError> {
-        let processed_html = if config.clean_html {
+        if config.format == ContentFormat::Image {
+            return Err(PreviewError::UnsupportedOperation(
+                "Image format requires the page URL; use preprocess_url".into(),
+            ));
+        }
+        self.preprocess_url("", html, config).await
+    }
+    
+    /// Preprocess the content of `url`. `ContentFormat::Image` screenshots the page
+    /// with the configured browser and returns it base64-encoded.
+    pub async fn preprocess_url(
+        &self,
+        url: &str,
+        html: &str,
+        config: &LLMExtractorConfig,
+    ) -> Result<ProcessedContent, PreviewError> {
+        if config.format == ContentFormat::Image {
+            return self.capture_screenshot(url).await;
+        }
+        
+        let mut metadata = HashMap::new();
+        
+        let scoped_html = if config.prefer_article_body {
+            self.select_content_root(html)
+        } else {
+            None
+        };
+        let html = match &scoped_html {
+            Some((root, scoped)) => {
+                metadata.insert("content_root".to_string(), root.to_string());
+                scoped.as_str()
+            }
+            None => html,
+        };
+        
+        // Cleaning flattens the markup, so the Markdown converter drops noise itself
+        let processed_html = if config.clean_html && config.format != ContentFormat::Markdown {
             self.html_cleaner.clean(html)?
         } else {
             html.to_string()
@@ -259,11 +540,9 @@ impl ContentPreprocessor {
         
         let content = match config.format {
             ContentFormat::Html => processed_html,
-            ContentFormat::Markdown => self.convert_to_markdown(&processed_html)?,
+            ContentFormat::Markdown => self.convert_to_markdown(&processed_html, config.clean_html)?,
             ContentFormat::Text => self.extract_text(&processed_html)?,
-            ContentFormat::Image => {
-                return Err(PreviewError::UnsupportedOperation("Image format not yet implemented".into()));
-            }
+            ContentFormat::Image => unreachable!("image content is captured before HTML processing"),
         };
         
         // Truncate if needed
@@ -276,43 +555,62 @@ impl ContentPreprocessor {
         Ok(ProcessedContent {
             content,
             format: config.format.clone(),
-            metadata: HashMap::new(),
+            metadata,
         })
     }
     
-    /// Convert HTML to Markdown
-    fn convert_to_markdown(&self, html: &str) -> Result<String, PreviewError> {
-        // Simple conversion - in production, use a proper HTML to Markdown converter
-        use scraper::{Html, Selector};
-        
-        let document = Html::parse_document(html);
-        let mut markdown = String::new();
-        
-        // Extract title
-        if let Ok(title_selector) = Selector::parse("title") {
-            if let Some(title) = document.select(&title_selector).next() {
-                markdown.push_str(&format!("# {}\n\n", title.text().collect::<String>()));
-            }
-        }
+    /// Screenshot `url` into base64 image content
+    #[cfg(feature = "browser")]
+    async fn capture_screenshot(&self, url: &str) -> Result<ProcessedContent, PreviewError> {
+        let Some(browser) = &self.browser else {
+            return Err(PreviewError::UnsupportedOperation(
+                "Image format requires a browser screenshot source".into(),
+            ));
+        };
         
-        // Extract headings
-        for i in 1..=6 {
-            if let Ok(selector) = Selector::parse(&format!("h{}", i)) {
-                for element in document.select(&selector) {
-                    let heading_level = "#".repeat(i);
-                    markdown.push_str(&format!("{} {}\n\n", heading_level, element.text().collect::<String>()));
-                }
-            }
-        }
+        let screenshot = browser.take_screenshot(url).await?;
+        let metadata = HashMap::from([
+            ("content_type".to_string(), "image".to_string()),
+            ("mime_type".to_string(), "image/png".to_string()),
+            ("encoding".to_string(), "base64".to_string()),
+            ("source_url".to_string(), url.to_string()),
+        ]);
         
-        // Extract paragraphs
-        if let Ok(p_selector) = Selector::parse("p") {
-            for element in document.select(&p_selector) {
-                markdown.push_str(&format!("{}\n\n", element.text().collect::<String>()));
-            }
-        }
+        Ok(ProcessedContent {
+            content: base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &screenshot),
+            format: ContentFormat::Image,
+            metadata,
+        })
+    }
+    
+    #[cfg(not(feature = "browser"))]
+    async fn capture_screenshot(&self, _url: &str) -> Result<ProcessedContent, PreviewError> {
+        Err(PreviewError::UnsupportedOperation(
+            "Image format requires the browser feature".into(),
+        ))
+    }
+    
+    /// Find the main content element, preferring schema.org `articleBody` over `<article>`.
+    /// Returns the matched selector and the element's outer HTML.
+    fn select_content_root(&self, html: &str) -> Option<(&'static str, String)> {
+        use scraper::{Html, Selector};
         
-        Ok(markdown)
+        let document = Html::parse_document(html);
+        ["[itemprop='articleBody']", "article"]
+            .into_iter()
+            .find_map(|root| {
+                let selector = Selector::parse(root).ok()?;
+                document
+                    .select(&selector)
+                    .find(|el| !el.text().collect::<String>().trim().is_empty())
+                    .map(|el| (root, el.html()))
+            })
+    }
+    
+    /// Convert HTML to Markdown, optionally dropping the elements `HtmlCleaner` removes
+    fn convert_to_markdown(&self, html: &str, clean: bool) -> Result<String, PreviewError> {
+        let skipped: &[&str] = if clean { NOISE_ELEMENTS } else { &[] };
+        Ok(MarkdownConverter::new(skipped).convert(html))
     }
     
     /// Extract clean text from HTML
@@ -332,6 +630,15 @@ impl ContentPreprocessor {
     }
 }
 
+/// Elements dropped entirely when cleaning HTML
+const NOISE_ELEMENTS: &[&str] = &[
+    "script", "style", "noscript", "iframe", "object", "embed", 
+    "form", "input", "button", "select", "textarea", "option",
+    "nav", "header", "footer", "aside", "menu", "menuitem",
+    "audio", "video", "source", "track", "canvas", "svg",
+    "meta", "link", "base", "title"
+];
+
 /// HTML cleaner
 struct HtmlCleaner;
 
@@ -348,13 +655,7 @@ impl HtmlCleaner {
         let document = Html::parse_document(html);
         
         // Elements to remove completely
-        let remove_selectors = vec![
-            "script", "style", "noscript", "iframe", "object", "embed", 
-            "form", "input", "button", "select", "textarea", "option",
-            "nav", "header", "footer", "aside", "menu", "menuitem",
-            "audio", "video", "source", "track", "canvas", "svg",
-            "meta", "link", "base", "title"
-        ];
+        let remove_selectors = NOISE_ELEMENTS;
         
         // Elements to keep but clean attributes (for future use)
         let _content_selectors = vec![
@@ -373,10 +674,10 @@ impl HtmlCleaner {
         // Process the HTML to extract clean content
         if let Ok(body_selector) = Selector::parse("body") {
             if let Some(body) = document.select(&body_selector).next() {
-                cleaned_html = self.extract_clean_content(body, &remove_selectors, &mut removed_tags);
+                cleaned_html = self.extract_clean_content(body, remove_selectors, &mut removed_tags);
             } else {
                 // If no body tag, process the entire document
-                cleaned_html = self.extract_clean_content(document.root_element(), &remove_selectors, &mut removed_tags);
+                cleaned_html = self.extract_clean_content(document.root_element(), remove_selectors, &mut removed_tags);
             }
         }
         
@@ -457,6 +758,324 @@ impl HtmlCleaner {
     }
 }
 
+/// DOM-walking HTML to Markdown converter
+///
+/// Output is deterministic: whitespace is collapsed, blocks are separated by one
+/// blank line and list items are kept tight.
+struct MarkdownConverter<'a> {
+    skipped: &'a [&'a str],
+}
+
+impl<'a> MarkdownConverter<'a> {
+    fn new(skipped: &'a [&'a str]) -> Self {
+        Self { skipped }
+    }
+    
+    fn convert(&self, html: &str) -> String {
+        use scraper::{Html, Selector};
+        
+        let document = Html::parse_document(html);
+        let mut out = String::new();
+        
+        if let Ok(title_selector) = Selector::parse("title") {
+            if let Some(title) = document.select(&title_selector).next() {
+                let title = title.text().collect::<String>();
+                if !title.trim().is_empty() {
+                    out.push_str("# ");
+                    push_text(&mut out, &title);
+                    out.push_str("\n\n");
+                }
+            }
+        }
+        
+        let body = Selector::parse("body")
+            .ok()
+            .and_then(|selector| document.select(&selector).next())
+            .unwrap_or_else(|| document.root_element());
+        self.render_children(body, &mut out);
+        
+        normalize_markdown(&out)
+    }
+    
+    fn render_children(&self, element: scraper::ElementRef, out: &mut String) {
+        use scraper::{ElementRef, Node};
+        
+        for child in element.children() {
+            match child.value() {
+                Node::Text(text) => push_text(out, text),
+                Node::Element(_) => {
+                    if let Some(child) = ElementRef::wrap(child) {
+                        self.render_element(child, out);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    
+    /// Render `element`'s children into a fresh buffer
+    fn render_fragment(&self, element: scraper::ElementRef) -> String {
+        let mut fragment = String::new();
+        self.render_children(element, &mut fragment);
+        fragment
+    }
+    
+    fn render_element(&self, element: scraper::ElementRef, out: &mut String) {
+        let tag = element.value().name();
+        if self.skipped.contains(&tag) {
+            return;
+        }
+        
+        match tag {
+            "head" | "script" | "style" | "noscript" | "template" => {}
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                let level = usize::from(tag.as_bytes()[1] - b'0');
+                let text = self.render_fragment(element);
+                let text = collapse_whitespace(&text);
+                if !text.is_empty() {
+                    start_block(out);
+                    out.push_str(&format!("{} {}", "#".repeat(level), text));
+                    start_block(out);
+                }
+            }
+            "p" | "div" | "section" | "article" | "main" | "header" | "footer" | "aside"
+            | "nav" | "figure" | "figcaption" | "address" | "details" | "summary" | "dl"
+            | "dt" | "dd" | "form" => {
+                start_block(out);
+                self.render_children(element, out);
+                start_block(out);
+            }
+            "ul" | "ol" => self.render_list(element, tag == "ol", out),
+            "pre" => {
+                let code = element.text().collect::<String>();
+                let language = code_language(element).unwrap_or_default();
+                start_block(out);
+                out.push_str(&format!("```{}\n{}\n```", language, code.trim_end_matches('\n')));
+                start_block(out);
+            }
+            "blockquote" => {
+                let quote = normalize_markdown(&self.render_fragment(element));
+                if !quote.is_empty() {
+                    start_block(out);
+                    let quoted: Vec<String> = quote
+                        .lines()
+                        .map(|line| if line.is_empty() { ">".to_string() } else { format!("> {}", line) })
+                        .collect();
+                    out.push_str(&quoted.join("\n"));
+                    start_block(out);
+                }
+            }
+            "table" => self.render_table(element, out),
+            "br" => {
+                trim_trailing_spaces(out);
+                out.push('\n');
+            }
+            "hr" => {
+                start_block(out);
+                out.push_str("---");
+                start_block(out);
+            }
+            "a" => {
+                let text = self.render_fragment(element);
+                match element.value().attr("href").map(str::trim) {
+                    Some(href) if !href.is_empty() && !href.starts_with("javascript:") => {
+                        self.push_inline(out, element, &text, |inner| format!("[{}]({})", inner, href));
+                    }
+                    _ => push_text(out, &text),
+                }
+            }
+            "img" => {
+                if let Some(src) = element.value().attr("src").filter(|src| !src.trim().is_empty()) {
+                    let alt = collapse_whitespace(element.value().attr("alt").unwrap_or(""));
+                    push_text(out, &format!("![{}]({})", alt, src.trim()));
+                }
+            }
+            "strong" | "b" => {
+                let text = self.render_fragment(element);
+                self.push_inline(out, element, &text, |inner| format!("**{}**", inner));
+            }
+            "em" | "i" => {
+                let text = self.render_fragment(element);
+                self.push_inline(out, element, &text, |inner| format!("*{}*", inner));
+            }
+            "code" => {
+                let text = element.text().collect::<String>();
+                self.push_inline(out, element, &text, |inner| format!("`{}`", inner));
+            }
+            _ => self.render_children(element, out),
+        }
+    }
+    
+    /// Push the rendered inline `text` of `element` wrapped by `wrap`, keeping the
+    /// element's surrounding whitespace outside the markers
+    fn push_inline(
+        &self,
+        out: &mut String,
+        element: scraper::ElementRef,
+        text: &str,
+        wrap: impl Fn(&str) -> String,
+    ) {
+        let inner = collapse_whitespace(text);
+        if inner.is_empty() {
+            return;
+        }
+        let raw = element.text().collect::<String>();
+        if raw.starts_with(char::is_whitespace) {
+            push_text(out, " ");
+        }
+        push_text(out, &wrap(&inner));
+        if raw.ends_with(char::is_whitespace) {
+            push_text(out, " ");
+        }
+    }
+    
+    fn render_list(&self, element: scraper::ElementRef, ordered: bool, out: &mut String) {
+        use scraper::ElementRef;
+        
+        let mut number: usize = element
+            .value()
+            .attr("start")
+            .and_then(|start| start.trim().parse().ok())
+            .unwrap_or(1);
+        let mut items = Vec::new();
+        
+        for item in element.children().filter_map(ElementRef::wrap) {
+            if item.value().name() != "li" {
+                continue;
+            }
+            let marker = if ordered {
+                format!("{}. ", number)
+            } else {
+                "- ".to_string()
+            };
+            number += 1;
+            
+            // Nested blocks stay tight inside an item and are indented under its marker
+            let body = normalize_markdown(&self.render_fragment(item)).replace("\n\n", "\n");
+            let indent = " ".repeat(marker.len());
+            let mut lines = body.lines();
+            let mut rendered = format!("{}{}", marker, lines.next().unwrap_or(""));
+            for line in lines {
+                rendered.push('\n');
+                if !line.is_empty() {
+                    rendered.push_str(&indent);
+                    rendered.push_str(line);
+                }
+            }
+            items.push(rendered.trim_end().to_string());
+        }
+        
+        if !items.is_empty() {
+            start_block(out);
+            out.push_str(&items.join("\n"));
+            start_block(out);
+        }
+    }
+    
+    fn render_table(&self, element: scraper::ElementRef, out: &mut String) {
+        use scraper::Selector;
+        
+        let (Ok(row_selector), Ok(cell_selector)) = (Selector::parse("tr"), Selector::parse("th, td")) else {
+            return;
+        };
+        let mut rows = Vec::new();
+        for row in element.select(&row_selector) {
+            let cells: Vec<String> = row
+                .select(&cell_selector)
+                .map(|cell| collapse_whitespace(&self.render_fragment(cell)).replace('|', "\\|"))
+                .collect();
+            if !cells.is_empty() {
+                rows.push(cells);
+            }
+        }
+        if rows.is_empty() {
+            return;
+        }
+        
+        let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+        let format_row = |cells: &[String]| {
+            let mut cells = cells.to_vec();
+            cells.resize(columns, String::new());
+            format!("| {} |", cells.join(" | "))
+        };
+        
+        start_block(out);
+        out.push_str(&format_row(&rows[0]));
+        out.push('\n');
+        out.push_str(&format!("|{}", " --- |".repeat(columns)));
+        for row in &rows[1..] {
+            out.push('\n');
+            out.push_str(&format_row(row));
+        }
+        start_block(out);
+    }
+}
+
+/// `language-xxx`/`lang-xxx` class on a `<pre>` or its `<code>` child
+fn code_language(pre: scraper::ElementRef) -> Option<String> {
+    use scraper::ElementRef;
+    
+    std::iter::once(pre)
+        .chain(pre.children().filter_map(ElementRef::wrap).filter(|el| el.value().name() == "code"))
+        .flat_map(|el| el.value().classes())
+        .find_map(|class| class.strip_prefix("language-").or_else(|| class.strip_prefix("lang-")))
+        .map(str::to_string)
+}
+
+/// Append text, collapsing whitespace runs and dropping whitespace at line starts
+fn push_text(out: &mut String, text: &str) {
+    let mut previous_space = out.is_empty() || out.ends_with(char::is_whitespace);
+    for c in text.chars() {
+        if c.is_whitespace() {
+            if !previous_space {
+                out.push(' ');
+                previous_space = true;
+            }
+        } else {
+            out.push(c);
+            previous_space = false;
+        }
+    }
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn trim_trailing_spaces(out: &mut String) {
+    let trimmed = out.trim_end_matches(' ').len();
+    out.truncate(trimmed);
+}
+
+/// Ensure the next output starts a new block
+fn start_block(out: &mut String) {
+    trim_trailing_spaces(out);
+    if !out.is_empty() && !out.ends_with("\n\n") {
+        out.push_str(if out.ends_with('\n') { "\n" } else { "\n\n" });
+    }
+}
+
+/// Trim line ends and collapse runs of blank lines into one, outside code fences
+fn normalize_markdown(markdown: &str) -> String {
+    let mut normalized = String::new();
+    let mut blank_run = 0;
+    let mut in_fence = false;
+    for line in markdown.lines().map(str::trim_end) {
+        if line.starts_with("```") {
+            in_fence = !in_fence;
+        } else if line.is_empty() && !in_fence {
+            blank_run += 1;
+            continue;
+        }
+        if !normalized.is_empty() {
+            normalized.push_str(if blank_run > 0 { "\n\n" } else { "\n" });
+        }
+        normalized.push_str(line);
+        blank_run = 0;
+    }
+    normalized
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -473,5 +1092,158 @@ mod tests {
         assert_eq!(config.format, ContentFormat::Html);
         assert!(config.clean_html);
         assert_eq!(config.max_content_length, 50_000);
+        assert_eq!(config.max_output_bytes, 256 * 1024);
+        assert_eq!(config.max_tokens, None);
+        assert_eq!(config.temperature, None);
+    }
+    
+    #[test]
+    fn test_output_size_limit() {
+        let config = LLMExtractorConfig {
+            max_output_bytes: 32,
+            ..Default::default()
+        };
+        let extractor = LLMExtractor::with_config(
+            Arc::new(crate::MockProvider::new()),
+            config,
+        );
+        
+        let small = serde_json::json!({ "title": "ok" });
+        assert!(extractor.check_output_size(&small).is_ok());
+        
+        let large = serde_json::json!({ "title": "x".repeat(64) });
+        assert!(matches!(
+            extractor.check_output_size(&large),
+            Err(PreviewError::OutputTooLarge { limit: 32, .. })
+        ));
     }
-}
\ No newline at end of file
+    
+    #[tokio::test]
+    async fn test_markdown_conversion() {
+        let html = r#"<html><head><title>Guide</title></head><body>
+<h2>Install</h2>
+<p>Run the <strong>installer</strong> or read <a href="https://example.com/docs">the <em>docs</em></a>.</p>
+<ul>
+  <li>Fast</li>
+  <li>Safe
+    <ol><li>Memory</li><li>Threads</li></ol>
+  </li>
+</ul>
+<pre><code class="language-rust">fn main() {
+    println!("hi");
+}
+</code></pre>
+<p>Use <code>cargo build</code> first.</p>
+</body></html>"#;
+        let config = LLMExtractorConfig {
+            format: ContentFormat::Markdown,
+            ..Default::default()
+        };
+        
+        let processed = ContentPreprocessor::new().preprocess(html, &config).await.unwrap();
+        assert_eq!(
+            processed.content,
+            "# Guide\n\n\
+             ## Install\n\n\
+             Run the **installer** or read [the *docs*](https://example.com/docs).\n\n\
+             - Fast\n\
+             - Safe\n  1. Memory\n  2. Threads\n\n\
+             ```rust\nfn main() {\n    println!(\"hi\");\n}\n```\n\n\
+             Use `cargo build` first."
+        );
+    }
+    
+    #[test]
+    fn test_markdown_blocks_and_cleaning() {
+        let html = "<body><nav>Menu</nav><blockquote><p>Quote</p></blockquote>\
+            <table><tr><th>A</th><th>B</th></tr><tr><td>1</td><td>2</td></tr></table><hr></body>";
+        let preprocessor = ContentPreprocessor::new();
+        
+        assert_eq!(
+            preprocessor.convert_to_markdown(html, true).unwrap(),
+            "> Quote\n\n| A | B |\n| --- | --- |\n| 1 | 2 |\n\n---"
+        );
+        assert!(preprocessor.convert_to_markdown(html, false).unwrap().starts_with("Menu\n\n> Quote"));
+    }
+    
+    #[test]
+    fn test_multimodal_message() {
+        let extractor = LLMExtractor::new(Arc::new(crate::MockProvider::new()));
+        let content = ProcessedContent {
+            content: "aW1n".to_string(),
+            format: ContentFormat::Image,
+            metadata: HashMap::new(),
+        };
+        
+        let message = extractor
+            .build_multimodal_message(&content, &serde_json::json!({ "type": "object" }))
+            .unwrap();
+        assert_eq!(message["role"], "user");
+        assert!(message["content"][0]["text"].as_str().unwrap().contains("screenshot"));
+        assert_eq!(message["content"][1]["image_url"]["url"], "data:image/png;base64,aW1n");
+    }
+    
+    #[test]
+    fn test_drain_json_objects() {
+        let mut buffer = String::from("```json\n{\"a\": 1}, {\"a\": {\"b\": \"}\"");
+        let values = drain_json_objects(&mut buffer).unwrap();
+        assert_eq!(values, vec![serde_json::json!({ "a": 1 })]);
+        assert_eq!(buffer, "{\"a\": {\"b\": \"}\"");
+        
+        buffer.push_str("}}\n```");
+        let values = drain_json_objects(&mut buffer).unwrap();
+        assert_eq!(values, vec![serde_json::json!({ "a": { "b": "}" } })]);
+        assert!(buffer.is_empty());
+        
+        let mut invalid = String::from("{\"a\": nope}");
+        assert!(drain_json_objects(&mut invalid).is_err());
+    }
+    
+    #[tokio::test]
+    async fn test_preprocess_prefers_article_body() {
+        let html = r#"<html><body>
+            <div class="sidebar">Trending: celebrity gossip</div>
+            <article>
+                <h1>Headline</h1>
+                <div itemprop="articleBody"><p>The actual story.</p></div>
+                <div class="share">Share this</div>
+            </article>
+        </body></html>"#;
+        let preprocessor = ContentPreprocessor::new();
+        let config = LLMExtractorConfig {
+            format: ContentFormat::Text,
+            ..Default::default()
+        };
+        
+        let processed = preprocessor.preprocess(html, &config).await.unwrap();
+        assert_eq!(processed.content, "The actual story.");
+        assert_eq!(
+            processed.metadata.get("content_root").map(String::as_str),
+            Some("[itemprop='articleBody']")
+        );
+        
+        let config = LLMExtractorConfig {
+            prefer_article_body: false,
+            ..config
+        };
+        let processed = preprocessor.preprocess(html, &config).await.unwrap();
+        assert!(processed.content.contains("Trending"));
+        assert!(processed.metadata.is_empty());
+    }
+    
+    #[tokio::test]
+    async fn test_preprocess_falls_back_to_article_element() {
+        let html = r#"<html><body>
+            <nav>Home | About</nav>
+            <div class="ad">Buy now</div>
+            <article><p>Story text.</p></article>
+        </body></html>"#;
+        let config = LLMExtractorConfig {
+            format: ContentFormat::Text,
+            ..Default::default()
+        };
+        
+        let processed = ContentPreprocessor::new().preprocess(html, &config).await.unwrap();
+        assert_eq!(processed.content, "Story text.");
+    }
+}
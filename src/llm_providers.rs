@@ -13,6 +13,7 @@ use std::collections::HashMap;
 pub struct MockProvider {
     name: String,
     responses: HashMap<String, Value>,
+    stream_chunks: Option<Vec<String>>,
 }
 
 impl MockProvider {
@@ -20,6 +21,7 @@ impl MockProvider {
         Self {
             name: "mock".to_string(),
             responses: HashMap::new(),
+            stream_chunks: None,
         }
     }
     
@@ -27,6 +29,12 @@ impl MockProvider {
         self.responses.insert(key, response);
         self
     }
+    
+    /// Enable streaming, emitting `chunks` as raw text fragments in order
+    pub fn with_stream_chunks(mut self, chunks: Vec<String>) -> Self {
+        self.stream_chunks = Some(chunks);
+        self
+    }
 }
 
 #[async_trait]
@@ -64,16 +72,30 @@ impl LLMProvider for MockProvider {
             Ok(Value::Object(serde_json::Map::new()))
         }
     }
+    
+    async fn stream(
+        &self,
+        _prompt: String,
+        _schema: Value,
+        _config: &LLMExtractorConfig,
+    ) -> Result<Box<dyn futures::Stream<Item = Result<Value, PreviewError>> + Send + Unpin>, PreviewError> {
+        let Some(chunks) = &self.stream_chunks else {
+            return Err(PreviewError::UnsupportedOperation("Streaming not supported by this provider".into()));
+        };
+        let chunks: Vec<Result<Value, PreviewError>> = chunks.iter().cloned().map(|chunk| Ok(Value::String(chunk))).collect();
+        Ok(Box::new(futures::stream::iter(chunks)))
+    }
 }
 
 #[cfg(feature = "async-openai")]
 pub mod openai {
     use super::*;
     use async_openai::{Client, config::OpenAIConfig};
+    use futures::StreamExt;
     use async_openai::types::{
         ChatCompletionRequestMessage, ChatCompletionRequestSystemMessageArgs,
-        ChatCompletionRequestUserMessageArgs, CreateChatCompletionRequestArgs,
-        ChatCompletionToolArgs, ChatCompletionToolType,
+        ChatCompletionRequestUserMessageArgs, CreateChatCompletionRequest,
+        CreateChatCompletionRequestArgs, ChatCompletionToolArgs, ChatCompletionToolType,
         FunctionObjectArgs,
     };
     
@@ -105,36 +127,38 @@ pub mod openai {
             }
         }
         
-        /// Extract JSON from text content
-        fn extract_json_from_text(text: &str) -> Option<String> {
-            // Find the first '{' and last '}'
-            let start = text.find('{')?;
-            let end = text.rfind('}')?;
-            
-            if start <= end {
-                let potential_json = &text[start..=end];
-                // Basic validation - check if it looks like valid JSON
-                if potential_json.contains('"') || potential_json.contains(':') {
-                    return Some(potential_json.to_string());
-                }
-            }
-            
-            None
-        }
-    }
-    
-    #[async_trait]
-    impl LLMProvider for OpenAIProvider {
-        fn name(&self) -> &str {
-            "openai"
+        /// Whether the configured model accepts image input
+        fn supports_vision(&self) -> bool {
+            self.model.starts_with("gpt-4o")
+                || self.model.starts_with("gpt-4-turbo")
+                || self.model.contains("vision")
         }
         
-        async fn generate(
+        /// Build the tool-calling chat request shared by `generate` and `stream`
+        fn build_request(
             &self,
             prompt: String,
             schema: Value,
-            _config: &LLMExtractorConfig,
-        ) -> Result<Value, PreviewError> {
+            config: &LLMExtractorConfig,
+        ) -> Result<CreateChatCompletionRequest, PreviewError> {
+            let user_message = ChatCompletionRequestUserMessageArgs::default()
+                .content(prompt)
+                .build()
+                .map_err(|e| PreviewError::ExternalServiceError {
+                    service: "OpenAI".to_string(),
+                    message: e.to_string(),
+                })?;
+            
+            self.build_request_with_message(ChatCompletionRequestMessage::User(user_message), schema, config)
+        }
+        
+        /// Build the tool-calling chat request around a prepared user message
+        fn build_request_with_message(
+            &self,
+            user_message: ChatCompletionRequestMessage,
+            schema: Value,
+            config: &LLMExtractorConfig,
+        ) -> Result<CreateChatCompletionRequest, PreviewError> {
             // Build function definition
             let function = FunctionObjectArgs::default()
                 .name("extract_data")
@@ -164,29 +188,32 @@ pub mod openai {
                     message: e.to_string(),
                 })?;
             
-            let user_message = ChatCompletionRequestUserMessageArgs::default()
-                .content(prompt)
-                .build()
-                .map_err(|e| PreviewError::ExternalServiceError {
-                    service: "OpenAI".to_string(),
-                    message: e.to_string(),
-                })?;
-            
             // Create request
-            let request = CreateChatCompletionRequestArgs::default()
+            let mut request = CreateChatCompletionRequestArgs::default();
+            request
                 .model(&self.model)
                 .messages(vec![
                     ChatCompletionRequestMessage::System(system_message),
-                    ChatCompletionRequestMessage::User(user_message),
+                    user_message,
                 ])
                 .tools(vec![tool])
-                .tool_choice("required")
+                .tool_choice("required");
+            if let Some(max_tokens) = config.max_tokens {
+                request.max_tokens(max_tokens);
+            }
+            if let Some(temperature) = config.temperature {
+                request.temperature(temperature);
+            }
+            request
                 .build()
                 .map_err(|e| PreviewError::ExternalServiceError {
                     service: "OpenAI".to_string(),
                     message: e.to_string(),
-                })?;
-            
+                })
+        }
+        
+        /// Send a chat request and pull the extracted JSON out of the reply
+        async fn complete(&self, request: CreateChatCompletionRequest) -> Result<Value, PreviewError> {
             // Make API call
             let response = self.client
                 .chat()
@@ -230,6 +257,107 @@ pub mod openai {
                 message: "No function call or valid JSON in response".to_string(),
             })
         }
+        
+        /// Extract JSON from text content
+        fn extract_json_from_text(text: &str) -> Option<String> {
+            // Find the first '{' and last '}'
+            let start = text.find('{')?;
+            let end = text.rfind('}')?;
+            
+            if start <= end {
+                let potential_json = &text[start..=end];
+                // Basic validation - check if it looks like valid JSON
+                if potential_json.contains('"') || potential_json.contains(':') {
+                    return Some(potential_json.to_string());
+                }
+            }
+            
+            None
+        }
+    }
+    
+    #[async_trait]
+    impl LLMProvider for OpenAIProvider {
+        fn name(&self) -> &str {
+            "openai"
+        }
+        
+        async fn generate(
+            &self,
+            prompt: String,
+            schema: Value,
+            config: &LLMExtractorConfig,
+        ) -> Result<Value, PreviewError> {
+            let request = self.build_request(prompt, schema, config)?;
+            
+            self.complete(request).await
+        }
+        
+        async fn generate_multimodal(
+            &self,
+            message: Value,
+            schema: Value,
+            config: &LLMExtractorConfig,
+        ) -> Result<Value, PreviewError> {
+            if !self.supports_vision() {
+                return Err(PreviewError::UnsupportedOperation(format!(
+                    "Model {} does not accept image input",
+                    self.model
+                )));
+            }
+            
+            let message: ChatCompletionRequestMessage = serde_json::from_value(message)?;
+            let request = self.build_request_with_message(message, schema, config)?;
+            self.complete(request).await
+        }
+        
+        async fn stream(
+            &self,
+            prompt: String,
+            schema: Value,
+            config: &LLMExtractorConfig,
+        ) -> Result<Box<dyn futures::Stream<Item = Result<Value, PreviewError>> + Send + Unpin>, PreviewError> {
+            let request = self.build_request(prompt, schema, config)?;
+            
+            let stream = self.client
+                .chat()
+                .create_stream(request)
+                .await
+                .map_err(|e| PreviewError::ExternalServiceError {
+                    service: "OpenAI".to_string(),
+                    message: e.to_string(),
+                })?;
+            
+            // Forward tool-call argument deltas, or content deltas for
+            // compatible endpoints that answer in plain text
+            let chunks = stream.filter_map(|response| {
+                let chunk = match response {
+                    Ok(response) => {
+                        let text: String = response
+                            .choices
+                            .into_iter()
+                            .flat_map(|choice| {
+                                let arguments = choice
+                                    .delta
+                                    .tool_calls
+                                    .into_iter()
+                                    .flatten()
+                                    .filter_map(|call| call.function.and_then(|f| f.arguments));
+                                arguments.chain(choice.delta.content)
+                            })
+                            .collect();
+                        (!text.is_empty()).then_some(Ok(Value::String(text)))
+                    }
+                    Err(e) => Some(Err(PreviewError::ExternalServiceError {
+                        service: "OpenAI".to_string(),
+                        message: e.to_string(),
+                    })),
+                };
+                futures::future::ready(chunk)
+            });
+            
+            Ok(Box::new(chunks))
+        }
     }
 }
 
@@ -237,11 +365,17 @@ pub mod openai {
 // #[cfg(feature = "anthropic")]
 pub mod anthropic {
     use super::*;
+    use crate::llm_extractor::TokenUsage;
+    
+    const DEFAULT_BASE_URL: &str = "https://api.anthropic.com";
+    /// The Messages API requires `max_tokens`
+    const DEFAULT_MAX_TOKENS: u32 = 4096;
     
     /// Anthropic Claude provider implementation
     pub struct AnthropicProvider {
         api_key: String,
         model: String,
+        base_url: String,
     }
     
     impl AnthropicProvider {
@@ -249,6 +383,7 @@ pub mod anthropic {
             Self {
                 api_key,
                 model: "claude-3-opus-20240229".to_string(),
+                base_url: DEFAULT_BASE_URL.to_string(),
             }
         }
         
@@ -256,6 +391,12 @@ pub mod anthropic {
             self.model = model;
             self
         }
+        
+        /// Send requests to a different API root, e.g. a proxy
+        pub fn with_base_url(mut self, base_url: String) -> Self {
+            self.base_url = base_url.trim_end_matches('/').to_string();
+            self
+        }
     }
     
     #[async_trait]
@@ -268,8 +409,19 @@ pub mod anthropic {
             &self,
             prompt: String,
             schema: Value,
-            _config: &LLMExtractorConfig,
+            config: &LLMExtractorConfig,
         ) -> Result<Value, PreviewError> {
+            self.generate_with_usage(prompt, schema, config)
+                .await
+                .map(|(result, _)| result)
+        }
+        
+        async fn generate_with_usage(
+            &self,
+            prompt: String,
+            schema: Value,
+            config: &LLMExtractorConfig,
+        ) -> Result<(Value, Option<TokenUsage>), PreviewError> {
             // Build the system prompt with schema instructions
             let schema_str = serde_json::to_string_pretty(&schema)
                 .map_err(|e| PreviewError::ParseError(e.to_string()))?;
@@ -282,21 +434,24 @@ pub mod anthropic {
             );
             
             // Build the request payload
-            let request_body = serde_json::json!({
+            let mut request_body = serde_json::json!({
                 "model": self.model,
-                "max_tokens": 1000,
+                "max_tokens": config.max_tokens.unwrap_or(DEFAULT_MAX_TOKENS),
                 "system": system_prompt,
                 "messages": [{
                     "role": "user",
                     "content": prompt
                 }]
             });
+            if let Some(temperature) = config.temperature {
+                request_body["temperature"] = serde_json::json!(temperature);
+            }
             
             // Make the API call
             let client = reqwest::Client::new();
             let response = client
-                .post("https://api.anthropic.com/v1/messages")
-                .header("Authorization", format!("Bearer {}", self.api_key))
+                .post(format!("{}/v1/messages", self.base_url))
+                .header("x-api-key", &self.api_key)
                 .header("Content-Type", "application/json")
                 .header("anthropic-version", "2023-06-01")
                 .json(&request_body)
@@ -304,17 +459,31 @@ pub mod anthropic {
                 .await
                 .map_err(|e| PreviewError::FetchError(e.to_string()))?;
             
-            if !response.status().is_success() {
+            let status = response.status();
+            if !status.is_success() {
                 let error_text = response.text().await.unwrap_or_default();
                 return Err(PreviewError::ExternalServiceError {
                     service: "Anthropic".to_string(),
-                    message: format!("API error: {}", error_text)
+                    message: Self::describe_error(status.as_u16(), &error_text),
                 });
             }
             
             let response_json: Value = response.json().await
                 .map_err(|e| PreviewError::ParseError(e.to_string()))?;
             
+            let usage = &response_json["usage"];
+            let usage = match (usage["input_tokens"].as_u64(), usage["output_tokens"].as_u64()) {
+                (Some(input), Some(output)) => {
+                    let (input, output) = (input as u32, output as u32);
+                    Some(TokenUsage {
+                        prompt_tokens: input,
+                        completion_tokens: output,
+                        total_tokens: input + output,
+                    })
+                }
+                _ => None,
+            };
+            
             // Extract content from Claude's response
             let content = response_json["content"][0]["text"]
                 .as_str()
@@ -324,25 +493,39 @@ pub mod anthropic {
                 })?;
             
             // Try to parse the content as JSON
-            match serde_json::from_str::<Value>(content) {
-                Ok(json) => Ok(json),
+            let result = match serde_json::from_str::<Value>(content) {
+                Ok(json) => json,
                 Err(_) => {
                     // Try to extract JSON from text
                     if let Some(json_str) = AnthropicProvider::extract_json_from_text(content) {
                         serde_json::from_str(&json_str)
-                            .map_err(|e| PreviewError::ParseError(e.to_string()))
+                            .map_err(|e| PreviewError::ParseError(e.to_string()))?
                     } else {
-                        Err(PreviewError::ExternalServiceError {
+                        return Err(PreviewError::ExternalServiceError {
                             service: "Anthropic".to_string(),
                             message: "Could not extract valid JSON from response".to_string()
-                        })
+                        });
                     }
                 }
-            }
+            };
+            Ok((result, usage))
         }
     }
     
     impl AnthropicProvider {
+        /// Summarize an error response such as
+        /// `{"type":"error","error":{"type":"authentication_error","message":"invalid x-api-key"}}`
+        fn describe_error(status: u16, body: &str) -> String {
+            let parsed: Value = serde_json::from_str(body).unwrap_or_default();
+            let error = &parsed["error"];
+            match (error["type"].as_str(), error["message"].as_str()) {
+                (Some(error_type), Some(message)) => {
+                    format!("HTTP {}: {}: {}", status, error_type, message)
+                }
+                _ => format!("HTTP {}: {}", status, body),
+            }
+        }
+        
         /// Extract JSON from text content
         fn extract_json_from_text(text: &str) -> Option<String> {
             let start = text.find('{')?;
@@ -389,7 +572,7 @@ impl LLMProvider for LocalProvider {
         &self,
         prompt: String,
         schema: Value,
-        _config: &LLMExtractorConfig,
+        config: &LLMExtractorConfig,
     ) -> Result<Value, PreviewError> {
         // Build request for Ollama or local model server
         let schema_str = serde_json::to_string_pretty(&schema)
@@ -401,12 +584,24 @@ impl LLMProvider for LocalProvider {
             prompt
         );
         
-        let request_body = serde_json::json!({
+        // Ollama takes generation controls under `options`
+        let mut options = serde_json::Map::new();
+        if let Some(max_tokens) = config.max_tokens {
+            options.insert("num_predict".to_string(), serde_json::json!(max_tokens));
+        }
+        if let Some(temperature) = config.temperature {
+            options.insert("temperature".to_string(), serde_json::json!(temperature));
+        }
+        
+        let mut request_body = serde_json::json!({
             "model": self.model,
             "prompt": full_prompt,
             "format": "json",
             "stream": false
         });
+        if !options.is_empty() {
+            request_body["options"] = Value::Object(options);
+        }
         
         // Make request to local model server (e.g., Ollama)
         let client = reqwest::Client::new();
@@ -58,7 +58,7 @@ impl LLMProvider for ClaudeCompatProvider {
         &self,
         prompt: String,
         schema: Value,
-        _config: &LLMExtractorConfig,
+        config: &LLMExtractorConfig,
     ) -> Result<Value, PreviewError> {
         // Build the extraction prompt
         let system_prompt = format!(
@@ -98,8 +98,8 @@ impl LLMProvider for ClaudeCompatProvider {
         let request = CreateChatCompletionRequestArgs::default()
             .model(&self.model)
             .messages(messages)
-            .temperature(0.1) // Low temperature for consistent extraction
-            .max_tokens(4096u32)
+            .temperature(config.temperature.unwrap_or(0.1)) // Low temperature for consistent extraction
+            .max_tokens(config.max_tokens.unwrap_or(4096))
             .build()
             .map_err(|e| PreviewError::ExternalServiceError {
                 service: "Claude".to_string(),
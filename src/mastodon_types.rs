@@ -0,0 +1,246 @@
+use crate::Preview;
+use scraper::Html;
+use serde::Deserialize;
+use serde_json::Value;
+use url::Url;
+
+/// NodeInfo `software.name` values of servers implementing the Mastodon client API
+const MASTODON_COMPATIBLE_SOFTWARE: &[&str] =
+    &["mastodon", "hometown", "pleroma", "akkoma", "gotosocial"];
+
+/// A status as returned by `GET /api/v1/statuses/:id`
+#[derive(Debug, Clone, Deserialize)]
+pub struct MastodonStatus {
+    pub id: String,
+    #[serde(default)]
+    pub url: Option<String>,
+    #[serde(default)]
+    pub created_at: Option<String>,
+    /// Post body as HTML
+    #[serde(default)]
+    pub content: String,
+    /// Content warning shown instead of the body until expanded
+    #[serde(default)]
+    pub spoiler_text: String,
+    pub account: MastodonAccount,
+    #[serde(default)]
+    pub media_attachments: Vec<MastodonMedia>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MastodonAccount {
+    pub username: String,
+    /// `user` for local accounts, `user@domain` for remote ones
+    pub acct: String,
+    #[serde(default)]
+    pub display_name: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MastodonMedia {
+    /// `image`, `gifv`, `video`, `audio` or `unknown`
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(default)]
+    pub url: Option<String>,
+    #[serde(default)]
+    pub preview_url: Option<String>,
+}
+
+/// Matches the shape of a Mastodon status URL, `https://host/@user/<numeric id>`.
+///
+/// Other sites use the same shape, so whether the host really is a fediverse
+/// instance is only confirmed via NodeInfo when the status is fetched.
+pub fn is_mastodon_url(url: &str) -> bool {
+    parse_mastodon_status_url(url).is_some()
+}
+
+/// Splits a status URL into the instance origin and the status id, e.g.
+/// `https://mastodon.social/@Gargron/1` -> `("https://mastodon.social", "1")`
+pub fn parse_mastodon_status_url(url: &str) -> Option<(String, String)> {
+    let parsed = Url::parse(url).ok()?;
+    let segments: Vec<&str> = parsed.path_segments()?.collect();
+    match segments.as_slice() {
+        [user, id] | [user, id, ""]
+            if user.len() > 1
+                && user.starts_with('@')
+                && !id.is_empty()
+                && id.bytes().all(|b| b.is_ascii_digit()) =>
+        {
+            Some((parsed.origin().ascii_serialization(), id.to_string()))
+        }
+        _ => None,
+    }
+}
+
+/// The NodeInfo 2.x document URL advertised by a `/.well-known/nodeinfo` response,
+/// resolved against `base`
+pub fn nodeinfo_url(links: &Value, base: &str) -> Option<String> {
+    let href = links["links"]
+        .as_array()?
+        .iter()
+        .filter(|link| {
+            link["rel"].as_str().is_some_and(|rel| {
+                rel.starts_with("http://nodeinfo.diaspora.software/ns/schema/2.")
+            })
+        })
+        .filter_map(|link| link["href"].as_str())
+        .next_back()?;
+    Url::parse(base).ok()?.join(href).ok().map(String::from)
+}
+
+/// Whether a NodeInfo document describes a server implementing the Mastodon API
+pub fn is_mastodon_compatible(nodeinfo: &Value) -> bool {
+    nodeinfo["software"]["name"]
+        .as_str()
+        .is_some_and(|name| MASTODON_COMPATIBLE_SOFTWARE.contains(&name.to_lowercase().as_str()))
+}
+
+/// Flattens status HTML to text, keeping paragraph and line breaks
+fn html_to_text(html: &str) -> String {
+    let html = html
+        .replace("<br>", "\n")
+        .replace("<br/>", "\n")
+        .replace("<br />", "\n")
+        .replace("</p><p>", "\n\n");
+    let text: String = Html::parse_fragment(&html).root_element().text().collect();
+    text.trim().to_string()
+}
+
+impl MastodonStatus {
+    /// The author's handle as shown by Mastodon, e.g. `@user@mastodon.social`
+    pub fn handle(&self, instance_host: &str) -> String {
+        if self.account.acct.contains('@') {
+            format!("@{}", self.account.acct)
+        } else {
+            format!("@{}@{instance_host}", self.account.acct)
+        }
+    }
+
+    /// Builds a preview with the post text as description and the author's handle as
+    /// `site_name`. Posts behind a content warning are described by the warning.
+    pub fn into_preview(self, url: &str) -> Preview {
+        let host = Url::parse(url)
+            .ok()
+            .and_then(|parsed| parsed.host_str().map(String::from))
+            .unwrap_or_default();
+        let handle = self.handle(&host);
+        let display_name =
+            Some(self.account.display_name.trim().to_string()).filter(|name| !name.is_empty());
+
+        let spoiler = self.spoiler_text.trim();
+        let description = if spoiler.is_empty() {
+            html_to_text(&self.content)
+        } else {
+            spoiler.to_string()
+        };
+
+        let image_url = self.media_attachments.into_iter().find_map(|media| {
+            if media.kind == "image" {
+                media.url.or(media.preview_url)
+            } else {
+                media.preview_url
+            }
+        });
+
+        Preview {
+            url: url.to_string(),
+            title: Some(display_name.clone().unwrap_or_else(|| handle.clone())),
+            description: (!description.is_empty()).then_some(description),
+            image_url,
+            favicon: (!host.is_empty()).then(|| format!("https://{host}/favicon.ico")),
+            site_name: Some(handle),
+            canonical_url: self.url,
+            author: display_name.clone(),
+            authors: display_name.into_iter().collect(),
+            published_at: self.created_at,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(name: &str) -> Value {
+        let path = format!(
+            "{}/tests/fixtures/mastodon/{name}",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_parse_mastodon_status_url() {
+        assert_eq!(
+            parse_mastodon_status_url("https://mastodon.social/@Gargron/109318825217224375"),
+            Some((
+                "https://mastodon.social".to_string(),
+                "109318825217224375".to_string()
+            ))
+        );
+        assert!(is_mastodon_url("https://fosstodon.org/@rust/1234/"));
+        assert!(!is_mastodon_url("https://mastodon.social/@Gargron"));
+        assert!(!is_mastodon_url(
+            "https://medium.com/@someone/my-post-1a2b3c"
+        ));
+        assert!(!is_mastodon_url("https://example.com/users/alice/1234"));
+    }
+
+    #[test]
+    fn test_instance_detection() {
+        let links = sample("well_known_nodeinfo.json");
+        assert_eq!(
+            nodeinfo_url(&links, "https://mastodon.social").as_deref(),
+            Some("https://mastodon.social/nodeinfo/2.0")
+        );
+        assert!(is_mastodon_compatible(&sample("nodeinfo.json")));
+
+        let other = serde_json::json!({"software": {"name": "lemmy", "version": "0.19"}});
+        assert!(!is_mastodon_compatible(&other));
+    }
+
+    #[test]
+    fn test_status_preview() {
+        let url = "https://mastodon.social/@rustlang/112233445566778899";
+        let status: MastodonStatus = serde_json::from_value(sample("status.json")).unwrap();
+        let preview = status.into_preview(url);
+
+        assert_eq!(preview.title.as_deref(), Some("Rust Language"));
+        assert_eq!(
+            preview.site_name.as_deref(),
+            Some("@rustlang@mastodon.social")
+        );
+        assert_eq!(
+            preview.description.as_deref(),
+            Some(
+                "Rust 1.80 is out!\n\nRead the release notes: \
+                 https://blog.rust-lang.org/2024/07/25/Rust-1.80.0.html"
+            )
+        );
+        assert_eq!(
+            preview.image_url.as_deref(),
+            Some("https://files.mastodon.social/media_attachments/files/original/release.png")
+        );
+        assert_eq!(preview.canonical_url.as_deref(), Some(url));
+        assert_eq!(
+            preview.published_at.as_deref(),
+            Some("2024-07-25T15:00:00.000Z")
+        );
+    }
+
+    #[test]
+    fn test_content_warning_replaces_body() {
+        let mut status: MastodonStatus = serde_json::from_value(sample("status.json")).unwrap();
+        status.spoiler_text = "Spoilers for the release".to_string();
+        status.account.acct = "rustlang@hachyderm.io".to_string();
+
+        let preview = status.into_preview("https://mastodon.social/@rustlang@hachyderm.io/1");
+        assert_eq!(
+            preview.description.as_deref(),
+            Some("Spoilers for the release")
+        );
+        assert_eq!(preview.site_name.as_deref(), Some("@rustlang@hachyderm.io"));
+    }
+}
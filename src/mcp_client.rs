@@ -6,8 +6,10 @@
 use crate::PreviewError;
 use jsonrpc_core::Value;
 use serde::{Deserialize, Serialize};
+use reqwest::header::{ACCEPT, CONTENT_TYPE};
 use std::collections::HashMap;
 use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, Ordering};
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::process::{Child, Command};
 use tokio::sync::Mutex;
@@ -22,7 +24,9 @@ use tracing::{debug, instrument};
 pub enum McpTransport {
     /// Standard I/O transport (default)
     Stdio,
-    /// HTTP with Server-Sent Events (not implemented yet)
+    /// HTTP with Server-Sent Events: JSON-RPC messages are POSTed to this endpoint
+    /// (e.g. `http://localhost:8931/mcp`) and responses are read from the reply,
+    /// either plain JSON or an SSE stream
     HttpSse(String),
 }
 
@@ -39,6 +43,81 @@ pub struct McpConfig {
     pub browser_timeout: u64,
     /// Maximum concurrent browser sessions
     pub max_sessions: usize,
+    /// How to decide that a page has finished loading after navigation
+    pub wait_strategy: WaitStrategy,
+}
+
+/// How the browser waits for a page to finish loading. `FixedDelay` uses the
+/// `browser_wait_for` tool; the others poll the page with `browser_evaluate` and give
+/// up after half of `browser_timeout`, capturing the page as it is.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum WaitStrategy {
+    /// Wait a fixed time
+    FixedDelay(Duration),
+    /// Wait until the document has loaded and no new resources were requested for
+    /// half a second (default)
+    #[default]
+    NetworkIdle,
+    /// Wait until an element matches this CSS selector
+    Selector(String),
+}
+
+/// Polls until the load event fired and the resource count stopped growing
+const NETWORK_IDLE_SCRIPT: &str = r#"async () => {
+    const deadline = Date.now() + __TIMEOUT_MS__;
+    let seen = -1;
+    let since = Date.now();
+    while (Date.now() < deadline) {
+        const count = performance.getEntriesByType('resource').length;
+        if (document.readyState !== 'complete' || count !== seen) {
+            seen = count;
+            since = Date.now();
+        } else if (Date.now() - since >= 500) {
+            return true;
+        }
+        await new Promise(resolve => setTimeout(resolve, 100));
+    }
+    return false;
+}"#;
+
+/// Polls until `__SELECTOR__` matches an element
+const SELECTOR_SCRIPT: &str = r#"async () => {
+    const deadline = Date.now() + __TIMEOUT_MS__;
+    while (Date.now() < deadline) {
+        if (document.querySelector(__SELECTOR__)) {
+            return true;
+        }
+        await new Promise(resolve => setTimeout(resolve, 100));
+    }
+    return false;
+}"#;
+
+impl WaitStrategy {
+    /// Name and arguments of the tool call implementing this strategy. Polling
+    /// stops after `limit` so the call returns before the request times out.
+    fn tool_call(&self, limit: Duration) -> (&'static str, Value) {
+        let timeout_ms = limit.as_millis().to_string();
+        match self {
+            Self::FixedDelay(delay) => (
+                "browser_wait_for",
+                serde_json::json!({ "time": delay.as_secs_f64() }),
+            ),
+            Self::NetworkIdle => (
+                "browser_evaluate",
+                serde_json::json!({
+                    "function": NETWORK_IDLE_SCRIPT.replace("__TIMEOUT_MS__", &timeout_ms)
+                }),
+            ),
+            Self::Selector(selector) => {
+                // A JSON string is a valid, escaped JavaScript string literal
+                let selector = Value::String(selector.clone()).to_string();
+                let script = SELECTOR_SCRIPT
+                    .replace("__TIMEOUT_MS__", &timeout_ms)
+                    .replace("__SELECTOR__", &selector);
+                ("browser_evaluate", serde_json::json!({ "function": script }))
+            }
+        }
+    }
 }
 
 impl Default for McpConfig {
@@ -53,6 +132,7 @@ impl Default for McpConfig {
             transport: McpTransport::Stdio,
             browser_timeout: 30,
             max_sessions: 5,
+            wait_strategy: WaitStrategy::default(),
         }
     }
 }
@@ -112,12 +192,18 @@ struct McpError {
     data: Option<Value>,
 }
 
+/// Header carrying the session assigned by an HTTP MCP server
+const MCP_SESSION_HEADER: &str = "Mcp-Session-Id";
+
 /// MCP client for browser automation
 pub struct McpClient {
     config: McpConfig,
     process: Arc<Mutex<Option<Child>>>,
     request_id: Arc<Mutex<u64>>,
     tools: Arc<Mutex<HashMap<String, McpTool>>>,
+    http_client: reqwest::Client,
+    http_connected: Arc<AtomicBool>,
+    http_session_id: Arc<Mutex<Option<String>>>,
 }
 
 impl McpClient {
@@ -128,16 +214,60 @@ impl McpClient {
             process: Arc::new(Mutex::new(None)),
             request_id: Arc::new(Mutex::new(0)),
             tools: Arc::new(Mutex::new(HashMap::new())),
+            http_client: reqwest::Client::new(),
+            http_connected: Arc::new(AtomicBool::new(false)),
+            http_session_id: Arc::new(Mutex::new(None)),
         }
     }
     
-    /// Start the MCP server
+    /// Start the MCP server, or connect to it for the HTTP transport
     #[cfg_attr(feature = "logging", instrument(skip(self)))]
     pub async fn start(&self) -> Result<(), PreviewError> {
         if !self.config.enabled {
             return Ok(());
         }
         
+        match &self.config.transport {
+            McpTransport::Stdio => self.start_stdio().await,
+            McpTransport::HttpSse(endpoint) => self.start_http(endpoint).await,
+        }
+    }
+    
+    /// Connect to a remote MCP server and run the handshake
+    async fn start_http(&self, endpoint: &str) -> Result<(), PreviewError> {
+        if self.http_connected.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+        
+        #[cfg(feature = "logging")]
+        debug!("Connecting to MCP server at {}", endpoint);
+        
+        let handshake = timeout(
+            Duration::from_secs(self.config.browser_timeout),
+            self.initialize(),
+        )
+        .await
+        .unwrap_or_else(|_| {
+            Err(PreviewError::TimeoutError(format!(
+                "handshake did not complete within {} seconds",
+                self.config.browser_timeout
+            )))
+        });
+        
+        if let Err(e) = handshake {
+            *self.http_session_id.lock().await = None;
+            return Err(PreviewError::BrowserUnavailable(format!(
+                "MCP endpoint `{}` failed to initialize: {}",
+                endpoint, e
+            )));
+        }
+        
+        self.http_connected.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+    
+    /// Spawn a local MCP server process and run the handshake
+    async fn start_stdio(&self) -> Result<(), PreviewError> {
         let mut process_guard = self.process.lock().await;
         if process_guard.is_some() {
             return Ok(()); // Already started
@@ -146,16 +276,36 @@ impl McpClient {
         #[cfg(feature = "logging")]
         debug!("Starting MCP server with command: {:?}", self.config.server_command);
         
-        let mut cmd = Command::new(&self.config.server_command[0]);
-        cmd.args(&self.config.server_command[1..])
+        let (program, args) = self.config.server_command.split_first()
+            .ok_or_else(|| PreviewError::BrowserUnavailable(
+                "No MCP server command configured".to_string(),
+            ))?;
+        
+        let mut cmd = Command::new(program);
+        cmd.args(args)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
-            .stderr(Stdio::piped());
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
         
+        // A missing command means the server (or Node.js) is not installed; other
+        // spawn failures, e.g. permissions, are reported as they are
         let mut child = cmd.spawn()
-            .map_err(|e| PreviewError::ExternalServiceError {
-                service: "MCP".to_string(),
-                message: format!("Failed to start MCP server: {}", e),
+            .map_err(|e| match e.kind() {
+                std::io::ErrorKind::NotFound => PreviewError::BrowserUnavailable(format!(
+                    "MCP server `{}` is not installed: {}. Install Node.js and run \
+                     `npx @playwright/mcp@latest` once, or set McpConfig::server_command",
+                    self.config.server_command.join(" "),
+                    e
+                )),
+                _ => PreviewError::ExternalServiceError {
+                    service: "MCP".to_string(),
+                    message: format!(
+                        "Failed to spawn MCP server `{}`: {}",
+                        self.config.server_command.join(" "),
+                        e
+                    ),
+                },
             })?;
         
         // Check if process started successfully
@@ -180,7 +330,27 @@ impl McpClient {
         // Wait a bit for the server to start
         tokio::time::sleep(Duration::from_millis(500)).await;
         
-        self.initialize().await?;
+        // Treat a failed or stalled handshake as a missing/broken server
+        let handshake = timeout(
+            Duration::from_secs(self.config.browser_timeout),
+            self.initialize(),
+        )
+        .await
+        .unwrap_or_else(|_| {
+            Err(PreviewError::TimeoutError(format!(
+                "handshake did not complete within {} seconds",
+                self.config.browser_timeout
+            )))
+        });
+        
+        if let Err(e) = handshake {
+            let _ = self.stop().await;
+            return Err(PreviewError::BrowserUnavailable(format!(
+                "MCP server `{}` failed to initialize: {}",
+                self.config.server_command.join(" "),
+                e
+            )));
+        }
         
         Ok(())
     }
@@ -188,6 +358,9 @@ impl McpClient {
     /// Stop the MCP server
     #[cfg_attr(feature = "logging", instrument(skip(self)))]
     pub async fn stop(&self) -> Result<(), PreviewError> {
+        self.http_connected.store(false, Ordering::SeqCst);
+        *self.http_session_id.lock().await = None;
+        
         let mut process_guard = self.process.lock().await;
         if let Some(mut child) = process_guard.take() {
             #[cfg(feature = "logging")]
@@ -198,6 +371,11 @@ impl McpClient {
         Ok(())
     }
     
+    /// Whether the MCP server process is running, or the HTTP transport connected
+    pub async fn is_running(&self) -> bool {
+        self.http_connected.load(Ordering::SeqCst) || self.process.lock().await.is_some()
+    }
+    
     /// Initialize connection and discover tools
     async fn initialize(&self) -> Result<(), PreviewError> {
         // Send initialization request
@@ -263,6 +441,15 @@ impl McpClient {
     
     /// Send a notification (no response expected)
     async fn send_notification(&self, request: McpRequest) -> Result<(), PreviewError> {
+        match &self.config.transport {
+            McpTransport::Stdio => self.send_notification_stdio(request).await,
+            McpTransport::HttpSse(endpoint) => {
+                self.post_http(endpoint, &request).await.map(|_| ())
+            }
+        }
+    }
+    
+    async fn send_notification_stdio(&self, request: McpRequest) -> Result<(), PreviewError> {
         let mut process_guard = self.process.lock().await;
         let child = process_guard.as_mut()
             .ok_or_else(|| PreviewError::ExternalServiceError {
@@ -304,6 +491,113 @@ impl McpClient {
     
     /// Send a request to the MCP server
     async fn send_request(&self, request: McpRequest) -> Result<McpResponse, PreviewError> {
+        match &self.config.transport {
+            McpTransport::Stdio => self.send_request_stdio(request).await,
+            McpTransport::HttpSse(endpoint) => self.send_request_http(endpoint, request).await,
+        }
+    }
+    
+    /// POST a JSON-RPC message, carrying over the server-assigned session id
+    async fn post_http(
+        &self,
+        endpoint: &str,
+        request: &McpRequest,
+    ) -> Result<reqwest::Response, PreviewError> {
+        #[cfg(feature = "logging")]
+        debug!("Posting {} to {}", request.method, endpoint);
+        
+        let mut builder = self.http_client
+            .post(endpoint)
+            .header(ACCEPT, "application/json, text/event-stream")
+            .json(request);
+        if let Some(session_id) = self.http_session_id.lock().await.clone() {
+            builder = builder.header(MCP_SESSION_HEADER, session_id);
+        }
+        
+        let response = builder.send().await
+            .map_err(|e| PreviewError::ExternalServiceError {
+                service: "MCP".to_string(),
+                message: format!("Failed to reach MCP endpoint: {}", e),
+            })?;
+        
+        if let Some(session_id) = response.headers()
+            .get(MCP_SESSION_HEADER)
+            .and_then(|value| value.to_str().ok())
+        {
+            *self.http_session_id.lock().await = Some(session_id.to_string());
+        }
+        
+        if !response.status().is_success() {
+            return Err(PreviewError::ExternalServiceError {
+                service: "MCP".to_string(),
+                message: format!("MCP endpoint returned status: {}", response.status()),
+            });
+        }
+        
+        Ok(response)
+    }
+    
+    /// Send a request over HTTP, reading the reply as JSON or from an SSE stream
+    async fn send_request_http(
+        &self,
+        endpoint: &str,
+        request: McpRequest,
+    ) -> Result<McpResponse, PreviewError> {
+        let timeout_duration = Duration::from_secs(self.config.browser_timeout);
+        
+        timeout(timeout_duration, self.exchange_http(endpoint, &request)).await
+            .map_err(|_| PreviewError::ExternalServiceError {
+                service: "MCP".to_string(),
+                message: format!("Request timed out after {} seconds", self.config.browser_timeout),
+            })?
+    }
+    
+    async fn exchange_http(
+        &self,
+        endpoint: &str,
+        request: &McpRequest,
+    ) -> Result<McpResponse, PreviewError> {
+        let mut response = self.post_http(endpoint, request).await?;
+        let is_sse = response.headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.starts_with("text/event-stream"));
+        
+        if !is_sse {
+            return response.json::<McpResponse>().await
+                .map_err(|e| PreviewError::ParseError(format!(
+                    "Failed to parse MCP response: {}", e
+                )));
+        }
+        
+        // Read events until the one answering this request arrives
+        let mut buffer = String::new();
+        loop {
+            let chunk = response.chunk().await
+                .map_err(|e| PreviewError::ExternalServiceError {
+                    service: "MCP".to_string(),
+                    message: format!("Failed to read SSE stream: {}", e),
+                })?
+                .ok_or_else(|| PreviewError::ExternalServiceError {
+                    service: "MCP".to_string(),
+                    message: "SSE stream ended before a response arrived".to_string(),
+                })?;
+            buffer.push_str(&String::from_utf8_lossy(&chunk));
+            
+            for data in drain_sse_events(&mut buffer) {
+                #[cfg(feature = "logging")]
+                debug!("Received SSE message: {}", data);
+                
+                if let Ok(response) = serde_json::from_str::<McpResponse>(&data) {
+                    if response.id == request.id {
+                        return Ok(response);
+                    }
+                }
+            }
+        }
+    }
+    
+    async fn send_request_stdio(&self, request: McpRequest) -> Result<McpResponse, PreviewError> {
         let mut process_guard = self.process.lock().await;
         let child = process_guard.as_mut()
             .ok_or_else(|| PreviewError::ExternalServiceError {
@@ -554,16 +848,21 @@ impl McpClient {
             .map(|s| s.to_string())
     }
     
-    /// Wait for page to load
+    /// Wait for page to load, using the configured [`WaitStrategy`]
     pub async fn wait_for_load(&self) -> Result<(), PreviewError> {
+        self.wait_for(&self.config.wait_strategy).await
+    }
+    
+    /// Wait for page to load using `strategy`
+    pub async fn wait_for(&self, strategy: &WaitStrategy) -> Result<(), PreviewError> {
+        let limit = Duration::from_millis(self.config.browser_timeout * 500);
+        let (tool, arguments) = strategy.tool_call(limit);
         let request = McpRequest {
             jsonrpc: "2.0".to_string(),
             method: "tools/call".to_string(),
             params: serde_json::json!({
-                "name": "browser_wait_for",
-                "arguments": {
-                    "time": 2
-                }
+                "name": tool,
+                "arguments": arguments
             }),
             id: self.next_request_id().await,
         };
@@ -579,16 +878,41 @@ impl McpClient {
     }
 }
 
+/// Removes complete events from `buffer` and returns their `data` payloads.
+/// A trailing partial event is left in the buffer for the next chunk.
+fn drain_sse_events(buffer: &mut String) -> Vec<String> {
+    if buffer.contains('\r') {
+        *buffer = buffer.replace("\r\n", "\n");
+    }
+    
+    let mut events = Vec::new();
+    while let Some(end) = buffer.find("\n\n") {
+        let event: String = buffer.drain(..end + 2).collect();
+        let data: Vec<&str> = event
+            .lines()
+            .filter_map(|line| line.strip_prefix("data:"))
+            .map(|value| value.strip_prefix(' ').unwrap_or(value))
+            .collect();
+        if !data.is_empty() {
+            events.push(data.join("\n"));
+        }
+    }
+    events
+}
+
 impl Drop for McpClient {
     fn drop(&mut self) {
-        // Clean up the process when the client is dropped
+        // Clean up the process when the client is dropped. Without a runtime the
+        // child is still killed, without waiting, by `kill_on_drop`.
         let process = self.process.clone();
-        tokio::spawn(async move {
-            let mut process_guard = process.lock().await;
-            if let Some(mut child) = process_guard.take() {
-                let _ = child.kill().await;
-            }
-        });
+        if let Ok(handle) = tokio::runtime::Handle::try_current() {
+            handle.spawn(async move {
+                let mut process_guard = process.lock().await;
+                if let Some(mut child) = process_guard.take() {
+                    let _ = child.kill().await;
+                }
+            });
+        }
     }
 }
 
@@ -604,9 +928,75 @@ mod tests {
         assert_eq!(config.max_sessions, 5);
     }
     
+    #[test]
+    fn test_drain_sse_events() {
+        let mut buffer = String::from(
+            "event: message\r\ndata: {\"id\":1}\r\n\r\n: keep-alive\n\ndata: line one\ndata: line two\n\ndata: {\"par",
+        );
+        assert_eq!(
+            drain_sse_events(&mut buffer),
+            vec!["{\"id\":1}".to_string(), "line one\nline two".to_string()]
+        );
+        assert_eq!(buffer, "data: {\"par");
+        
+        buffer.push_str("tial\":true}\n\n");
+        assert_eq!(drain_sse_events(&mut buffer), vec!["{\"partial\":true}".to_string()]);
+        assert!(buffer.is_empty());
+    }
+    
     #[test]
     fn test_browser_usage_policy() {
         assert_ne!(BrowserUsagePolicy::Always, BrowserUsagePolicy::Never);
         assert_eq!(BrowserUsagePolicy::Auto, BrowserUsagePolicy::Auto);
     }
-}
\ No newline at end of file
+    
+    #[tokio::test]
+    async fn test_missing_server_binary_is_browser_unavailable() {
+        let client = McpClient::new(McpConfig {
+            enabled: true,
+            server_command: vec!["/nonexistent/mcp-server".to_string()],
+            ..Default::default()
+        });
+        
+        match client.start().await {
+            Err(PreviewError::BrowserUnavailable(message)) => {
+                assert!(message.contains("/nonexistent/mcp-server"));
+                assert!(message.contains("@playwright/mcp"));
+            }
+            other => panic!("expected BrowserUnavailable, got {:?}", other),
+        }
+    }
+    
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_other_spawn_failures_are_external_service_errors() {
+        // A directory exists but cannot be executed
+        let client = McpClient::new(McpConfig {
+            enabled: true,
+            server_command: vec![std::env::temp_dir().display().to_string()],
+            ..Default::default()
+        });
+        
+        assert!(matches!(
+            client.start().await,
+            Err(PreviewError::ExternalServiceError { .. })
+        ));
+    }
+    
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_handshake_timeout_is_browser_unavailable() {
+        let client = McpClient::new(McpConfig {
+            enabled: true,
+            server_command: vec!["sleep".to_string(), "30".to_string()],
+            browser_timeout: 1,
+            ..Default::default()
+        });
+        
+        assert!(matches!(
+            client.start().await,
+            Err(PreviewError::BrowserUnavailable(_))
+        ));
+        assert!(client.process.lock().await.is_none());
+    }
+}
@@ -0,0 +1,31 @@
+//! Records preview and fetch activity through the [`metrics`](::metrics) facade
+//!
+//! Nothing is exported until the application installs a recorder, e.g. one from
+//! `metrics-exporter-prometheus`. The emitted metrics are:
+//!
+//! - `url_preview_requests_total`: every preview request handled by `PreviewService`
+//! - `url_preview_errors_total{kind}`: failed previews, labelled with
+//!   [`PreviewError::kind`]
+//! - `url_preview_fetch_duration_seconds{outcome}`: latency of `Fetcher` page
+//!   fetches, labelled `success` or `error`
+
+use crate::observer::FetchOutcome;
+use crate::PreviewError;
+use std::time::Duration;
+
+pub(crate) fn record_preview<T>(result: &Result<T, PreviewError>) {
+    ::metrics::counter!("url_preview_requests_total").increment(1);
+    if let Err(error) = result {
+        ::metrics::counter!("url_preview_errors_total", "kind" => error.kind()).increment(1);
+    }
+}
+
+pub(crate) fn record_fetch<T>(result: &Result<T, PreviewError>, duration: Duration) {
+    let outcome = if result.is_ok() {
+        FetchOutcome::Success
+    } else {
+        FetchOutcome::Error
+    };
+    ::metrics::histogram!("url_preview_fetch_duration_seconds", "outcome" => outcome.as_str())
+        .record(duration.as_secs_f64());
+}
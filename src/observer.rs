@@ -0,0 +1,34 @@
+//! Metrics hooks for observing fetch and cache activity
+//!
+//! Implement [`MetricsObserver`] and register it through
+//! `PreviewServiceConfig::with_metrics_observer` to feed your own metrics backend.
+
+use std::time::Duration;
+
+/// Outcome of a single fetch performed by a preview generator
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FetchOutcome {
+    Success,
+    Error,
+}
+
+impl FetchOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FetchOutcome::Success => "success",
+            FetchOutcome::Error => "error",
+        }
+    }
+}
+
+/// Receives events from preview generation. All methods default to no-ops.
+pub trait MetricsObserver: Send + Sync {
+    /// Called after every network fetch with its outcome and latency
+    fn on_fetch(&self, _outcome: FetchOutcome, _duration: Duration) {}
+
+    /// Called when a preview is served from cache
+    fn on_cache_hit(&self) {}
+
+    /// Called when a cache lookup finds nothing
+    fn on_cache_miss(&self) {}
+}
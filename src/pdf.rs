@@ -0,0 +1,69 @@
+//! Reads the document information dictionary of PDF responses
+
+use crate::fetcher::PdfInfo;
+use lopdf::{Dictionary, Document, Object};
+
+/// Title, author and subject from a PDF's `/Info` dictionary. A document that
+/// cannot be parsed, e.g. an encrypted one, yields empty info.
+pub(crate) fn read_info(bytes: &[u8]) -> PdfInfo {
+    let Ok(document) = Document::load_mem(bytes) else {
+        return PdfInfo::default();
+    };
+    let info = match document.trailer.get(b"Info") {
+        Ok(Object::Reference(id)) => document.get_dictionary(*id).ok(),
+        Ok(Object::Dictionary(dictionary)) => Some(dictionary),
+        _ => None,
+    };
+    let Some(info) = info else {
+        return PdfInfo::default();
+    };
+
+    PdfInfo {
+        title: text_entry(info, b"Title"),
+        author: text_entry(info, b"Author"),
+        subject: text_entry(info, b"Subject"),
+    }
+}
+
+fn text_entry(info: &Dictionary, key: &[u8]) -> Option<String> {
+    match info.get(key) {
+        Ok(Object::String(bytes, _)) => Some(decode_text_string(bytes))
+            .map(|text| text.trim().to_string())
+            .filter(|text| !text.is_empty()),
+        _ => None,
+    }
+}
+
+/// Decodes a PDF text string: UTF-16BE when it starts with a byte order mark,
+/// otherwise PDFDocEncoding, which agrees with Latin-1 for printable text
+fn decode_text_string(bytes: &[u8]) -> String {
+    match bytes.strip_prefix(&[0xFE, 0xFF]) {
+        Some(utf16) => {
+            let units: Vec<u16> = utf16
+                .chunks_exact(2)
+                .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+                .collect();
+            String::from_utf16_lossy(&units)
+        }
+        None => bytes.iter().map(|&byte| char::from(byte)).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_text_string() {
+        assert_eq!(decode_text_string(b"Caf\xe9"), "Café");
+        assert_eq!(
+            decode_text_string(&[0xFE, 0xFF, 0x00, 0x50, 0x00, 0xE9, 0x4E, 0x2D]),
+            "Pé中"
+        );
+    }
+
+    #[test]
+    fn test_read_info_of_invalid_document_is_empty() {
+        assert_eq!(read_info(b"not a pdf"), PdfInfo::default());
+    }
+}
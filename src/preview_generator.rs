@@ -1,25 +1,41 @@
 use crate::fetcher::FetchResult;
+use crate::observer::{FetchOutcome, MetricsObserver};
 #[cfg(feature = "cache")]
-use crate::Cache;
-use crate::{Fetcher, MetadataExtractor, Preview, PreviewError, PreviewGenerator};
+use crate::{Cache, PreviewCache};
+use crate::{
+    is_vimeo_url, is_youtube_url, ExtractorConfig, Fetcher, MetadataExtractor, Preview,
+    PreviewError, PreviewGenerator, PreviewWithMeta,
+};
 use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use url::Url;
 
 #[derive(Clone, Copy, Default)]
 pub enum CacheStrategy {
     #[default]
     UseCache,
+    /// Like `UseCache`, but entries written by the generator expire after the given TTL
+    UseCacheWithTtl(Duration),
     NoCache,
     ForceUpdate,
 }
 
+impl CacheStrategy {
+    /// Whether previews are looked up in and written to the cache
+    pub fn uses_cache(&self) -> bool {
+        matches!(self, CacheStrategy::UseCache | CacheStrategy::UseCacheWithTtl(_))
+    }
+}
+
 #[derive(Clone)]
 pub struct UrlPreviewGenerator {
     #[cfg(feature = "cache")]
-    pub cache: Cache,
+    pub cache: Arc<dyn PreviewCache>,
     pub cache_strategy: CacheStrategy,
     pub fetcher: Fetcher,
     extractor: MetadataExtractor,
+    pub(crate) metrics_observer: Option<Arc<dyn MetricsObserver>>,
 }
 
 impl UrlPreviewGenerator {
@@ -29,10 +45,11 @@ impl UrlPreviewGenerator {
     ) -> Self {
         Self {
             #[cfg(feature = "cache")]
-            cache: Cache::new(cache_capacity),
+            cache: Arc::new(Cache::new(cache_capacity)),
             cache_strategy,
             fetcher: Fetcher::new(),
             extractor: MetadataExtractor::new(),
+            metrics_observer: None,
         }
     }
 
@@ -43,42 +60,204 @@ impl UrlPreviewGenerator {
     ) -> Self {
         Self {
             #[cfg(feature = "cache")]
-            cache: Cache::new(cache_capacity),
+            cache: Arc::new(Cache::new(cache_capacity)),
             cache_strategy,
+            extractor: MetadataExtractor::new()
+                .with_max_parse_size(fetcher.content_limits().max_parse_size),
             fetcher,
-            extractor: MetadataExtractor::new(),
+            metrics_observer: None,
         }
     }
-}
 
-// For Twitter url and Normal url
-#[async_trait]
-impl PreviewGenerator for UrlPreviewGenerator {
-    async fn generate_preview(&self, url: &str) -> Result<Preview, PreviewError> {
+    /// Replaces the in-memory cache with another backend
+    #[cfg(feature = "cache")]
+    pub fn with_cache(mut self, cache: Arc<dyn PreviewCache>) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    pub fn with_metrics_observer(mut self, observer: Arc<dyn MetricsObserver>) -> Self {
+        self.metrics_observer = Some(observer);
+        self
+    }
+
+    pub fn with_extractor_config(mut self, config: ExtractorConfig) -> Self {
+        self.extractor = MetadataExtractor::with_config(config)
+            .with_max_parse_size(self.fetcher.content_limits().max_parse_size);
+        self
+    }
+
+    /// Store a preview according to the cache strategy
+    #[cfg(feature = "cache")]
+    pub(crate) async fn cache_preview(&self, url: &str, preview: &Preview) {
+        match self.cache_strategy {
+            CacheStrategy::UseCache => self.cache.set(url.to_string(), preview.clone()).await,
+            CacheStrategy::UseCacheWithTtl(ttl) => {
+                self.cache
+                    .set_with_ttl(url.to_string(), preview.clone(), ttl)
+                    .await
+            }
+            CacheStrategy::NoCache | CacheStrategy::ForceUpdate => {}
+        }
+    }
+
+    /// Generate a preview along with the response status, final URL and cache flag
+    pub async fn generate_preview_detailed(
+        &self,
+        url: &str,
+    ) -> Result<PreviewWithMeta, PreviewError> {
         #[cfg(feature = "cache")]
-        if let CacheStrategy::UseCache = self.cache_strategy {
+        let stale = if self.cache_strategy.uses_cache() {
             if let Some(cached) = self.cache.get(url).await {
-                return Ok(cached);
+                if let Some(observer) = &self.metrics_observer {
+                    observer.on_cache_hit();
+                }
+                return Ok(PreviewWithMeta {
+                    final_url: cached.url.clone(),
+                    preview: cached,
+                    status: None,
+                    from_cache: true,
+                });
             };
+            if let Some(observer) = &self.metrics_observer {
+                observer.on_cache_miss();
+            }
+            // An expired preview with validators is revalidated rather than refetched
+            self.cache.get_stale(url).await.filter(|cached| {
+                cached
+                    .http_metadata
+                    .as_ref()
+                    .is_some_and(crate::HttpMeta::has_validators)
+            })
+        } else {
+            None
         };
 
         let _ = Url::parse(url)?;
-        let content = self.fetcher.fetch(url).await?;
+        let started = Instant::now();
+        #[cfg(feature = "cache")]
+        let validators = stale
+            .as_ref()
+            .and_then(|cached| cached.http_metadata.as_ref());
+        #[cfg(feature = "cache")]
+        let response = match validators {
+            Some(validators) => self.fetcher.revalidate(url, validators).await,
+            None => self.fetcher.fetch_detailed(url).await,
+        };
+        #[cfg(not(feature = "cache"))]
+        let response = self.fetcher.fetch_detailed(url).await;
+        if let Some(observer) = &self.metrics_observer {
+            let outcome = if response.is_ok() {
+                FetchOutcome::Success
+            } else {
+                FetchOutcome::Error
+            };
+            observer.on_fetch(outcome, started.elapsed());
+        }
+        let response = response?;
 
-        let mut preview = match content {
+        #[cfg(feature = "cache")]
+        if let (FetchResult::NotModified, Some(mut cached)) = (&response.result, stale) {
+            // Keep the cached preview, restarting its TTL
+            if let Some(newer) = response.http_metadata {
+                cached
+                    .http_metadata
+                    .get_or_insert_with(Default::default)
+                    .update(newer);
+            }
+            self.cache_preview(url, &cached).await;
+            return Ok(PreviewWithMeta {
+                preview: cached,
+                status: Some(response.status),
+                final_url: response.final_url,
+                from_cache: true,
+            });
+        }
+
+        let mut preview = match response.result {
+            FetchResult::OEmbed(oembed) if is_youtube_url(url) => {
+                self.extractor.extract_from_youtube_oembed(&oembed)
+            }
+            FetchResult::OEmbed(oembed) if is_vimeo_url(url) => {
+                self.extractor.extract_from_vimeo_oembed(&oembed)
+            }
             FetchResult::OEmbed(oembed) => self
                 .extractor
                 .extract_from_oembed(&oembed.html)
                 .ok_or_else(|| {
                     PreviewError::ExtractError("Failed to extract from oEmbed".into())
                 })?,
-            FetchResult::Html(html) => self.extractor.extract(&html, url)?,
+            // Resolve relative URLs against where the page was actually served from
+            FetchResult::Html(html) => self.extractor.extract(&html, &response.final_url)?,
+            FetchResult::Image(image) => self.extractor.extract_from_image(&image, url),
+            FetchResult::Pdf(pdf) => self.extractor.extract_from_pdf(&pdf, url),
+            FetchResult::NotModified => {
+                return Err(PreviewError::ExtractError(
+                    "Not Modified without a cached preview".into(),
+                ))
+            }
         };
         preview.url = url.to_string();
+        preview.resolved_url = Some(response.final_url.clone());
+        preview.http_metadata = response.http_metadata;
         #[cfg(feature = "cache")]
-        if let CacheStrategy::UseCache = self.cache_strategy {
-            self.cache.set(url.to_string(), preview.clone()).await;
+        self.cache_preview(url, &preview).await;
+        Ok(PreviewWithMeta {
+            preview,
+            status: Some(response.status),
+            final_url: response.final_url,
+            from_cache: false,
+        })
+    }
+
+    /// Build a preview from a response the caller has already received, e.g. from
+    /// their own authenticated client, without fetching the page again. `url` is
+    /// validated as usual and the body is read within the fetcher's content limits.
+    /// The cache is neither consulted nor updated.
+    pub async fn preview_from_response(
+        &self,
+        response: reqwest::Response,
+        url: &str,
+    ) -> Result<Preview, PreviewError> {
+        self.fetcher.url_validator().validate(url)?;
+        let page = self.fetcher.read_response(response).await?;
+        let mut preview = match page.result {
+            FetchResult::Html(html) => self.extractor.extract(&html, &page.final_url)?,
+            FetchResult::Image(image) => self.extractor.extract_from_image(&image, url),
+            FetchResult::Pdf(pdf) => self.extractor.extract_from_pdf(&pdf, url),
+            FetchResult::OEmbed(_) | FetchResult::NotModified => {
+                return Err(PreviewError::ExtractError(
+                    "Expected an HTML response".into(),
+                ))
+            }
         };
+        preview.url = url.to_string();
+        preview.resolved_url = Some(page.final_url);
+        preview.http_metadata = page.http_metadata;
         Ok(preview)
     }
+
+    /// Build a preview from HTML the caller has already fetched, without any network
+    /// access. `url` goes through the fetcher's URL validation and is used to resolve
+    /// relative links. The cache is neither consulted nor updated.
+    pub fn generate_preview_from_html(
+        &self,
+        url: &str,
+        html: &str,
+    ) -> Result<Preview, PreviewError> {
+        self.fetcher.url_validator().validate(url)?;
+        let mut preview = self.extractor.extract(html, url)?;
+        preview.url = url.to_string();
+        Ok(preview)
+    }
+}
+
+// For Twitter url and Normal url
+#[async_trait]
+impl PreviewGenerator for UrlPreviewGenerator {
+    async fn generate_preview(&self, url: &str) -> Result<Preview, PreviewError> {
+        self.generate_preview_detailed(url)
+            .await
+            .map(|detailed| detailed.preview)
+    }
 }
@@ -1,16 +1,36 @@
 #[cfg(feature = "github")]
 use crate::github_types::{is_github_url, GitHubDetailedInfo};
+use crate::observer::{FetchOutcome, MetricsObserver};
+#[cfg(feature = "gitlab")]
+use crate::gitlab_types::{is_gitlab_url, parse_gitlab_url};
+#[cfg(feature = "mastodon")]
+use crate::mastodon_types::is_mastodon_url;
+#[cfg(feature = "reddit")]
+use crate::reddit_types::is_reddit_url;
 use crate::{
-    is_twitter_url, CacheStrategy, Fetcher, Preview, PreviewError, PreviewGenerator,
-    UrlPreviewGenerator,
+    is_twitter_url, CacheStrategy, ContentLimits, ExtractorConfig, Fetcher, FetcherConfig,
+    PlatformHandler, Preview, PreviewError, PreviewGenerator, PreviewWithMeta, ProbeResult,
+    UrlPreviewGenerator, UrlValidationConfig,
 };
 #[cfg(feature = "browser")]
 use crate::browser_fetcher::BrowserPreviewService;
+#[cfg(feature = "cache")]
+use crate::CacheStats;
 #[cfg(feature = "browser")]
 use crate::mcp_client::{McpConfig, BrowserUsagePolicy};
+use dashmap::DashMap;
 use std::sync::Arc;
-use tokio::sync::Semaphore;
-#[cfg(all(feature = "logging", feature = "github"))]
+use std::time::Duration;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+#[cfg(all(
+    feature = "logging",
+    any(
+        feature = "github",
+        feature = "reddit",
+        feature = "gitlab",
+        feature = "mastodon"
+    )
+))]
 use tracing::warn;
 #[cfg(feature = "logging")]
 use tracing::{debug, instrument};
@@ -27,12 +47,52 @@ pub struct PreviewService {
     pub github_generator: Arc<UrlPreviewGenerator>,
     #[cfg(feature = "browser")]
     pub browser_service: Option<Arc<BrowserPreviewService>>,
+    /// Custom handlers, tried in order before the built-in ones
+    handlers: Vec<Arc<dyn PlatformHandler>>,
     // Max Concurrent Requests
     semaphore: Arc<Semaphore>,
+    /// Present when `max_per_host_requests` is configured
+    host_limiter: Option<Arc<HostLimiter>>,
 }
 
 pub const MAX_CONCURRENT_REQUESTS: usize = 500;
 
+/// Idle hosts are dropped from the per-host limiter once it tracks more than this many
+const HOST_LIMITER_PRUNE_THRESHOLD: usize = 1024;
+
+/// Caps concurrent previews per URL host, on top of the global semaphore
+struct HostLimiter {
+    max_per_host: usize,
+    semaphores: DashMap<String, Arc<Semaphore>>,
+}
+
+impl HostLimiter {
+    fn new(max_per_host: usize) -> Self {
+        Self {
+            max_per_host: max_per_host.max(1),
+            semaphores: DashMap::new(),
+        }
+    }
+
+    /// The semaphore for the URL's host, or `None` if the URL has no host
+    fn semaphore(&self, url: &str) -> Option<Arc<Semaphore>> {
+        let host = Url::parse(url).ok()?.host_str()?.to_ascii_lowercase();
+
+        if self.semaphores.len() > HOST_LIMITER_PRUNE_THRESHOLD {
+            // Entries only the map references have no waiters or holders
+            self.semaphores
+                .retain(|_, semaphore| Arc::strong_count(semaphore) > 1);
+        }
+
+        Some(
+            self.semaphores
+                .entry(host)
+                .or_insert_with(|| Arc::new(Semaphore::new(self.max_per_host)))
+                .clone(),
+        )
+    }
+}
+
 impl Default for PreviewService {
     fn default() -> Self {
         Self::new()
@@ -89,7 +149,9 @@ impl PreviewService {
             github_generator,
             #[cfg(feature = "browser")]
             browser_service: None,
+            handlers: Vec::new(),
             semaphore,
+            host_limiter: None,
         }
     }
 
@@ -130,46 +192,65 @@ impl PreviewService {
             github_generator,
             #[cfg(feature = "browser")]
             browser_service: None,
+            handlers: Vec::new(),
             semaphore,
+            host_limiter: None,
         }
     }
 
+    /// Starts a [`PreviewServiceBuilder`] for configuring URL validation and content
+    /// limits without assembling the fetcher configs by hand
+    pub fn builder() -> PreviewServiceBuilder {
+        PreviewServiceBuilder::new()
+    }
+
     pub fn new_with_config(config: PreviewServiceConfig) -> Self {
         #[cfg(feature = "logging")]
         debug!("Initializing PreviewService with custom configuration");
 
-        let default_generator = Arc::new(UrlPreviewGenerator::new_with_fetcher(
+        let with_observer = |generator: UrlPreviewGenerator| {
+            let generator = generator.with_extractor_config(config.extractor_config.clone());
+            match &config.metrics_observer {
+                Some(observer) => generator.with_metrics_observer(observer.clone()),
+                None => generator,
+            }
+        };
+
+        let default_generator = Arc::new(with_observer(UrlPreviewGenerator::new_with_fetcher(
             config.cache_capacity,
             config.cache_strategy,
             config.default_fetcher.unwrap_or_default(),
-        ));
+        )));
 
         #[cfg(feature = "twitter")]
-        let twitter_generator = Arc::new(UrlPreviewGenerator::new_with_fetcher(
+        let twitter_generator = Arc::new(with_observer(UrlPreviewGenerator::new_with_fetcher(
             config.cache_capacity,
             config.cache_strategy,
             config
                 .twitter_fetcher
                 .unwrap_or_else(Fetcher::new_twitter_client),
-        ));
+        )));
 
         #[cfg(feature = "github")]
-        let github_generator = Arc::new(UrlPreviewGenerator::new_with_fetcher(
+        let github_generator = Arc::new(with_observer(UrlPreviewGenerator::new_with_fetcher(
             config.cache_capacity,
             config.cache_strategy,
             config
                 .github_fetcher
                 .unwrap_or_else(Fetcher::new_github_client),
-        ));
+        )));
 
         let semaphore = Arc::new(Semaphore::new(config.max_concurrent_requests));
+        let host_limiter = config
+            .max_per_host_requests
+            .map(|max_per_host| Arc::new(HostLimiter::new(max_per_host)));
         
         #[cfg(feature = "browser")]
         let browser_service = if let Some(mcp_config) = config.mcp_config {
-            Some(Arc::new(BrowserPreviewService::new(
-                mcp_config,
-                config.browser_usage_policy,
-            )))
+            Some(Arc::new(
+                BrowserPreviewService::new(mcp_config, config.browser_usage_policy)
+                    .with_fallback_threshold(config.browser_fallback_threshold),
+            ))
         } else {
             None
         };
@@ -185,7 +266,9 @@ impl PreviewService {
             github_generator,
             #[cfg(feature = "browser")]
             browser_service,
+            handlers: config.handlers,
             semaphore,
+            host_limiter,
         }
     }
 
@@ -203,13 +286,146 @@ impl PreviewService {
         None
     }
 
+    /// Previews a Reddit post from its `.json` endpoint, falling back to the generic
+    /// generator for galleries, crossposts and failed API requests
+    #[cfg(feature = "reddit")]
+    async fn generate_reddit_preview_detailed(
+        &self,
+        url: &str,
+    ) -> Result<PreviewWithMeta, PreviewError> {
+        let post = self.default_generator.fetcher.fetch_reddit_post(url);
+        self.generate_api_preview_detailed(url, "Reddit", post)
+            .await
+    }
+
+    /// Previews a GitLab project from the GitLab API, falling back to the generic
+    /// generator when the API request fails
+    #[cfg(feature = "gitlab")]
+    async fn generate_gitlab_preview_detailed(
+        &self,
+        url: &str,
+    ) -> Result<PreviewWithMeta, PreviewError> {
+        let project = async {
+            let (namespace, project) = parse_gitlab_url(url).ok_or_else(|| {
+                PreviewError::InvalidUrl(format!("Not a GitLab project URL: {url}"))
+            })?;
+            self.default_generator
+                .fetcher
+                .fetch_gitlab_project(&namespace, &project)
+                .await
+                .map(|project| project.into_preview(url))
+        };
+        self.generate_api_preview_detailed(url, "GitLab", project)
+            .await
+    }
+
+    /// Previews a Mastodon status from the instance API, falling back to the generic
+    /// generator for hosts that turn out not to be fediverse instances
+    #[cfg(feature = "mastodon")]
+    async fn generate_mastodon_preview_detailed(
+        &self,
+        url: &str,
+    ) -> Result<PreviewWithMeta, PreviewError> {
+        let status = self.default_generator.fetcher.fetch_mastodon_status(url);
+        self.generate_api_preview_detailed(url, "Mastodon", status)
+            .await
+    }
+
+    /// Serves `url` from the default generator's cache, or else from `fetch` (a
+    /// platform API call), falling back to generic HTML extraction if it fails
+    #[cfg(any(feature = "reddit", feature = "gitlab", feature = "mastodon"))]
+    async fn generate_api_preview_detailed(
+        &self,
+        url: &str,
+        #[allow(unused_variables)] platform: &str,
+        fetch: impl std::future::Future<Output = Result<Preview, PreviewError>>,
+    ) -> Result<PreviewWithMeta, PreviewError> {
+        let generator = &self.default_generator;
+        let observer = generator.metrics_observer.as_ref();
+
+        #[cfg(feature = "cache")]
+        if generator.cache_strategy.uses_cache() {
+            if let Some(cached) = generator.cache.get(url).await {
+                if let Some(observer) = observer {
+                    observer.on_cache_hit();
+                }
+                return Ok(PreviewWithMeta {
+                    final_url: cached.url.clone(),
+                    preview: cached,
+                    status: None,
+                    from_cache: true,
+                });
+            }
+            if let Some(observer) = observer {
+                observer.on_cache_miss();
+            }
+        }
+
+        let started = std::time::Instant::now();
+        let result = fetch.await;
+        if let Some(observer) = observer {
+            let outcome = if result.is_ok() {
+                FetchOutcome::Success
+            } else {
+                FetchOutcome::Error
+            };
+            observer.on_fetch(outcome, started.elapsed());
+        }
+
+        match result {
+            Ok(preview) => {
+                #[cfg(feature = "cache")]
+                generator.cache_preview(url, &preview).await;
+
+                Ok(PreviewWithMeta {
+                    preview,
+                    status: None,
+                    final_url: url.to_string(),
+                    from_cache: false,
+                })
+            }
+            Err(_e) => {
+                #[cfg(feature = "logging")]
+                warn!(
+                    error = ?_e,
+                    platform = %platform,
+                    "Platform API preview failed, will use general preview generator as fallback"
+                );
+                generator.generate_preview_detailed(url).await
+            }
+        }
+    }
+
     #[cfg(feature = "github")]
     #[cfg_attr(feature = "logging", instrument(level = "debug", skip(self)))]
     async fn generate_github_preview(&self, url: &str) -> Result<Preview, PreviewError> {
+        self.generate_github_preview_detailed(url)
+            .await
+            .map(|detailed| detailed.preview)
+    }
+
+    #[cfg(feature = "github")]
+    async fn generate_github_preview_detailed(
+        &self,
+        url: &str,
+    ) -> Result<PreviewWithMeta, PreviewError> {
+        let observer = self.github_generator.metrics_observer.as_ref();
+
         #[cfg(feature = "cache")]
-        if let CacheStrategy::UseCache = self.github_generator.cache_strategy {
+        if self.github_generator.cache_strategy.uses_cache() {
             if let Some(cached) = self.github_generator.cache.get(url).await {
-                return Ok(cached);
+                if let Some(observer) = observer {
+                    observer.on_cache_hit();
+                }
+                return Ok(PreviewWithMeta {
+                    final_url: cached.url.clone(),
+                    preview: cached,
+                    status: None,
+                    from_cache: true,
+                });
+            }
+            if let Some(observer) = observer {
+                observer.on_cache_miss();
             }
         }
 
@@ -219,12 +435,22 @@ impl PreviewService {
             PreviewError::ExtractError("Invalid GitHub URL format".into())
         })?;
 
-        match self
+        let started = std::time::Instant::now();
+        let basic_preview = self
             .github_generator
             .fetcher
             .fetch_github_basic_preview(&owner, &repo_name)
-            .await
-        {
+            .await;
+        if let Some(observer) = observer {
+            let outcome = if basic_preview.is_ok() {
+                FetchOutcome::Success
+            } else {
+                FetchOutcome::Error
+            };
+            observer.on_fetch(outcome, started.elapsed());
+        }
+
+        match basic_preview {
             Ok(basic_info) => {
                 #[cfg(feature = "logging")]
                 debug!("Found GitHub Repo {}/{} basic infos", owner, repo_name);
@@ -238,17 +464,18 @@ impl PreviewService {
                     favicon: Some(
                         "https://github.githubassets.com/favicons/favicon.svg".to_string(),
                     ),
+                    ..Default::default()
                 };
 
                 #[cfg(feature = "cache")]
-                if let CacheStrategy::UseCache = self.github_generator.cache_strategy {
-                    self.github_generator
-                        .cache
-                        .set(url.to_string(), preview.clone())
-                        .await;
-                }
+                self.github_generator.cache_preview(url, &preview).await;
 
-                Ok(preview)
+                Ok(PreviewWithMeta {
+                    preview,
+                    status: None,
+                    final_url: url.to_string(),
+                    from_cache: false,
+                })
             }
             Err(_e) => {
                 #[cfg(feature = "logging")]
@@ -256,24 +483,301 @@ impl PreviewService {
                     error = ?_e,
                     "Failed to get GitHub basic preview, will use general preview generator as fallback"
                 );
-                self.github_generator.generate_preview(url).await
+                self.github_generator.generate_preview_detailed(url).await
             }
         }
     }
 
-    #[cfg_attr(feature = "logging", instrument(level = "debug", skip(self)))]
+    #[cfg_attr(
+        feature = "logging",
+        instrument(
+            level = "debug",
+            skip(self, url),
+            fields(
+                http.url = %url,
+                http.status_code = tracing::field::Empty,
+                url_preview.cache_hit = tracing::field::Empty,
+            )
+        )
+    )]
     pub async fn generate_preview(&self, url: &str) -> Result<Preview, PreviewError> {
         #[cfg(feature = "logging")]
         debug!("Starting preview generation for URL: {}", url);
 
+        let _host_permit = self.acquire_host_permit(url, true).await?;
+        let _permit = self
+            .semaphore
+            .acquire()
+            .await
+            .map_err(|_| PreviewError::ConcurrencyLimitError)?;
+
+        let detailed = self.generate_preview_with_permit(url).await?;
+        #[cfg(feature = "logging")]
+        {
+            let span = tracing::Span::current();
+            span.record("url_preview.cache_hit", detailed.from_cache);
+            if let Some(status) = detailed.status {
+                span.record("http.status_code", status);
+            }
+            if detailed.from_cache {
+                debug!(url_preview.cache_hit = true, "Preview served from cache");
+            } else {
+                debug!(
+                    url_preview.cache_hit = false,
+                    "Preview generated after cache miss"
+                );
+            }
+        }
+        Ok(detailed.preview)
+    }
+
+    /// Like [`generate_preview`](Self::generate_preview), but also reports the
+    /// HTTP status, the final URL after redirects and whether the cache was hit
+    #[cfg_attr(feature = "logging", instrument(level = "debug", skip(self)))]
+    pub async fn generate_preview_detailed(
+        &self,
+        url: &str,
+    ) -> Result<PreviewWithMeta, PreviewError> {
+        let _host_permit = self.acquire_host_permit(url, true).await?;
         let _permit = self
             .semaphore
             .acquire()
             .await
             .map_err(|_| PreviewError::ConcurrencyLimitError)?;
 
-        let _ = Url::parse(url)
+        self.generate_preview_with_permit(url).await
+    }
+
+    /// Like [`generate_preview`](Self::generate_preview), but fails immediately with
+    /// `PreviewError::ConcurrencyLimitError` instead of waiting when no slot is free.
+    #[cfg_attr(feature = "logging", instrument(level = "debug", skip(self)))]
+    pub async fn try_generate_preview(&self, url: &str) -> Result<Preview, PreviewError> {
+        let _host_permit = self.acquire_host_permit(url, false).await?;
+        let _permit = self
+            .semaphore
+            .try_acquire()
+            .map_err(|_| PreviewError::ConcurrencyLimitError)?;
+
+        self.generate_preview_with_permit(url)
+            .await
+            .map(|detailed| detailed.preview)
+    }
+
+    /// Generates previews for a batch of URLs concurrently, bounded by
+    /// `max_concurrent_requests`. Unlike `Fetcher::fetch_batch`, a failing URL does
+    /// not abort the batch: every input gets its own result, in input order.
+    pub async fn generate_previews(
+        &self,
+        urls: &[&str],
+    ) -> Vec<(String, Result<Preview, PreviewError>)> {
+        let tasks = urls.iter().map(|url| async move {
+            let result = self.generate_preview(url).await;
+            (url.to_string(), result)
+        });
+        futures::future::join_all(tasks).await
+    }
+
+    /// Warms the cache with previews for `urls`, generated concurrently under the
+    /// same limits as [`generate_preview`](Self::generate_preview). URLs that are
+    /// already cached are skipped; the previews themselves are discarded.
+    pub async fn prefetch(&self, urls: &[&str]) -> Vec<Result<(), PreviewError>> {
+        let tasks = urls.iter().map(|url| async move {
+            #[cfg(feature = "cache")]
+            if self.generator_for(url).cache.contains(url).await {
+                return Ok(());
+            }
+            self.generate_preview_detailed(url).await.map(|_| ())
+        });
+        futures::future::join_all(tasks).await
+    }
+
+    /// Build a preview from HTML the caller has already fetched, without any network
+    /// access. The URL is validated by the generator `url` would be routed to, so
+    /// that generator's security policy and extractor settings apply.
+    pub fn generate_preview_from_html(
+        &self,
+        url: &str,
+        html: &str,
+    ) -> Result<Preview, PreviewError> {
+        self.generator_for(url)
+            .generate_preview_from_html(url, html)
+    }
+
+    /// The generator that handles page fetches (and holds cached previews) for
+    /// `url`, mirroring the routing in `generate_preview_with_permit`
+    fn generator_for(&self, #[allow(unused_variables)] url: &str) -> &UrlPreviewGenerator {
+        #[cfg(feature = "twitter")]
+        if is_twitter_url(url) {
+            return &self.twitter_generator;
+        }
+        #[cfg(feature = "github")]
+        if is_github_url(url) {
+            return &self.github_generator;
+        }
+        &self.default_generator
+    }
+
+    /// Runs the default fetcher's URL validation over a batch without any network
+    /// access, so rejected URLs can be filtered out before spending fetch budget
+    pub fn validate_many<S: AsRef<str>>(&self, urls: &[S]) -> Vec<Result<Url, PreviewError>> {
+        self.default_generator
+            .fetcher
+            .url_validator()
+            .validate_many(urls)
+    }
+
+    /// Cache counters summed across the default, Twitter and GitHub generators
+    #[cfg(feature = "cache")]
+    pub fn cache_stats(&self) -> CacheStats {
+        let generators = [
+            &self.default_generator,
+            #[cfg(feature = "twitter")]
+            &self.twitter_generator,
+            #[cfg(feature = "github")]
+            &self.github_generator,
+        ];
+        generators
+            .iter()
+            .map(|generator| generator.cache.stats())
+            .fold(CacheStats::default(), |total, stats| total + stats)
+    }
+
+    /// Drops any cached preview of `url` from the default, Twitter and GitHub
+    /// generators, so the next request for it is fetched again
+    #[cfg(feature = "cache")]
+    pub async fn invalidate(&self, url: &str) {
+        let generators = [
+            &self.default_generator,
+            #[cfg(feature = "twitter")]
+            &self.twitter_generator,
+            #[cfg(feature = "github")]
+            &self.github_generator,
+        ];
+        for generator in generators {
+            generator.cache.remove(url).await;
+        }
+    }
+
+    /// Stops the browser's MCP server, if one was started, and waits until it has
+    /// exited. Prefer this over relying on `Drop`, which can only schedule the
+    /// shutdown on a runtime that may already be going away. Browser previews are
+    /// unavailable afterwards and fall back to plain fetching.
+    pub async fn shutdown(&self) -> Result<(), PreviewError> {
+        #[cfg(feature = "browser")]
+        if let Some(browser_service) = &self.browser_service {
+            browser_service.shutdown().await?;
+        }
+        Ok(())
+    }
+
+    /// Number of preview slots currently free under `max_concurrent_requests`
+    pub fn available_permits(&self) -> usize {
+        self.semaphore.available_permits()
+    }
+
+    /// Takes a slot under `max_per_host_requests` for the URL's host, waiting for one
+    /// when `wait` is set and failing with `ConcurrencyLimitError` otherwise. The slot
+    /// is released when the returned permit is dropped, including on error paths.
+    async fn acquire_host_permit(
+        &self,
+        url: &str,
+        wait: bool,
+    ) -> Result<Option<OwnedSemaphorePermit>, PreviewError> {
+        let Some(semaphore) = self
+            .host_limiter
+            .as_ref()
+            .and_then(|limiter| limiter.semaphore(url))
+        else {
+            return Ok(None);
+        };
+
+        let permit = if wait {
+            semaphore.acquire_owned().await.ok()
+        } else {
+            semaphore.try_acquire_owned().ok()
+        };
+        permit
+            .map(Some)
+            .ok_or(PreviewError::ConcurrencyLimitError)
+    }
+
+    async fn generate_preview_with_permit(
+        &self,
+        url: &str,
+    ) -> Result<PreviewWithMeta, PreviewError> {
+        let result = self.route_preview(url).await;
+        #[cfg(feature = "metrics")]
+        crate::metrics::record_preview(&result);
+        result
+    }
+
+    /// Runs the first registered handler that matches `url`, if any. Handler previews
+    /// go through the default generator's cache and metrics observer.
+    async fn run_handler(
+        &self,
+        parsed: &Url,
+        url: &str,
+    ) -> Option<Result<PreviewWithMeta, PreviewError>> {
+        let handler = self
+            .handlers
+            .iter()
+            .find(|handler| handler.matches(parsed))?;
+        #[cfg(feature = "logging")]
+        debug!("Using registered platform handler for URL: {}", url);
+
+        let generator = &self.default_generator;
+        let observer = generator.metrics_observer.as_ref();
+
+        #[cfg(feature = "cache")]
+        if generator.cache_strategy.uses_cache() {
+            if let Some(cached) = generator.cache.get(url).await {
+                if let Some(observer) = observer {
+                    observer.on_cache_hit();
+                }
+                return Some(Ok(PreviewWithMeta {
+                    final_url: cached.url.clone(),
+                    preview: cached,
+                    status: None,
+                    from_cache: true,
+                }));
+            }
+            if let Some(observer) = observer {
+                observer.on_cache_miss();
+            }
+        }
+
+        let started = std::time::Instant::now();
+        let result = handler.generate(url, &generator.fetcher).await;
+        if let Some(observer) = observer {
+            let outcome = if result.is_ok() {
+                FetchOutcome::Success
+            } else {
+                FetchOutcome::Error
+            };
+            observer.on_fetch(outcome, started.elapsed());
+        }
+
+        let preview = match result {
+            Ok(preview) => preview,
+            Err(e) => return Some(Err(e)),
+        };
+        #[cfg(feature = "cache")]
+        generator.cache_preview(url, &preview).await;
+        Some(Ok(PreviewWithMeta {
+            preview,
+            status: None,
+            final_url: url.to_string(),
+            from_cache: false,
+        }))
+    }
+
+    async fn route_preview(&self, url: &str) -> Result<PreviewWithMeta, PreviewError> {
+        let parsed = Url::parse(url)
             .map_err(|e| PreviewError::ParseError(format!("Invalid URL format: {e}")))?;
+
+        if let Some(result) = self.run_handler(&parsed, url).await {
+            return result;
+        }
         
         // Try browser service first if available
         #[cfg(feature = "browser")]
@@ -283,7 +787,14 @@ impl PreviewService {
                 debug!("Using browser service for URL: {}", url);
                 
                 match browser_service.generate_preview(url).await {
-                    Ok(preview) => return Ok(preview),
+                    Ok(preview) => {
+                        return Ok(PreviewWithMeta {
+                            preview,
+                            status: None,
+                            final_url: url.to_string(),
+                            from_cache: false,
+                        })
+                    }
                     Err(_e) => {
                         #[cfg(feature = "logging")]
                         debug!("Browser service failed, falling back: {}", _e);
@@ -297,11 +808,11 @@ impl PreviewService {
             debug!("Detected Twitter URL, using specialized handler");
             #[cfg(feature = "twitter")]
             {
-                self.twitter_generator.generate_preview(url).await
+                self.twitter_generator.generate_preview_detailed(url).await
             }
             #[cfg(not(feature = "twitter"))]
             {
-                self.default_generator.generate_preview(url).await
+                self.default_generator.generate_preview_detailed(url).await
             }
         } else if cfg!(feature = "github") && {
             #[cfg(feature = "github")]
@@ -317,16 +828,85 @@ impl PreviewService {
             debug!("Detected GitHub URL, using specialized handler");
             #[cfg(feature = "github")]
             {
-                self.generate_github_preview(url).await
+                self.generate_github_preview_detailed(url).await
             }
             #[cfg(not(feature = "github"))]
             {
-                self.default_generator.generate_preview(url).await
+                self.default_generator.generate_preview_detailed(url).await
+            }
+        } else if cfg!(feature = "reddit") && {
+            #[cfg(feature = "reddit")]
+            {
+                is_reddit_url(url)
+            }
+            #[cfg(not(feature = "reddit"))]
+            {
+                false
+            }
+        } {
+            #[cfg(feature = "logging")]
+            debug!("Detected Reddit URL, using specialized handler");
+            #[cfg(feature = "reddit")]
+            {
+                self.generate_reddit_preview_detailed(url).await
+            }
+            #[cfg(not(feature = "reddit"))]
+            {
+                self.default_generator.generate_preview_detailed(url).await
+            }
+        } else if cfg!(feature = "gitlab") && {
+            #[cfg(feature = "gitlab")]
+            {
+                is_gitlab_url(url)
+            }
+            #[cfg(not(feature = "gitlab"))]
+            {
+                false
+            }
+        } {
+            #[cfg(feature = "logging")]
+            debug!("Detected GitLab URL, using specialized handler");
+            #[cfg(feature = "gitlab")]
+            {
+                self.generate_gitlab_preview_detailed(url).await
+            }
+            #[cfg(not(feature = "gitlab"))]
+            {
+                self.default_generator.generate_preview_detailed(url).await
+            }
+        } else if cfg!(feature = "mastodon") && {
+            #[cfg(feature = "mastodon")]
+            {
+                is_mastodon_url(url)
+            }
+            #[cfg(not(feature = "mastodon"))]
+            {
+                false
+            }
+        } {
+            #[cfg(feature = "logging")]
+            debug!("Detected Mastodon status URL, using specialized handler");
+            #[cfg(feature = "mastodon")]
+            {
+                self.generate_mastodon_preview_detailed(url).await
+            }
+            #[cfg(not(feature = "mastodon"))]
+            {
+                self.default_generator.generate_preview_detailed(url).await
             }
         } else {
             #[cfg(feature = "logging")]
             debug!("Using default URL handler");
-            self.default_generator.generate_preview(url).await
+            let result = self.default_generator.generate_preview_detailed(url).await;
+            #[cfg(feature = "browser")]
+            if let Some(browser_service) = &self.browser_service {
+                let mut meta = result?;
+                meta.preview = browser_service
+                    .complete_with_browser(url, meta.preview)
+                    .await;
+                return Ok(meta);
+            }
+            result
         }
     }
 
@@ -338,15 +918,20 @@ impl PreviewService {
         #[cfg(feature = "logging")]
         debug!("Starting preview generation for URL: {}", url);
 
+        let _host_permit = self.acquire_host_permit(url, true).await?;
         let _permit = self
             .semaphore
             .acquire()
             .await
             .map_err(|_| PreviewError::ConcurrencyLimitError)?;
 
-        let _ = Url::parse(url)
+        let parsed = Url::parse(url)
             .map_err(|e| PreviewError::ParseError(format!("Invalid URL format: {e}")))?;
 
+        if let Some(result) = self.run_handler(&parsed, url).await {
+            return result.map(|detailed| detailed.preview);
+        }
+
         if is_twitter_url(url) {
             #[cfg(feature = "logging")]
             debug!("Detected Twitter URL, using specialized handler");
@@ -378,6 +963,72 @@ impl PreviewService {
             {
                 self.default_generator.generate_preview(url).await
             }
+        } else if cfg!(feature = "reddit") && {
+            #[cfg(feature = "reddit")]
+            {
+                is_reddit_url(url)
+            }
+            #[cfg(not(feature = "reddit"))]
+            {
+                false
+            }
+        } {
+            #[cfg(feature = "logging")]
+            debug!("Detected Reddit URL, using specialized handler");
+            #[cfg(feature = "reddit")]
+            {
+                self.generate_reddit_preview_detailed(url)
+                    .await
+                    .map(|detailed| detailed.preview)
+            }
+            #[cfg(not(feature = "reddit"))]
+            {
+                self.default_generator.generate_preview(url).await
+            }
+        } else if cfg!(feature = "gitlab") && {
+            #[cfg(feature = "gitlab")]
+            {
+                is_gitlab_url(url)
+            }
+            #[cfg(not(feature = "gitlab"))]
+            {
+                false
+            }
+        } {
+            #[cfg(feature = "logging")]
+            debug!("Detected GitLab URL, using specialized handler");
+            #[cfg(feature = "gitlab")]
+            {
+                self.generate_gitlab_preview_detailed(url)
+                    .await
+                    .map(|detailed| detailed.preview)
+            }
+            #[cfg(not(feature = "gitlab"))]
+            {
+                self.default_generator.generate_preview(url).await
+            }
+        } else if cfg!(feature = "mastodon") && {
+            #[cfg(feature = "mastodon")]
+            {
+                is_mastodon_url(url)
+            }
+            #[cfg(not(feature = "mastodon"))]
+            {
+                false
+            }
+        } {
+            #[cfg(feature = "logging")]
+            debug!("Detected Mastodon status URL, using specialized handler");
+            #[cfg(feature = "mastodon")]
+            {
+                self.generate_mastodon_preview_detailed(url)
+                    .await
+                    .map(|detailed| detailed.preview)
+            }
+            #[cfg(not(feature = "mastodon"))]
+            {
+                self.default_generator.generate_preview(url).await
+            }
         } else {
             #[cfg(feature = "logging")]
             debug!("Using default URL handler");
@@ -385,6 +1036,13 @@ impl PreviewService {
         }
     }
 
+    /// Best-effort check of whether a URL is likely previewable, without
+    /// downloading the body. Only URL validation and a HEAD request are performed.
+    #[cfg_attr(feature = "logging", instrument(level = "debug", skip(self)))]
+    pub async fn probe(&self, url: &str) -> Result<ProbeResult, PreviewError> {
+        self.default_generator.fetcher.probe(url).await
+    }
+
     #[cfg(feature = "github")]
     pub async fn generate_github_basic_preview(&self, url: &str) -> Result<Preview, PreviewError> {
         let (owner, repo) = Self::extract_github_info(url)
@@ -403,6 +1061,7 @@ impl PreviewService {
             image_url: basic_info.image_url,
             site_name: Some("GitHub".to_string()),
             favicon: Some("https://github.githubassets.com/favicons/favicon.svg".to_string()),
+            ..Default::default()
         })
     }
 
@@ -439,7 +1098,9 @@ impl PreviewService {
             github_generator,
             #[cfg(feature = "browser")]
             browser_service: None,
+            handlers: Vec::new(),
             semaphore: Arc::new(Semaphore::new(10)),
+            host_limiter: None,
         }
     }
 
@@ -458,6 +1119,8 @@ pub struct PreviewServiceConfig {
     pub cache_capacity: usize,
     pub cache_strategy: CacheStrategy,
     pub max_concurrent_requests: usize,
+    /// Maximum concurrent previews per URL host (`None` leaves hosts unlimited)
+    pub max_per_host_requests: Option<usize>,
     pub default_fetcher: Option<Fetcher>,
     #[cfg(feature = "twitter")]
     pub twitter_fetcher: Option<Fetcher>,
@@ -467,6 +1130,14 @@ pub struct PreviewServiceConfig {
     pub mcp_config: Option<McpConfig>,
     #[cfg(feature = "browser")]
     pub browser_usage_policy: BrowserUsagePolicy,
+    /// Under [`BrowserUsagePolicy::Auto`], pages whose static preview has a
+    /// [`Preview::completeness`] below this are retried in the browser
+    #[cfg(feature = "browser")]
+    pub browser_fallback_threshold: f32,
+    pub metrics_observer: Option<Arc<dyn MetricsObserver>>,
+    pub extractor_config: ExtractorConfig,
+    /// Custom platform handlers, tried in order
+    pub handlers: Vec<Arc<dyn PlatformHandler>>,
 }
 
 impl PreviewServiceConfig {
@@ -475,6 +1146,7 @@ impl PreviewServiceConfig {
             cache_capacity,
             cache_strategy: CacheStrategy::UseCache,
             max_concurrent_requests: MAX_CONCURRENT_REQUESTS,
+            max_per_host_requests: None,
             default_fetcher: None,
             #[cfg(feature = "twitter")]
             twitter_fetcher: None,
@@ -484,6 +1156,11 @@ impl PreviewServiceConfig {
             mcp_config: None,
             #[cfg(feature = "browser")]
             browser_usage_policy: BrowserUsagePolicy::Auto,
+            #[cfg(feature = "browser")]
+            browser_fallback_threshold: crate::browser_fetcher::DEFAULT_FALLBACK_THRESHOLD,
+            metrics_observer: None,
+            extractor_config: ExtractorConfig::default(),
+            handlers: Vec::new(),
         }
     }
 
@@ -509,11 +1186,19 @@ impl PreviewServiceConfig {
         self
     }
 
+    pub fn with_max_per_host_requests(mut self, max_per_host_requests: usize) -> Self {
+        self.max_per_host_requests = Some(max_per_host_requests);
+        self
+    }
+
     pub fn with_cache_strategy(mut self, cache_strategy: CacheStrategy) -> Self {
         self.cache_strategy = cache_strategy;
         self
     }
     
+    /// Render pages that need JavaScript in a browser driven over MCP. The MCP
+    /// server is started on the first preview that uses it; concurrent first
+    /// previews share a single startup.
     #[cfg(feature = "browser")]
     pub fn with_mcp_config(mut self, mcp_config: McpConfig) -> Self {
         self.mcp_config = Some(mcp_config);
@@ -525,4 +1210,223 @@ impl PreviewServiceConfig {
         self.browser_usage_policy = policy;
         self
     }
+    
+    /// Retry pages in the browser when their static preview is less complete
+    /// than `threshold` (see [`Preview::completeness`]). Only applies under
+    /// [`BrowserUsagePolicy::Auto`]; `0.0` disables the retry.
+    #[cfg(feature = "browser")]
+    pub fn with_browser_fallback_threshold(mut self, threshold: f32) -> Self {
+        self.browser_fallback_threshold = threshold;
+        self
+    }
+
+    pub fn with_metrics_observer(mut self, observer: Arc<dyn MetricsObserver>) -> Self {
+        self.metrics_observer = Some(observer);
+        self
+    }
+
+    pub fn with_extractor_config(mut self, config: ExtractorConfig) -> Self {
+        self.extractor_config = config;
+        self
+    }
+
+    /// Registers a handler for the URLs it matches. Handlers are tried in the order
+    /// they were registered, before the built-in platform handling.
+    pub fn with_handler(mut self, handler: Arc<dyn PlatformHandler>) -> Self {
+        self.handlers.push(handler);
+        self
+    }
+}
+
+/// Fluent builder for a [`PreviewService`] whose default, Twitter and GitHub
+/// fetchers share one URL validation policy and set of content limits
+///
+/// ```rust,no_run
+/// use std::time::Duration;
+/// use url_preview::PreviewService;
+///
+/// let service = PreviewService::builder()
+///     .allow_domain("example.com")
+///     .https_only()
+///     .max_content_size(2 * 1024 * 1024)
+///     .timeout(Duration::from_secs(5))
+///     .build();
+/// ```
+pub struct PreviewServiceBuilder {
+    url_validation: UrlValidationConfig,
+    content_limits: ContentLimits,
+    /// Overrides each fetcher's own default timeout when set
+    timeout: Option<Duration>,
+    config: PreviewServiceConfig,
+}
+
+impl Default for PreviewServiceBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PreviewServiceBuilder {
+    pub fn new() -> Self {
+        Self {
+            url_validation: UrlValidationConfig::default(),
+            content_limits: ContentLimits::default(),
+            timeout: None,
+            config: PreviewServiceConfig::new(1000),
+        }
+    }
+
+    /// Replaces the whole URL validation policy; later calls such as
+    /// [`allow_domain`](Self::allow_domain) refine it
+    pub fn url_validation(mut self, url_validation: UrlValidationConfig) -> Self {
+        self.url_validation = url_validation;
+        self
+    }
+
+    /// Replaces all content limits; later calls such as
+    /// [`max_content_size`](Self::max_content_size) refine them
+    pub fn content_limits(mut self, content_limits: ContentLimits) -> Self {
+        self.content_limits = content_limits;
+        self
+    }
+
+    /// Adds a domain to the allowlist. Once any domain is allowed, all others are rejected.
+    pub fn allow_domain(mut self, domain: impl Into<String>) -> Self {
+        self.url_validation.allowed_domains.insert(domain.into());
+        self
+    }
+
+    pub fn block_domain(mut self, domain: impl Into<String>) -> Self {
+        self.url_validation.blocked_domains.insert(domain.into());
+        self
+    }
+
+    /// Rejects every scheme other than `https`
+    pub fn https_only(mut self) -> Self {
+        self.url_validation.allowed_schemes = ["https".to_string()].into_iter().collect();
+        self
+    }
+
+    /// Maximum response body size in bytes
+    pub fn max_content_size(mut self, max_content_size: usize) -> Self {
+        self.content_limits.max_content_size = max_content_size;
+        self
+    }
+
+    /// Request timeout applied to the default, Twitter and GitHub fetchers
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn cache_capacity(mut self, cache_capacity: usize) -> Self {
+        self.config.cache_capacity = cache_capacity;
+        self
+    }
+
+    pub fn cache_strategy(mut self, cache_strategy: CacheStrategy) -> Self {
+        self.config.cache_strategy = cache_strategy;
+        self
+    }
+
+    pub fn max_concurrent_requests(mut self, max_concurrent_requests: usize) -> Self {
+        self.config.max_concurrent_requests = max_concurrent_requests;
+        self
+    }
+
+    pub fn build(self) -> PreviewService {
+        let mut config = self.config;
+
+        let mut fetcher_config = FetcherConfig {
+            url_validation: self.url_validation.clone(),
+            content_limits: self.content_limits.clone(),
+            ..Default::default()
+        };
+        if let Some(timeout) = self.timeout {
+            fetcher_config.timeout = timeout;
+        }
+        config.default_fetcher = Some(Fetcher::with_config(fetcher_config));
+
+        #[cfg(feature = "twitter")]
+        {
+            let timeout = self.timeout.unwrap_or(Duration::from_secs(30));
+            let client = Fetcher::twitter_http_client(timeout);
+            config.twitter_fetcher = Some(
+                Fetcher::with_client(client)
+                    .with_policies(self.url_validation.clone(), self.content_limits.clone()),
+            );
+        }
+
+        #[cfg(feature = "github")]
+        {
+            let timeout = self.timeout.unwrap_or(Duration::from_secs(10));
+            let client = Fetcher::github_http_client(timeout);
+            config.github_fetcher = Some(
+                Fetcher::with_client(client)
+                    .with_policies(self.url_validation.clone(), self.content_limits.clone()),
+            );
+        }
+
+        PreviewService::new_with_config(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_try_generate_preview_fails_fast_when_saturated() {
+        let service =
+            PreviewService::new_with_config(PreviewServiceConfig::new(10).with_max_concurrent_requests(1));
+        assert_eq!(service.available_permits(), 1);
+
+        let _held = service.semaphore.clone().acquire_owned().await.unwrap();
+        assert_eq!(service.available_permits(), 0);
+
+        assert!(matches!(
+            service.try_generate_preview("https://example.com").await,
+            Err(PreviewError::ConcurrencyLimitError)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_host_permits_released_on_error() {
+        let service = PreviewService::new_with_config(
+            PreviewServiceConfig::new(10).with_max_per_host_requests(1),
+        );
+
+        // Rejected by URL validation after both permits were taken
+        for _ in 0..3 {
+            assert!(matches!(
+                service.generate_preview("http://localhost/page").await,
+                Err(PreviewError::LocalhostBlocked)
+            ));
+        }
+
+        let limiter = service.host_limiter.as_ref().unwrap();
+        let semaphore = limiter.semaphore("http://localhost/other").unwrap();
+        assert_eq!(semaphore.available_permits(), 1);
+        assert_eq!(service.available_permits(), MAX_CONCURRENT_REQUESTS);
+    }
+
+    #[tokio::test]
+    async fn test_try_generate_preview_fails_fast_when_host_saturated() {
+        let service = PreviewService::new_with_config(
+            PreviewServiceConfig::new(10).with_max_per_host_requests(1),
+        );
+        let semaphore = service
+            .host_limiter
+            .as_ref()
+            .unwrap()
+            .semaphore("https://example.com/a")
+            .unwrap();
+        let _held = semaphore.acquire_owned().await.unwrap();
+
+        assert!(matches!(
+            service.try_generate_preview("https://example.com/b").await,
+            Err(PreviewError::ConcurrencyLimitError)
+        ));
+        assert_eq!(service.available_permits(), MAX_CONCURRENT_REQUESTS);
+    }
 }
@@ -0,0 +1,152 @@
+//! Prometheus exporter for [`MetricsObserver`] events
+//!
+//! ```rust,no_run
+//! use std::sync::Arc;
+//! use url_preview::{PreviewService, PreviewServiceConfig, PrometheusMetrics};
+//!
+//! let metrics = Arc::new(PrometheusMetrics::new());
+//! let service = PreviewService::new_with_config(
+//!     PreviewServiceConfig::new(1000).with_metrics_observer(metrics.clone()),
+//! );
+//! // Serve `metrics.gather()` from your `/metrics` endpoint
+//! ```
+
+use crate::observer::{FetchOutcome, MetricsObserver};
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Upper bounds (in seconds) of the fetch latency histogram buckets
+const LATENCY_BUCKETS: [f64; 11] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// Counters and a latency histogram rendered in the Prometheus text format
+#[derive(Debug, Default)]
+pub struct PrometheusMetrics {
+    fetch_success: AtomicU64,
+    fetch_error: AtomicU64,
+    cache_hits: AtomicU64,
+    cache_misses: AtomicU64,
+    latency_buckets: [AtomicU64; LATENCY_BUCKETS.len()],
+    latency_sum_micros: AtomicU64,
+    latency_count: AtomicU64,
+}
+
+impl PrometheusMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Renders all metrics in the Prometheus text exposition format
+    pub fn gather(&self) -> String {
+        let mut out = String::new();
+
+        out.push_str("# HELP url_preview_fetches_total Total fetches by outcome.\n");
+        out.push_str("# TYPE url_preview_fetches_total counter\n");
+        for (outcome, counter) in [
+            (FetchOutcome::Success, &self.fetch_success),
+            (FetchOutcome::Error, &self.fetch_error),
+        ] {
+            let _ = writeln!(
+                out,
+                "url_preview_fetches_total{{outcome=\"{}\"}} {}",
+                outcome.as_str(),
+                counter.load(Ordering::Relaxed)
+            );
+        }
+
+        out.push_str("# HELP url_preview_cache_hits_total Total cache hits.\n");
+        out.push_str("# TYPE url_preview_cache_hits_total counter\n");
+        let _ = writeln!(
+            out,
+            "url_preview_cache_hits_total {}",
+            self.cache_hits.load(Ordering::Relaxed)
+        );
+
+        out.push_str("# HELP url_preview_cache_misses_total Total cache misses.\n");
+        out.push_str("# TYPE url_preview_cache_misses_total counter\n");
+        let _ = writeln!(
+            out,
+            "url_preview_cache_misses_total {}",
+            self.cache_misses.load(Ordering::Relaxed)
+        );
+
+        out.push_str("# HELP url_preview_fetch_duration_seconds Fetch latency in seconds.\n");
+        out.push_str("# TYPE url_preview_fetch_duration_seconds histogram\n");
+        for (bound, bucket) in LATENCY_BUCKETS.iter().zip(self.latency_buckets.iter()) {
+            let _ = writeln!(
+                out,
+                "url_preview_fetch_duration_seconds_bucket{{le=\"{}\"}} {}",
+                bound,
+                bucket.load(Ordering::Relaxed)
+            );
+        }
+        let count = self.latency_count.load(Ordering::Relaxed);
+        let _ = writeln!(
+            out,
+            "url_preview_fetch_duration_seconds_bucket{{le=\"+Inf\"}} {count}"
+        );
+        let _ = writeln!(
+            out,
+            "url_preview_fetch_duration_seconds_sum {}",
+            self.latency_sum_micros.load(Ordering::Relaxed) as f64 / 1_000_000.0
+        );
+        let _ = writeln!(out, "url_preview_fetch_duration_seconds_count {count}");
+
+        out
+    }
+}
+
+impl MetricsObserver for PrometheusMetrics {
+    fn on_fetch(&self, outcome: FetchOutcome, duration: Duration) {
+        match outcome {
+            FetchOutcome::Success => self.fetch_success.fetch_add(1, Ordering::Relaxed),
+            FetchOutcome::Error => self.fetch_error.fetch_add(1, Ordering::Relaxed),
+        };
+
+        let seconds = duration.as_secs_f64();
+        for (bound, bucket) in LATENCY_BUCKETS.iter().zip(self.latency_buckets.iter()) {
+            if seconds <= *bound {
+                bucket.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        self.latency_sum_micros
+            .fetch_add(duration.as_micros() as u64, Ordering::Relaxed);
+        self.latency_count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn on_cache_hit(&self) {
+        self.cache_hits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn on_cache_miss(&self) {
+        self.cache_misses.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gather_reports_counters_and_histogram() {
+        let metrics = PrometheusMetrics::new();
+        metrics.on_fetch(FetchOutcome::Success, Duration::from_millis(20));
+        metrics.on_fetch(FetchOutcome::Error, Duration::from_secs(3));
+        metrics.on_cache_hit();
+        metrics.on_cache_miss();
+        metrics.on_cache_miss();
+
+        let output = metrics.gather();
+        assert!(output.contains("url_preview_fetches_total{outcome=\"success\"} 1"));
+        assert!(output.contains("url_preview_fetches_total{outcome=\"error\"} 1"));
+        assert!(output.contains("url_preview_cache_hits_total 1"));
+        assert!(output.contains("url_preview_cache_misses_total 2"));
+        assert!(output.contains("url_preview_fetch_duration_seconds_bucket{le=\"0.01\"} 0"));
+        assert!(output.contains("url_preview_fetch_duration_seconds_bucket{le=\"0.025\"} 1"));
+        assert!(output.contains("url_preview_fetch_duration_seconds_bucket{le=\"5\"} 2"));
+        assert!(output.contains("url_preview_fetch_duration_seconds_bucket{le=\"+Inf\"} 2"));
+        assert!(output.contains("url_preview_fetch_duration_seconds_count 2"));
+    }
+}
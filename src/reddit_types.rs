@@ -0,0 +1,191 @@
+use crate::Preview;
+use serde::Deserialize;
+use serde_json::Value;
+use url::Url;
+
+#[derive(Debug, Deserialize)]
+pub struct RedditListing {
+    pub data: RedditListingData,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RedditListingData {
+    #[serde(default)]
+    pub children: Vec<RedditThing>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RedditThing {
+    pub kind: String,
+    pub data: RedditPost,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct RedditPost {
+    pub title: String,
+    pub subreddit_name_prefixed: String,
+    pub selftext: String,
+    pub author: String,
+    pub permalink: String,
+    pub thumbnail: Option<String>,
+    pub preview: Option<RedditPreviewImages>,
+    pub is_gallery: Option<bool>,
+    pub crosspost_parent: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct RedditPreviewImages {
+    pub images: Vec<RedditImage>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RedditImage {
+    pub source: RedditImageSource,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RedditImageSource {
+    pub url: String,
+}
+
+/// Matches Reddit post permalinks such as `reddit.com/r/rust/comments/<id>/<slug>/`
+pub fn is_reddit_url(url: &str) -> bool {
+    let Ok(parsed) = Url::parse(url) else {
+        return false;
+    };
+    let host = parsed.host_str().unwrap_or("");
+    let is_reddit_host = matches!(
+        host,
+        "reddit.com" | "www.reddit.com" | "old.reddit.com" | "new.reddit.com" | "np.reddit.com"
+    );
+    let segments: Vec<&str> = parsed.path_segments().into_iter().flatten().collect();
+    is_reddit_host
+        && matches!(segments.as_slice(), ["r", _, "comments", id, ..] if !id.is_empty())
+}
+
+/// The `.json` endpoint for a Reddit post URL, e.g.
+/// `https://www.reddit.com/r/rust/comments/abc/title/?utm=x` ->
+/// `https://www.reddit.com/r/rust/comments/abc/title.json?raw_json=1`
+pub fn reddit_json_url(url: &str) -> Option<String> {
+    let mut parsed = Url::parse(url).ok()?;
+    parsed.set_query(None);
+    parsed.set_fragment(None);
+    let path = format!("{}.json", parsed.path().trim_end_matches('/'));
+    parsed.set_path(&path);
+    // Without raw_json, URLs inside the payload are HTML-escaped
+    parsed.set_query(Some("raw_json=1"));
+    Some(parsed.into())
+}
+
+impl RedditPost {
+    /// Parses the post out of a `.json` response (an array whose first listing holds
+    /// the post). Returns `None` for galleries and crossposts, whose useful content
+    /// lives elsewhere, so callers can fall back to generic extraction.
+    pub fn from_json(payload: &Value) -> Option<Self> {
+        let listing = payload.as_array()?.first()?.clone();
+        let listing: RedditListing = serde_json::from_value(listing).ok()?;
+        let post = listing
+            .data
+            .children
+            .into_iter()
+            .find(|thing| thing.kind == "t3")?
+            .data;
+
+        if post.is_gallery == Some(true) || post.crosspost_parent.is_some() {
+            return None;
+        }
+        Some(post)
+    }
+
+    pub fn into_preview(self, url: &str) -> Preview {
+        let image_url = self
+            .preview
+            .and_then(|preview| preview.images.into_iter().next())
+            .map(|image| image.source.url)
+            .or_else(|| self.thumbnail.filter(|thumb| thumb.starts_with("http")));
+        let author = Some(self.author)
+            .filter(|author| !author.is_empty() && author != "[deleted]");
+        let non_empty = |value: String| {
+            let value = value.trim().to_string();
+            (!value.is_empty()).then_some(value)
+        };
+
+        Preview {
+            url: url.to_string(),
+            title: non_empty(self.title),
+            description: non_empty(self.selftext),
+            image_url,
+            favicon: Some("https://www.reddit.com/favicon.ico".to_string()),
+            site_name: non_empty(self.subreddit_name_prefixed),
+            canonical_url: non_empty(self.permalink)
+                .map(|permalink| format!("https://www.reddit.com{permalink}")),
+            author: author.clone(),
+            authors: author.into_iter().collect(),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(name: &str) -> Value {
+        let path = format!("{}/tests/fixtures/reddit/{name}", env!("CARGO_MANIFEST_DIR"));
+        serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_is_reddit_url() {
+        assert!(is_reddit_url(
+            "https://www.reddit.com/r/rust/comments/1abcde/announcing_rust_1800/"
+        ));
+        assert!(is_reddit_url("https://old.reddit.com/r/rust/comments/1abcde"));
+        assert!(!is_reddit_url("https://www.reddit.com/r/rust/"));
+        assert!(!is_reddit_url("https://www.reddit.com/user/someone"));
+        assert!(!is_reddit_url("https://notreddit.com/r/rust/comments/1abcde/"));
+    }
+
+    #[test]
+    fn test_reddit_json_url() {
+        assert_eq!(
+            reddit_json_url("https://www.reddit.com/r/rust/comments/1abcde/title/?utm_source=share")
+                .as_deref(),
+            Some("https://www.reddit.com/r/rust/comments/1abcde/title.json?raw_json=1")
+        );
+    }
+
+    #[test]
+    fn test_self_post_preview() {
+        let url = "https://www.reddit.com/r/rust/comments/1abcde/announcing_rust_1800/";
+        let preview = RedditPost::from_json(&sample("self_post.json"))
+            .unwrap()
+            .into_preview(url);
+
+        assert_eq!(preview.url, url);
+        assert_eq!(preview.title.as_deref(), Some("Announcing Rust 1.80.0"));
+        assert_eq!(preview.site_name.as_deref(), Some("r/rust"));
+        assert_eq!(
+            preview.description.as_deref(),
+            Some("The Rust team is happy to announce a new version of Rust & Cargo.")
+        );
+        assert_eq!(
+            preview.image_url.as_deref(),
+            Some("https://preview.redd.it/abc123.png?width=1200&format=png&s=def456")
+        );
+        assert_eq!(preview.author.as_deref(), Some("rustacean"));
+        assert_eq!(
+            preview.canonical_url.as_deref(),
+            Some("https://www.reddit.com/r/rust/comments/1abcde/announcing_rust_1800/")
+        );
+    }
+
+    #[test]
+    fn test_gallery_and_crosspost_fall_back() {
+        assert!(RedditPost::from_json(&sample("gallery_post.json")).is_none());
+        assert!(RedditPost::from_json(&sample("crosspost.json")).is_none());
+        assert!(RedditPost::from_json(&serde_json::json!({"kind": "Listing"})).is_none());
+    }
+}
@@ -0,0 +1,315 @@
+use crate::{PreviewError, UrlValidator};
+use dashmap::DashMap;
+use reqwest::RequestBuilder;
+use std::sync::Arc;
+use url::Url;
+#[cfg(feature = "logging")]
+use tracing::debug;
+
+/// Upper bound on how much of a robots.txt file is parsed
+const MAX_ROBOTS_SIZE: usize = 512 * 1024;
+
+/// A single `Allow`/`Disallow` line
+#[derive(Debug, Clone, PartialEq)]
+struct Rule {
+    allow: bool,
+    pattern: String,
+}
+
+/// Rules that apply to a set of user agents
+#[derive(Debug, Clone, Default)]
+struct Group {
+    agents: Vec<String>,
+    rules: Vec<Rule>,
+}
+
+/// Parsed robots.txt for one origin
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RobotsRules {
+    groups: Vec<Group>,
+}
+
+impl RobotsRules {
+    /// Parses robots.txt content. Unknown directives and malformed lines are ignored.
+    pub(crate) fn parse(content: &str) -> Self {
+        let mut groups: Vec<Group> = Vec::new();
+        // Consecutive User-agent lines share the rules that follow them
+        let mut collecting_agents = false;
+
+        for line in content.lines() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+
+            match key.trim().to_ascii_lowercase().as_str() {
+                "user-agent" => {
+                    if !collecting_agents {
+                        groups.push(Group::default());
+                        collecting_agents = true;
+                    }
+                    if let Some(group) = groups.last_mut() {
+                        group.agents.push(value.to_ascii_lowercase());
+                    }
+                }
+                directive @ ("allow" | "disallow") => {
+                    collecting_agents = false;
+                    // An empty Disallow allows everything, so it adds no rule
+                    if value.is_empty() {
+                        continue;
+                    }
+                    if let Some(group) = groups.last_mut() {
+                        group.rules.push(Rule {
+                            allow: directive == "allow",
+                            pattern: value.to_string(),
+                        });
+                    }
+                }
+                _ => collecting_agents = false,
+            }
+        }
+
+        Self { groups }
+    }
+
+    /// Whether `path` (including any query) may be fetched by `user_agent`.
+    ///
+    /// The group naming the longest matching agent token wins, falling back to `*`.
+    /// Within a group the longest matching pattern wins, and `Allow` wins ties.
+    pub(crate) fn is_allowed(&self, user_agent: &str, path: &str) -> bool {
+        let user_agent = user_agent.to_ascii_lowercase();
+        let product = user_agent
+            .split(|c: char| c == '/' || c.is_whitespace())
+            .next()
+            .unwrap_or("");
+
+        let specific = self
+            .groups
+            .iter()
+            .filter_map(|group| {
+                group
+                    .agents
+                    .iter()
+                    .filter(|agent| agent.as_str() != "*" && !agent.is_empty())
+                    .filter(|agent| product.contains(agent.as_str()))
+                    .map(|agent| agent.len())
+                    .max()
+                    .map(|len| (len, group))
+            })
+            .max_by_key(|(len, _)| *len)
+            .map(|(_, group)| group);
+        let group = specific.or_else(|| {
+            self.groups
+                .iter()
+                .find(|group| group.agents.iter().any(|agent| agent == "*"))
+        });
+
+        let Some(group) = group else {
+            return true;
+        };
+
+        group
+            .rules
+            .iter()
+            .filter(|rule| pattern_matches(&rule.pattern, path))
+            .max_by_key(|rule| (rule.pattern.len(), rule.allow))
+            .is_none_or(|rule| rule.allow)
+    }
+}
+
+/// Matches a robots.txt path pattern supporting `*` wildcards and a trailing `$` anchor
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(stripped) => (stripped, true),
+        None => (pattern, false),
+    };
+
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or("");
+    let Some(mut rest) = path.strip_prefix(first) else {
+        return false;
+    };
+
+    let parts: Vec<&str> = parts.collect();
+    for (index, part) in parts.iter().enumerate() {
+        let is_last = index == parts.len() - 1;
+        if is_last && anchored {
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(pos) => rest = &rest[pos + part.len()..],
+            None => return false,
+        }
+    }
+
+    !anchored || rest.is_empty()
+}
+
+/// Per-origin robots.txt cache, shared between clones of a fetcher
+#[derive(Clone, Default)]
+struct RobotsCache {
+    rules: Arc<DashMap<String, Arc<RobotsRules>>>,
+}
+
+impl RobotsCache {
+    fn get(&self, origin: &str) -> Option<Arc<RobotsRules>> {
+        self.rules.get(origin).map(|rules| rules.clone())
+    }
+
+    fn insert(&self, origin: String, rules: Arc<RobotsRules>) {
+        self.rules.insert(origin, rules);
+    }
+}
+
+/// Checks URLs against their origin's robots.txt on behalf of a fetcher
+#[derive(Clone)]
+pub(crate) struct RobotsChecker {
+    user_agent: String,
+    cache: RobotsCache,
+}
+
+impl RobotsChecker {
+    pub(crate) fn new(user_agent: &str) -> Self {
+        Self {
+            user_agent: user_agent.to_string(),
+            cache: RobotsCache::default(),
+        }
+    }
+
+    /// Returns `PreviewError::RobotsDisallowed` if the origin's robots.txt forbids `url`.
+    ///
+    /// robots.txt is requested with `get`, after passing `validator`. A missing file
+    /// (4xx) allows everything and is cached; other failures allow the fetch but are
+    /// retried next time.
+    pub(crate) async fn check<F>(
+        &self,
+        url: &Url,
+        validator: &UrlValidator,
+        get: F,
+    ) -> Result<(), PreviewError>
+    where
+        F: Fn(&str) -> RequestBuilder,
+    {
+        let origin = url.origin().ascii_serialization();
+        let rules = match self.cache.get(&origin) {
+            Some(rules) => rules,
+            None => match self.fetch_rules(&origin, validator, get).await? {
+                Some(rules) => {
+                    let rules = Arc::new(rules);
+                    self.cache.insert(origin, rules.clone());
+                    rules
+                }
+                None => return Ok(()),
+            },
+        };
+
+        let mut path = url.path().to_string();
+        if let Some(query) = url.query() {
+            path.push('?');
+            path.push_str(query);
+        }
+
+        if rules.is_allowed(&self.user_agent, &path) {
+            Ok(())
+        } else {
+            Err(PreviewError::RobotsDisallowed(url.to_string()))
+        }
+    }
+
+    async fn fetch_rules<F>(
+        &self,
+        origin: &str,
+        validator: &UrlValidator,
+        get: F,
+    ) -> Result<Option<RobotsRules>, PreviewError>
+    where
+        F: Fn(&str) -> RequestBuilder,
+    {
+        let robots_url = validator.validate(&format!("{origin}/robots.txt"))?;
+
+        let mut response = match get(robots_url.as_str()).send().await {
+            Ok(response) => response,
+            Err(_e) => {
+                #[cfg(feature = "logging")]
+                debug!(error = %_e, url = %robots_url, "Failed to fetch robots.txt");
+                return Ok(None);
+            }
+        };
+
+        let status = response.status();
+        if status.is_client_error() {
+            return Ok(Some(RobotsRules::default()));
+        }
+        if !status.is_success() {
+            return Ok(None);
+        }
+
+        // Stop reading at the cap rather than buffering whatever the host sends
+        let mut body = Vec::new();
+        while body.len() < MAX_ROBOTS_SIZE {
+            match response.chunk().await {
+                Ok(Some(chunk)) => {
+                    let take = chunk.len().min(MAX_ROBOTS_SIZE - body.len());
+                    body.extend_from_slice(&chunk[..take]);
+                }
+                Ok(None) => break,
+                Err(_) => return Ok(None),
+            }
+        }
+        Ok(Some(RobotsRules::parse(&String::from_utf8_lossy(&body))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROBOTS: &str = "
+# Comments are ignored
+User-agent: *
+Disallow: /private/
+Allow: /private/press
+Disallow: /*.pdf$
+
+User-agent: url_preview
+User-agent: otherbot
+Disallow: /drafts
+";
+
+    #[test]
+    fn test_wildcard_group_rules() {
+        let rules = RobotsRules::parse(ROBOTS);
+        assert!(rules.is_allowed("SomeBot/1.0", "/"));
+        assert!(!rules.is_allowed("SomeBot/1.0", "/private/secret"));
+        assert!(rules.is_allowed("SomeBot/1.0", "/private/press/2024"));
+        assert!(!rules.is_allowed("SomeBot/1.0", "/files/report.pdf"));
+        assert!(rules.is_allowed("SomeBot/1.0", "/files/report.pdf?download=1"));
+    }
+
+    #[test]
+    fn test_specific_group_replaces_wildcard() {
+        let rules = RobotsRules::parse(ROBOTS);
+        assert!(!rules.is_allowed("url_preview/0.1.0", "/drafts/post"));
+        // Only the most specific group applies
+        assert!(rules.is_allowed("url_preview/0.1.0", "/private/secret"));
+    }
+
+    #[test]
+    fn test_empty_or_missing_rules_allow_everything() {
+        assert!(RobotsRules::parse("").is_allowed("bot", "/anything"));
+        let rules = RobotsRules::parse("User-agent: *\nDisallow:\n");
+        assert!(rules.is_allowed("bot", "/anything"));
+    }
+
+    #[test]
+    fn test_pattern_matching() {
+        assert!(pattern_matches("/a", "/abc"));
+        assert!(pattern_matches("/a*c", "/a/b/c/d"));
+        assert!(pattern_matches("/a*c$", "/a/b/c"));
+        assert!(!pattern_matches("/a*c$", "/a/b/cd"));
+        assert!(pattern_matches("/exact$", "/exact"));
+        assert!(!pattern_matches("/exact$", "/exact/"));
+        assert!(!pattern_matches("/b", "/abc"));
+    }
+}
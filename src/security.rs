@@ -1,7 +1,11 @@
 use crate::error::PreviewError;
+use hyper::client::connect::dns::Name;
+use reqwest::dns::{Addrs, Resolve, Resolving};
 use std::collections::HashSet;
-use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
-use url::Url;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use url::{Host, Url};
 
 /// Configuration for URL validation
 #[derive(Debug, Clone)]
@@ -18,6 +22,18 @@ pub struct UrlValidationConfig {
     pub allowed_domains: HashSet<String>,
     /// Maximum number of redirects allowed
     pub max_redirects: usize,
+    /// File extensions rejected before fetching, with or without the leading dot
+    /// (e.g. `.exe`, `zip`; matched case-insensitively against the URL path)
+    pub blocked_extensions: HashSet<String>,
+    /// Maximum number of DNS lookups a validator (and its clones) runs at once (default: 32)
+    pub max_concurrent_dns_resolutions: usize,
+    /// Also check the addresses a hostname resolves to against the private IP and
+    /// localhost rules, so DNS rebinding cannot reach internal hosts (default: false).
+    /// The fetcher connects only to the addresses that passed the check.
+    pub resolve_and_check: bool,
+    /// Ports URLs may target, explicit or implied by the scheme (default: 80 and 443).
+    /// An empty set allows every port.
+    pub allowed_ports: HashSet<u16>,
 }
 
 impl Default for UrlValidationConfig {
@@ -33,6 +49,10 @@ impl Default for UrlValidationConfig {
             blocked_domains: HashSet::new(),
             allowed_domains: HashSet::new(),
             max_redirects: 10,
+            blocked_extensions: HashSet::new(),
+            max_concurrent_dns_resolutions: 32,
+            resolve_and_check: false,
+            allowed_ports: HashSet::from([80, 443]),
         }
     }
 }
@@ -41,17 +61,29 @@ impl Default for UrlValidationConfig {
 #[derive(Clone)]
 pub struct UrlValidator {
     config: UrlValidationConfig,
+    /// Shared between clones so every fetcher using this validator is paced together
+    dns_permits: Arc<Semaphore>,
 }
 
 impl UrlValidator {
     pub fn new(config: UrlValidationConfig) -> Self {
-        Self { config }
+        let dns_permits = Arc::new(Semaphore::new(config.max_concurrent_dns_resolutions.max(1)));
+        Self {
+            config,
+            dns_permits,
+        }
     }
 
     pub fn with_default_config() -> Self {
         Self::new(UrlValidationConfig::default())
     }
 
+    /// Whether fetchers should check resolved addresses, see
+    /// [`UrlValidationConfig::resolve_and_check`]
+    pub(crate) fn resolves_and_checks(&self) -> bool {
+        self.config.resolve_and_check
+    }
+
     /// Validates a URL string
     pub fn validate(&self, url_str: &str) -> Result<Url, PreviewError> {
         // Parse the URL
@@ -76,30 +108,108 @@ impl UrlValidator {
             return Err(PreviewError::DomainBlocked(host.to_string()));
         }
 
+        // Check file extension blacklist
+        if let Some(extension) = self.blocked_extension(&url) {
+            return Err(PreviewError::ContentTypeNotAllowed(format!(
+                "blocked file extension: .{extension}"
+            )));
+        }
+
+        // Canonical form of an IP host, however it was encoded
+        let host_ip = host_ip(&url);
+
         // Check for localhost
-        if self.config.block_localhost && self.is_localhost(host) {
+        if self.config.block_localhost
+            && (self.is_localhost(host)
+                || host_ip.is_some_and(|ip| self.is_localhost(&ip.to_string())))
+        {
             return Err(PreviewError::LocalhostBlocked);
         }
 
         // Check for private IPs if host is an IP address
         if self.config.block_private_ips {
-            // Handle IPv6 addresses which may be wrapped in brackets
-            let ip_str = if host.starts_with('[') && host.ends_with(']') {
-                &host[1..host.len() - 1]
-            } else {
-                host
-            };
-            
-            if let Ok(ip) = ip_str.parse::<IpAddr>() {
+            if let Some(ip) = host_ip {
                 if self.is_private_ip(&ip) {
                     return Err(PreviewError::PrivateIpBlocked(ip.to_string()));
                 }
             }
         }
 
+        // Check port allowlist
+        if !self.config.allowed_ports.is_empty() {
+            if let Some(port) = url.port_or_known_default() {
+                if !self.config.allowed_ports.contains(&port) {
+                    return Err(PreviewError::PortNotAllowed(port));
+                }
+            }
+        }
+
         Ok(url)
     }
 
+    /// Validates a batch of URLs with the synchronous checks only (no network),
+    /// returning one result per input in the same order
+    pub fn validate_many<S: AsRef<str>>(&self, urls: &[S]) -> Vec<Result<Url, PreviewError>> {
+        urls.iter().map(|url| self.validate(url.as_ref())).collect()
+    }
+
+    /// Resolves a host to its IP addresses, waiting for a slot when
+    /// `max_concurrent_dns_resolutions` lookups are already in flight
+    pub async fn resolve_host(&self, host: &str, port: u16) -> Result<Vec<IpAddr>, PreviewError> {
+        self.resolve_host_with(host, port, |host, port| async move {
+            tokio::net::lookup_host((host.as_str(), port))
+                .await
+                .map(|addrs| addrs.collect())
+        })
+        .await
+    }
+
+    /// [`resolve_host`](Self::resolve_host) with the lookup itself supplied by `lookup`
+    async fn resolve_host_with<F, Fut>(
+        &self,
+        host: &str,
+        port: u16,
+        lookup: F,
+    ) -> Result<Vec<IpAddr>, PreviewError>
+    where
+        F: FnOnce(String, u16) -> Fut,
+        Fut: std::future::Future<Output = std::io::Result<Vec<SocketAddr>>>,
+    {
+        let _permit = self
+            .dns_permits
+            .acquire()
+            .await
+            .map_err(|_| PreviewError::ConcurrencyLimitError)?;
+
+        let host = host.trim_start_matches('[').trim_end_matches(']');
+        let addrs = lookup(host.to_string(), port)
+            .await
+            .map_err(|e| PreviewError::DnsError(format!("Failed to resolve {host}: {e}")))?;
+
+        Ok(addrs.into_iter().map(|addr| addr.ip()).collect())
+    }
+
+    /// Resolves a host and rejects it with `PreviewError::PrivateIpBlocked` if any
+    /// address falls in a range blocked by `block_private_ips` or `block_localhost`
+    pub async fn resolve_checked(
+        &self,
+        host: &str,
+        port: u16,
+    ) -> Result<Vec<SocketAddr>, PreviewError> {
+        let ips = self.resolve_host(host, port).await?;
+        if let Some(ip) = ips.iter().find(|ip| self.is_blocked_ip(ip)) {
+            return Err(PreviewError::PrivateIpBlocked(ip.to_string()));
+        }
+        Ok(ips.into_iter().map(|ip| SocketAddr::new(ip, port)).collect())
+    }
+
+    fn is_blocked_ip(&self, ip: &IpAddr) -> bool {
+        // IPv4-mapped IPv6 addresses are checked as the IPv4 address they carry
+        let ip = ip.to_canonical();
+        (self.config.block_private_ips && self.is_private_ip(&ip))
+            || (self.config.block_localhost && ip.is_loopback())
+    }
+
     fn is_domain_allowed(&self, host: &str) -> bool {
         self.config
             .allowed_domains
@@ -114,6 +224,22 @@ impl UrlValidator {
             .any(|blocked| host == blocked || host.ends_with(&format!(".{blocked}")))
     }
 
+    fn blocked_extension(&self, url: &Url) -> Option<String> {
+        if self.config.blocked_extensions.is_empty() {
+            return None;
+        }
+
+        let file_name = url.path_segments()?.next_back()?;
+        let (_, extension) = file_name.rsplit_once('.')?;
+        let extension = extension.to_ascii_lowercase();
+
+        self.config
+            .blocked_extensions
+            .iter()
+            .any(|blocked| blocked.trim_start_matches('.').eq_ignore_ascii_case(&extension))
+            .then_some(extension)
+    }
+
     fn is_localhost(&self, host: &str) -> bool {
         matches!(host, "localhost" | "127.0.0.1" | "::1" | "[::1]")
     }
@@ -171,6 +297,81 @@ impl UrlValidator {
     }
 }
 
+/// The IP address a URL's host refers to, with IPv4-mapped IPv6 addresses unwrapped.
+///
+/// `url` already normalizes octal, hex and integer IPv4 hosts for special schemes
+/// such as http; other schemes keep them as domains, so those are parsed here too.
+fn host_ip(url: &Url) -> Option<IpAddr> {
+    match url.host()? {
+        Host::Ipv4(ip) => Some(IpAddr::V4(ip)),
+        Host::Ipv6(ip) => Some(IpAddr::V6(ip).to_canonical()),
+        Host::Domain(domain) => parse_legacy_ipv4(domain).map(IpAddr::V4),
+    }
+}
+
+/// Parses the `inet_aton` IPv4 forms: one to four dot-separated parts, each
+/// decimal, octal (leading `0`) or hex (`0x`), with the last part filling the
+/// remaining bytes (e.g. `0177.1`, `0x7f000001`, `2130706433`)
+fn parse_legacy_ipv4(host: &str) -> Option<Ipv4Addr> {
+    let host = host.strip_suffix('.').unwrap_or(host);
+    let parts = host
+        .split('.')
+        .map(|part| {
+            if let Some(hex) = part.strip_prefix("0x").or_else(|| part.strip_prefix("0X")) {
+                if hex.is_empty() {
+                    Some(0)
+                } else {
+                    u32::from_str_radix(hex, 16).ok()
+                }
+            } else if part.len() > 1 && part.starts_with('0') {
+                u32::from_str_radix(&part[1..], 8).ok()
+            } else if !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit()) {
+                part.parse().ok()
+            } else {
+                None
+            }
+        })
+        .collect::<Option<Vec<u32>>>()?;
+    let (last, leading) = parts.split_last()?;
+    if leading.len() > 3 || leading.iter().any(|&part| part > 255) {
+        return None;
+    }
+
+    let last_bits = 8 * (4 - leading.len() as u32);
+    if last_bits < 32 && *last >= 1 << last_bits {
+        return None;
+    }
+    let value = leading
+        .iter()
+        .enumerate()
+        .fold(*last, |value, (index, &part)| value | (part << (24 - 8 * index as u32)));
+    Some(Ipv4Addr::from(value))
+}
+
+/// DNS resolver for fetchers with `resolve_and_check` enabled. Every resolved
+/// address goes through [`UrlValidator::resolve_checked`], so the address that was
+/// validated is the one the connection is made to.
+pub(crate) struct CheckedResolver {
+    validator: UrlValidator,
+}
+
+impl CheckedResolver {
+    pub(crate) fn new(validator: UrlValidator) -> Self {
+        Self { validator }
+    }
+}
+
+impl Resolve for CheckedResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let validator = self.validator.clone();
+        Box::pin(async move {
+            // The connector fills in the real port
+            let addrs = validator.resolve_checked(name.as_str(), 0).await?;
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
 /// Content size and time limits configuration
 #[derive(Debug, Clone)]
 pub struct ContentLimits {
@@ -180,6 +381,13 @@ pub struct ContentLimits {
     pub max_download_time: u64,
     /// Allowed content types (if not empty, only these are allowed)
     pub allowed_content_types: HashSet<String>,
+    /// Maximum number of bytes of a page handed to the HTML parser (default: 1MB).
+    /// Metadata lives in `<head>`, so parsing also stops at the first `</head>`.
+    pub max_parse_size: usize,
+    /// Accept a response whose declared type is not in `allowed_content_types` if
+    /// its body starts like an HTML document (default: false). Servers sometimes
+    /// label pages `application/octet-stream`.
+    pub sniff_content_type: bool,
 }
 
 impl Default for ContentLimits {
@@ -194,6 +402,8 @@ impl Default for ContentLimits {
             max_content_size: 10 * 1024 * 1024, // 10MB
             max_download_time: 30,
             allowed_content_types: allowed_types,
+            max_parse_size: 1024 * 1024, // 1MB
+            sniff_content_type: false,
         }
     }
 }
@@ -252,4 +462,181 @@ mod tests {
         assert!(validator.validate("http://sub.trusted.com").is_ok());
         assert!(validator.validate("http://untrusted.com").is_err());
     }
+
+    #[test]
+    fn test_url_validator_blocked_extensions() {
+        let mut config = UrlValidationConfig::default();
+        config.blocked_extensions.insert(".exe".to_string());
+        config.blocked_extensions.insert("mp4".to_string());
+        let validator = UrlValidator::new(config);
+
+        assert!(matches!(
+            validator.validate("https://example.com/setup.exe"),
+            Err(PreviewError::ContentTypeNotAllowed(_))
+        ));
+        assert!(validator.validate("https://example.com/videos/Clip.MP4?t=10").is_err());
+        assert!(validator.validate("https://example.com/setup.exe.html").is_ok());
+        assert!(validator.validate("https://example.com/exe").is_ok());
+        assert!(validator.validate("https://example.com/").is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_resolve_host_is_bounded_by_semaphore() {
+        let config = UrlValidationConfig {
+            max_concurrent_dns_resolutions: 2,
+            ..Default::default()
+        };
+        let validator = UrlValidator::new(config);
+        assert_eq!(validator.dns_permits.available_permits(), 2);
+
+        let handles: Vec<_> = (1..=20u8)
+            .map(|i| {
+                let validator = validator.clone();
+                tokio::spawn(async move { validator.resolve_host(&format!("10.0.0.{i}"), 80).await })
+            })
+            .collect();
+        for (i, handle) in (1..=20u8).zip(handles) {
+            let ips = handle.await.unwrap().unwrap();
+            assert_eq!(ips, vec![IpAddr::V4(Ipv4Addr::new(10, 0, 0, i))]);
+        }
+
+        assert_eq!(validator.dns_permits.available_permits(), 2);
+        assert_eq!(
+            validator.resolve_host("[::1]", 443).await.unwrap(),
+            vec![IpAddr::V6(Ipv6Addr::LOCALHOST)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_slow_lookups_never_exceed_the_limit() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let validator = UrlValidator::new(UrlValidationConfig {
+            max_concurrent_dns_resolutions: 2,
+            ..Default::default()
+        });
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+
+        let handles: Vec<_> = (1..=20u8)
+            .map(|i| {
+                let validator = validator.clone();
+                let (in_flight, peak) = (in_flight.clone(), peak.clone());
+                tokio::spawn(async move {
+                    let host = format!("host-{i}.example");
+                    validator
+                        .resolve_host_with(&host, 80, |_, port| async move {
+                            let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                            peak.fetch_max(now, Ordering::SeqCst);
+                            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+                            in_flight.fetch_sub(1, Ordering::SeqCst);
+                            Ok(vec![SocketAddr::new(Ipv4Addr::new(10, 0, 0, i).into(), port)])
+                        })
+                        .await
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.await.unwrap().unwrap();
+        }
+
+        // Lookups did overlap, but never past the limit
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+        assert_eq!(validator.dns_permits.available_permits(), 2);
+    }
+
+    #[test]
+    fn test_url_validator_ports() {
+        let validator = UrlValidator::with_default_config();
+        assert!(validator.validate("https://example.com:443/").is_ok());
+        // Either allowed port may be used with either scheme
+        assert!(validator.validate("https://example.com:80").is_ok());
+        assert!(matches!(
+            validator.validate("http://example.com:9200"),
+            Err(PreviewError::PortNotAllowed(9200))
+        ));
+
+        let validator = UrlValidator::new(UrlValidationConfig {
+            allowed_ports: HashSet::new(),
+            ..Default::default()
+        });
+        assert!(validator.validate("http://example.com:9200").is_ok());
+    }
+
+    #[test]
+    fn test_parse_legacy_ipv4() {
+        let localhost = Some(Ipv4Addr::LOCALHOST);
+        assert_eq!(parse_legacy_ipv4("127.0.0.1"), localhost);
+        assert_eq!(parse_legacy_ipv4("0177.0.0.1"), localhost);
+        assert_eq!(parse_legacy_ipv4("0x7f000001"), localhost);
+        assert_eq!(parse_legacy_ipv4("2130706433"), localhost);
+        assert_eq!(parse_legacy_ipv4("127.1"), localhost);
+        assert_eq!(parse_legacy_ipv4("0x7f.0.0.1."), localhost);
+        assert_eq!(parse_legacy_ipv4("192.168.1"), Some(Ipv4Addr::new(192, 168, 0, 1)));
+
+        assert_eq!(parse_legacy_ipv4("example.com"), None);
+        assert_eq!(parse_legacy_ipv4("256.0.0.1"), None);
+        assert_eq!(parse_legacy_ipv4("1.2.3.4.5"), None);
+        assert_eq!(parse_legacy_ipv4("1.2.3.256"), None);
+        assert_eq!(parse_legacy_ipv4("089"), None);
+        assert_eq!(parse_legacy_ipv4("1..2"), None);
+    }
+
+    #[test]
+    fn test_encoded_hosts_blocked_for_other_schemes() {
+        let mut config = UrlValidationConfig::default();
+        config.allowed_schemes.insert("gopher".to_string());
+        let validator = UrlValidator::new(config);
+
+        // Not normalized by `url` for non-special schemes
+        assert!(matches!(
+            validator.validate("gopher://0x7f000001/"),
+            Err(PreviewError::LocalhostBlocked)
+        ));
+        assert!(matches!(
+            validator.validate("gopher://3232235521/"),
+            Err(PreviewError::PrivateIpBlocked(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_checked_rejects_blocked_addresses() {
+        let validator = UrlValidator::with_default_config();
+        assert!(matches!(
+            validator.resolve_checked("localhost", 80).await,
+            Err(PreviewError::PrivateIpBlocked(_))
+        ));
+        assert!(matches!(
+            validator.resolve_checked("::ffff:10.0.0.1", 80).await,
+            Err(PreviewError::PrivateIpBlocked(_))
+        ));
+        assert_eq!(
+            validator.resolve_checked("93.184.216.34", 443).await.unwrap(),
+            vec!["93.184.216.34:443".parse::<SocketAddr>().unwrap()]
+        );
+
+        let permissive = UrlValidator::new(UrlValidationConfig {
+            block_private_ips: false,
+            block_localhost: false,
+            ..Default::default()
+        });
+        assert!(permissive.resolve_checked("localhost", 80).await.is_ok());
+    }
+
+    #[test]
+    fn test_validate_many_preserves_order() {
+        let validator = UrlValidator::with_default_config();
+        let results = validator.validate_many(&[
+            "https://example.com",
+            "http://localhost",
+            "not a url",
+            "https://rust-lang.org/learn",
+        ]);
+
+        assert_eq!(results.len(), 4);
+        assert_eq!(results[0].as_ref().unwrap().as_str(), "https://example.com/");
+        assert!(matches!(results[1], Err(PreviewError::LocalhostBlocked)));
+        assert!(matches!(results[2], Err(PreviewError::UrlParseError(_))));
+        assert!(results[3].is_ok());
+    }
 }
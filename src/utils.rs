@@ -1,6 +1,7 @@
+use encoding_rs::{Encoding, UTF_8};
 use unicode_width::UnicodeWidthChar;
 
-use url::{ParseError, Url};
+use url::Url;
 
 /// Safely truncate a string, ensuring it is not truncated in the middle of multi-byte characters
 ///
@@ -34,24 +35,267 @@ pub fn truncate_str(s: &str, max_width: usize) -> String {
     result
 }
 
-pub fn pickup_host_from_url(url: &str) -> Result<String, ParseError> {
-    let parsed_url = Url::parse(url)?;
-    let scheme = parsed_url.scheme();
-    let host = parsed_url.host_str().ok_or(url::ParseError::EmptyHost)?;
+/// Resolve a URL found in a page against the document base URL.
+///
+/// Absolute `http(s)://` URLs are returned unchanged, protocol-relative `//host/path`
+/// URLs take the base scheme, and root-relative or relative paths are joined onto
+/// the base. Empty or malformed candidates yield `None`.
+pub fn resolve_url(base: &Url, candidate: &str) -> Option<String> {
+    let candidate = candidate.trim();
+    if candidate.is_empty() {
+        return None;
+    }
+
+    let is_absolute_http = ["http://", "https://"].iter().any(|scheme| {
+        candidate
+            .get(..scheme.len())
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case(scheme))
+    });
+    if is_absolute_http {
+        return Url::parse(candidate).ok().map(|_| candidate.to_string());
+    }
+
+    base.join(candidate).ok().map(String::from)
+}
+
+/// How many leading bytes are scanned for a `<meta>` charset declaration
+const META_CHARSET_SNIFF_LEN: usize = 4096;
+
+/// Decode an HTML body to a string, honoring the charset from the `Content-Type`
+/// header, then a BOM, then a `<meta charset>`/`http-equiv` declaration near the
+/// top of the document. Unknown charsets and invalid bytes fall back to lossy UTF-8.
+pub fn decode_html(bytes: &[u8], content_type: Option<&str>) -> String {
+    let encoding = content_type
+        .and_then(charset_from_content_type)
+        .or_else(|| Encoding::for_bom(bytes).map(|(encoding, _)| encoding))
+        .or_else(|| sniff_meta_charset(bytes))
+        .unwrap_or(UTF_8);
+
+    let (decoded, _, _) = encoding.decode(bytes);
+    decoded.into_owned()
+}
+
+/// Extract the encoding named by a `charset=` parameter in a `Content-Type` value
+fn charset_from_content_type(content_type: &str) -> Option<&'static Encoding> {
+    content_type.split(';').skip(1).find_map(|param| {
+        let (name, value) = param.split_once('=')?;
+        if !name.trim().eq_ignore_ascii_case("charset") {
+            return None;
+        }
+        Encoding::for_label(value.trim().trim_matches(['"', '\'']).as_bytes())
+    })
+}
+
+/// Find a `charset` declared in a `<meta>` tag within the first few KB
+fn sniff_meta_charset(bytes: &[u8]) -> Option<&'static Encoding> {
+    let head = &bytes[..bytes.len().min(META_CHARSET_SNIFF_LEN)];
+    let head = String::from_utf8_lossy(head).to_ascii_lowercase();
+
+    head.match_indices("charset").find_map(|(pos, _)| {
+        // Only trust declarations inside a <meta> tag
+        let tag_start = head[..pos].rfind('<')?;
+        if !head[tag_start..].starts_with("<meta") || head[tag_start..pos].contains('>') {
+            return None;
+        }
+
+        let rest = head[pos + "charset".len()..].trim_start();
+        let value = rest.strip_prefix('=')?.trim_start();
+        let value = value.trim_start_matches(['"', '\'']);
+        let end = value
+            .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | ':' | '.')))
+            .unwrap_or(value.len());
+        Encoding::for_label(&value.as_bytes()[..end])
+    })
+}
+
+/// Whether a body starts like an HTML document (`<!doctype html` or `<html`),
+/// ignoring a UTF-8 BOM, leading whitespace and letter case
+pub fn looks_like_html(bytes: &[u8]) -> bool {
+    let bytes = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+    let start = bytes
+        .iter()
+        .position(|b| !b.is_ascii_whitespace())
+        .unwrap_or(bytes.len());
+    let bytes = &bytes[start..];
+    [&b"<!doctype html"[..], b"<html"].iter().any(|signature| {
+        bytes.len() >= signature.len() && bytes[..signature.len()].eq_ignore_ascii_case(signature)
+    })
+}
+
+/// Decodes HTML entities left in extracted text, e.g. by pages that escape their
+/// metadata twice (`&amp;amp;`), and collapses runs of whitespace into single spaces
+pub fn normalize_text(text: &str) -> String {
+    html_escape::decode_html_entities(text)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Shortens `text` to at most `max_chars` characters, including a trailing `…`,
+/// cutting at the last word boundary that fits. A single overlong word is cut
+/// mid-word. Text that already fits is returned unchanged.
+pub fn truncate_at_word(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let Some(keep) = max_chars.checked_sub(1) else {
+        return String::new();
+    };
+    let end = text.char_indices().nth(keep).map_or(text.len(), |(i, _)| i);
+    let head = &text[..end];
+    // Cut at a word boundary unless the text continues the last word exactly there
+    let head = if text[end..].starts_with(char::is_whitespace) {
+        head
+    } else {
+        head.rfind(char::is_whitespace).map_or(head, |i| &head[..i])
+    };
+    let head = head.trim_end_matches(|c: char| c.is_whitespace() || matches!(c, ',' | ';' | ':'));
+    format!("{head}…")
+}
+
+/// Page titles that mark a "not found" page, compared after lowercasing and
+/// reducing punctuation to spaces
+const NOT_FOUND_TITLES: &[&str] = &[
+    "404",
+    "404 not found",
+    "404 page not found",
+    "404 error",
+    "error 404",
+    "error 404 not found",
+    "error 404 page not found",
+    "not found",
+    "page not found",
+    "page not found 404",
+    "404 file not found",
+    "file not found",
+];
+
+/// Whether a page's `<title>` is a not-found message, either as a whole
+/// ("404 Not Found") or as one part of a separated title ("Page Not Found | Example").
+/// Body text is ignored, so pages that merely mention "not found" don't match.
+pub fn looks_like_soft_404(html: &str) -> bool {
+    let Some(title) = document_title(html) else {
+        return false;
+    };
+    let normalize = |text: &str| -> String {
+        text.split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+            .to_lowercase()
+    };
+
+    std::iter::once(title.as_str())
+        .chain(title.split(['|', '–', '—', '·', '»']))
+        .chain(title.split(" - "))
+        .any(|part| NOT_FOUND_TITLES.contains(&normalize(part).as_str()))
+}
+
+/// Text of the first `<title>` element, with entities decoded
+fn document_title(html: &str) -> Option<String> {
+    let lower = html.to_ascii_lowercase();
+    let start = lower.find("<title")?;
+    let end = start + lower[start..].find("</title")?;
+    let fragment = scraper::Html::parse_document(&html[start..end]);
+    let selector = scraper::Selector::parse("title").ok()?;
+    let title = fragment
+        .select(&selector)
+        .next()?
+        .text()
+        .collect::<String>();
+    Some(title.trim().to_string())
+}
+
+/// Second-level labels that, combined with a country code, form a public suffix
+/// (e.g. `co.uk`, `com.au`). Not a full public suffix list, but covers common cases.
+const SECOND_LEVEL_SUFFIXES: &[&str] = &[
+    "ac", "co", "com", "edu", "gov", "net", "ne", "or", "org",
+];
+
+/// Returns the registrable label of a host, e.g. `news.example.co.uk` -> `example`
+pub fn registrable_label(host: &str) -> Option<&str> {
+    let host = host.trim_end_matches('.');
+    if host.parse::<std::net::IpAddr>().is_ok() || host.starts_with('[') {
+        return None;
+    }
 
-    let port = parsed_url
-        .port()
-        .map(|x| format!(":{x}"))
-        .or_else(|| Some("".to_string()))
-        .unwrap();
+    let labels: Vec<&str> = host.split('.').filter(|l| !l.is_empty()).collect();
+    match labels.len() {
+        0 => None,
+        1 => Some(labels[0]),
+        n => {
+            let tld = labels[n - 1];
+            let second = labels[n - 2];
+            if n >= 3 && tld.len() == 2 && SECOND_LEVEL_SUFFIXES.contains(&second) {
+                Some(labels[n - 3])
+            } else {
+                Some(second)
+            }
+        }
+    }
+}
 
-    Ok(format!("{scheme}://{host}{port}/"))
+/// Derives a human-friendly site name from a host by title-casing each hyphenated
+/// word of its registrable label, e.g. `www.example.co.uk` -> `Example` and
+/// `blog.rust-lang.org` -> `Rust-Lang`
+pub fn site_name_from_host(host: &str) -> Option<String> {
+    let label = registrable_label(host)?;
+    let words: Vec<String> = label
+        .split('-')
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first
+                    .to_uppercase()
+                    .chain(chars.flat_map(char::to_lowercase))
+                    .collect(),
+                None => String::new(),
+            }
+        })
+        .collect();
+    Some(words.join("-"))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_looks_like_html() {
+        assert!(looks_like_html(b"<!DOCTYPE html><html></html>"));
+        assert!(looks_like_html(b"\xEF\xBB\xBF\n  <HTML lang=\"en\">"));
+        assert!(!looks_like_html(b"%PDF-1.7"));
+        assert!(!looks_like_html(b"\x89PNG\r\n\x1a\n"));
+        assert!(!looks_like_html(b"<htm"));
+    }
+
+    #[test]
+    fn test_resolve_url() {
+        let base = Url::parse("https://www.example.com/albums/123").unwrap();
+        let cases = [
+            (
+                "https://cdn.example.net/a.jpg",
+                Some("https://cdn.example.net/a.jpg"),
+            ),
+            (
+                "//picasso-static.example.net/covers/123.jpg",
+                Some("https://picasso-static.example.net/covers/123.jpg"),
+            ),
+            ("/favicon.ico", Some("https://www.example.com/favicon.ico")),
+            ("covers/123.jpg", Some("https://www.example.com/albums/covers/123.jpg")),
+            ("http://exa mple.com/a.jpg", None),
+            ("   ", None),
+        ];
+
+        for (candidate, expected) in cases {
+            assert_eq!(
+                resolve_url(&base, candidate).as_deref(),
+                expected,
+                "resolving {candidate:?}"
+            );
+        }
+    }
+
     #[test]
     fn test_truncate_str() {
         assert_eq!(truncate_str("Hello, world!", 10), "Hello, ...");
@@ -59,4 +303,91 @@ mod tests {
         assert_eq!(truncate_str("Hello 你好！", 10), "Hello ...");
         assert_eq!(truncate_str("Hi!", 10), "Hi!");
     }
+
+    #[test]
+    fn test_normalize_text() {
+        assert_eq!(
+            normalize_text("  Tom &amp; Jerry&#39;s\n\t Show "),
+            "Tom & Jerry's Show"
+        );
+        assert_eq!(
+            normalize_text("Caf&eacute;&nbsp;&#x2014; Menu"),
+            "Café — Menu"
+        );
+        assert_eq!(normalize_text("Plain text"), "Plain text");
+    }
+
+    #[test]
+    fn test_truncate_at_word() {
+        assert_eq!(truncate_at_word("Short title", 20), "Short title");
+        assert_eq!(truncate_at_word("Exactly ten", 11), "Exactly ten");
+        assert_eq!(
+            truncate_at_word("The quick brown fox jumps over the lazy dog", 20),
+            "The quick brown fox…"
+        );
+        assert_eq!(truncate_at_word("Rust, the language", 8), "Rust…");
+        assert_eq!(truncate_at_word("Supercalifragilistic", 6), "Super…");
+        assert_eq!(truncate_at_word("日本語 の タイトル です", 8), "日本語 の…");
+    }
+
+    #[test]
+    fn test_looks_like_soft_404() {
+        let page = |title: &str| format!("<html><head><title>{title}</title></head></html>");
+        assert!(looks_like_soft_404(&page("404 Not Found")));
+        assert!(looks_like_soft_404(&page("Page Not Found | Example News")));
+        assert!(looks_like_soft_404(&page("Example &ndash; Page not found")));
+        assert!(looks_like_soft_404(&page("Error 404: Page Not Found")));
+        assert!(looks_like_soft_404(&page("  page NOT found  ")));
+
+        assert!(!looks_like_soft_404(&page("Lost and Found Office Hours")));
+        assert!(!looks_like_soft_404(&page(
+            "Why the file was not found - Blog"
+        )));
+        assert!(!looks_like_soft_404(&page("Room 404 | Grand Hotel")));
+        assert!(!looks_like_soft_404(
+            "<html><body>Page not found</body></html>"
+        ));
+    }
+
+    #[test]
+    fn test_site_name_from_host() {
+        assert_eq!(site_name_from_host("example.com").as_deref(), Some("Example"));
+        assert_eq!(site_name_from_host("www.example.co.uk").as_deref(), Some("Example"));
+        assert_eq!(site_name_from_host("blog.rust-lang.org").as_deref(), Some("Rust-Lang"));
+        assert_eq!(site_name_from_host("WWW.NYTIMES.COM").as_deref(), Some("Nytimes"));
+        assert_eq!(site_name_from_host("shop.example.com.au").as_deref(), Some("Example"));
+        assert_eq!(site_name_from_host("t.co").as_deref(), Some("T"));
+        assert_eq!(site_name_from_host("127.0.0.1"), None);
+    }
+
+    #[test]
+    fn test_decode_html_from_meta_charset() {
+        let html = "<html><head><meta charset=\"Shift_JIS\"><title>日本語のタイトル</title></head></html>";
+        let (bytes, _, _) = encoding_rs::SHIFT_JIS.encode(html);
+        assert_eq!(decode_html(&bytes, None), html);
+
+        let html = "<html><head><meta http-equiv=\"Content-Type\" content=\"text/html; charset=windows-1251\">\
+                    <title>Привет, мир</title></head></html>";
+        let (bytes, _, _) = encoding_rs::WINDOWS_1251.encode(html);
+        assert_eq!(decode_html(&bytes, Some("text/html")), html);
+    }
+
+    #[test]
+    fn test_decode_html_header_charset_wins() {
+        let html = "<html><head><meta charset=\"utf-8\"><title>Привет</title></head></html>";
+        let (bytes, _, _) = encoding_rs::WINDOWS_1251.encode(html);
+        assert_eq!(
+            decode_html(&bytes, Some("text/html; charset=\"windows-1251\"")),
+            html
+        );
+    }
+
+    #[test]
+    fn test_decode_html_falls_back_to_lossy_utf8() {
+        assert_eq!(decode_html(b"<p>caf\xe9</p>", None), "<p>caf\u{fffd}</p>");
+        assert_eq!(
+            decode_html("<p>ok</p>".as_bytes(), Some("text/html; charset=bogus")),
+            "<p>ok</p>"
+        );
+    }
 }
@@ -1,10 +1,15 @@
 //! Tests for browser-based preview generation
 
+#[cfg(feature = "browser")]
+mod common;
+
 #[cfg(feature = "browser")]
 mod browser_tests {
+    use super::common::{local_fetcher_config, MockResponse, MockServer};
+    use serde_json::{json, Value};
     use url_preview::{
-        BrowserFetcher, BrowserUsagePolicy, McpConfig, McpTransport, PreviewService,
-        PreviewServiceConfig,
+        BrowserFetcher, BrowserUsagePolicy, Fetcher, McpClient, McpConfig, McpTransport,
+        PreviewError, PreviewService, PreviewServiceConfig, WaitStrategy,
     };
 
     #[test]
@@ -122,6 +127,405 @@ mod browser_tests {
         assert_eq!(request["method"], "tools/call");
         assert_eq!(request["params"]["name"], "browser_navigate");
     }
+
+    const RENDERED_PAGE: &str =
+        "<html><head><title>Rendered by JS</title></head><body></body></html>";
+    /// base64 of `fake-png`
+    const SCREENSHOT_BASE64: &str = "ZmFrZS1wbmc=";
+
+    /// Streamable-HTTP MCP server that answers requests over SSE
+    async fn mock_sse_mcp_server() -> MockServer {
+        mock_mcp_server_evaluating(|_| RENDERED_PAGE.to_string()).await
+    }
+
+    /// Like [`mock_sse_mcp_server`], answering `browser_evaluate` calls with the
+    /// text `evaluate` returns for the evaluated function
+    async fn mock_mcp_server_evaluating(
+        evaluate: impl Fn(&str) -> String + Send + Sync + 'static,
+    ) -> MockServer {
+        MockServer::start(move |req| {
+            let message: Value = serde_json::from_slice(&req.body).unwrap_or_default();
+            let result = match message["method"].as_str().unwrap_or_default() {
+                "initialize" => json!({
+                    "protocolVersion": "2024-11-05",
+                    "capabilities": {"tools": {}},
+                    "serverInfo": {"name": "mock-mcp", "version": "0.0.0"}
+                }),
+                "tools/list" => json!({
+                    "tools": [{
+                        "name": "browser_navigate",
+                        "description": "Navigate to a URL",
+                        "inputSchema": {"type": "object"}
+                    }]
+                }),
+                "tools/call" => match message["params"]["name"].as_str().unwrap_or_default() {
+                    "browser_evaluate" => {
+                        let function = message["params"]["arguments"]["function"]
+                            .as_str()
+                            .unwrap_or_default();
+                        json!({"content": [{"type": "text", "text": evaluate(function)}]})
+                    }
+                    "browser_take_screenshot" => json!({
+                        "content": [{
+                            "type": "image",
+                            "mimeType": "image/png",
+                            "data": SCREENSHOT_BASE64
+                        }]
+                    }),
+                    _ => json!({"content": [{"type": "text", "text": "ok"}]}),
+                },
+                // Notifications are accepted without a body
+                _ => return MockResponse::new(202),
+            };
+            let reply = json!({"jsonrpc": "2.0", "id": message["id"], "result": result});
+            let body = format!(": ping\n\nevent: message\ndata: {reply}\n\n");
+            MockResponse::new(200)
+                .header("Content-Type", "text/event-stream")
+                .header("Mcp-Session-Id", "session-1")
+                .body(body.as_bytes())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_http_sse_transport_handshake() {
+        let server = mock_sse_mcp_server().await;
+        let client = McpClient::new(McpConfig {
+            enabled: true,
+            transport: McpTransport::HttpSse(server.url("/mcp")),
+            browser_timeout: 5,
+            ..Default::default()
+        });
+
+        client.start().await.unwrap();
+
+        let requests = server.requests();
+        let methods: Vec<String> = requests
+            .iter()
+            .map(|req| {
+                let message: Value = serde_json::from_slice(&req.body).unwrap();
+                message["method"].as_str().unwrap().to_string()
+            })
+            .collect();
+        assert_eq!(
+            methods,
+            ["initialize", "notifications/initialized", "tools/list"]
+        );
+        assert!(requests.iter().all(|req| req.method == "POST" && req.path == "/mcp"));
+        // The session assigned in the initialize reply is sent on later messages
+        assert_eq!(requests[0].header("mcp-session-id"), None);
+        assert_eq!(requests[1].header("mcp-session-id"), Some("session-1"));
+        assert_eq!(requests[2].header("mcp-session-id"), Some("session-1"));
+
+        // Starting again reuses the connection
+        client.start().await.unwrap();
+        assert_eq!(server.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_wait_strategies_use_supported_tools() {
+        let server = mock_sse_mcp_server().await;
+        let client_with = |strategy| {
+            McpClient::new(McpConfig {
+                enabled: true,
+                transport: McpTransport::HttpSse(server.url("/mcp")),
+                browser_timeout: 5,
+                wait_strategy: strategy,
+                ..Default::default()
+            })
+        };
+        let last_call = || {
+            let last = server.requests().pop().unwrap();
+            let message: Value = serde_json::from_slice(&last.body).unwrap();
+            assert_eq!(message["method"], "tools/call");
+            message["params"].clone()
+        };
+
+        // `browser_wait_for` only takes `time`, `text` and `textGone`
+        let client = client_with(WaitStrategy::FixedDelay(std::time::Duration::from_millis(
+            1500,
+        )));
+        client.start().await.unwrap();
+        client.wait_for_load().await.unwrap();
+        let call = last_call();
+        assert_eq!(call["name"], "browser_wait_for");
+        assert_eq!(call["arguments"], json!({"time": 1.5}));
+
+        // The other strategies poll the page, giving up after half the browser timeout
+        let client = client_with(WaitStrategy::NetworkIdle);
+        client.start().await.unwrap();
+        client.wait_for_load().await.unwrap();
+        let call = last_call();
+        assert_eq!(call["name"], "browser_evaluate");
+        let function = call["arguments"]["function"].as_str().unwrap();
+        assert!(function.contains("document.readyState"));
+        assert!(function.contains("Date.now() + 2500"));
+
+        let client = client_with(WaitStrategy::Selector("#app \"loaded\"".into()));
+        client.start().await.unwrap();
+        client.wait_for_load().await.unwrap();
+        let call = last_call();
+        assert_eq!(call["name"], "browser_evaluate");
+        let function = call["arguments"]["function"].as_str().unwrap();
+        assert!(function.contains(r##"document.querySelector("#app \"loaded\"")"##));
+
+        assert_eq!(
+            McpConfig::default().wait_strategy,
+            WaitStrategy::NetworkIdle
+        );
+    }
+
+    #[tokio::test]
+    async fn test_http_sse_transport_failure_is_browser_unavailable() {
+        let server = MockServer::start(|_| MockResponse::new(500)).await;
+        let client = McpClient::new(McpConfig {
+            enabled: true,
+            transport: McpTransport::HttpSse(server.url("/mcp")),
+            browser_timeout: 5,
+            ..Default::default()
+        });
+
+        match client.start().await {
+            Err(PreviewError::BrowserUnavailable(message)) => {
+                assert!(message.contains("/mcp"));
+            }
+            other => panic!("expected BrowserUnavailable, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_preview_service_starts_browser_once_on_first_use() {
+        let server = mock_sse_mcp_server().await;
+        let config = PreviewServiceConfig::new(100)
+            .with_mcp_config(McpConfig {
+                enabled: true,
+                transport: McpTransport::HttpSse(server.url("/mcp")),
+                browser_timeout: 5,
+                ..Default::default()
+            })
+            .with_browser_usage_policy(BrowserUsagePolicy::Always);
+        let service = std::sync::Arc::new(PreviewService::new_with_config(config));
+        let browser = service.browser_service.clone().unwrap();
+        assert!(!browser.is_initialized());
+
+        let handles: Vec<_> = (0..5)
+            .map(|i| {
+                let service = service.clone();
+                tokio::spawn(async move {
+                    service
+                        .generate_preview(&format!("https://spa.example.com/{i}"))
+                        .await
+                })
+            })
+            .collect();
+        for handle in handles {
+            let preview = handle.await.unwrap().unwrap();
+            assert_eq!(preview.title.as_deref(), Some("Rendered by JS"));
+        }
+
+        assert!(browser.is_initialized());
+        let handshakes = server
+            .requests()
+            .iter()
+            .filter(|req| {
+                let message: Value = serde_json::from_slice(&req.body).unwrap_or_default();
+                message["method"] == "initialize"
+            })
+            .count();
+        assert_eq!(handshakes, 1);
+    }
+
+    #[tokio::test]
+    async fn test_preview_service_shutdown_stops_browser() {
+        let server = mock_sse_mcp_server().await;
+        let config = PreviewServiceConfig::new(100)
+            .with_mcp_config(McpConfig {
+                enabled: true,
+                transport: McpTransport::HttpSse(server.url("/mcp")),
+                browser_timeout: 5,
+                ..Default::default()
+            })
+            .with_browser_usage_policy(BrowserUsagePolicy::Always);
+        let service = PreviewService::new_with_config(config);
+        let browser = service.browser_service.clone().unwrap();
+
+        service
+            .generate_preview("https://spa.example.com/")
+            .await
+            .unwrap();
+        assert!(browser.is_running().await);
+
+        service.shutdown().await.unwrap();
+        assert!(!browser.is_running().await);
+        // Shutting down again is a no-op
+        service.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_auto_policy_falls_back_to_browser_for_empty_static_preview() {
+        let site = MockServer::start(|_| {
+            MockResponse::html("<html><head></head><body><div id=\"root\"></div></body></html>")
+        })
+        .await;
+        let mcp = mock_sse_mcp_server().await;
+        let service_with_threshold = |threshold: f32| {
+            PreviewService::new_with_config(
+                PreviewServiceConfig::new(100)
+                    .with_default_fetcher(Fetcher::with_config(local_fetcher_config()))
+                    .with_mcp_config(McpConfig {
+                        enabled: true,
+                        transport: McpTransport::HttpSse(mcp.url("/mcp")),
+                        browser_timeout: 5,
+                        ..Default::default()
+                    })
+                    .with_browser_usage_policy(BrowserUsagePolicy::Auto)
+                    .with_browser_fallback_threshold(threshold),
+            )
+        };
+
+        let service = service_with_threshold(0.6);
+        let browser = service.browser_service.clone().unwrap();
+        let url = site.url("/app");
+        assert!(!browser.should_use_browser(&url));
+        let preview = service.generate_preview(&url).await.unwrap();
+        assert_eq!(preview.title.as_deref(), Some("Rendered by JS"));
+        assert!(browser.is_initialized());
+
+        // A zero threshold keeps whatever the static fetch found
+        let service = service_with_threshold(0.0);
+        let preview = service.generate_preview(&url).await.unwrap();
+        assert_eq!(preview.title, None);
+        assert!(!service.browser_service.clone().unwrap().is_initialized());
+    }
+
+    #[tokio::test]
+    async fn test_browser_preview_reads_late_injected_og_tags() {
+        let server = mock_mcp_server_evaluating(|function| {
+            if function.contains("outerHTML") {
+                "<html><head></head><body><div id=\"app\"></div></body></html>".to_string()
+            } else {
+                json!({
+                    "title": "Injected title",
+                    "description": "Set after load",
+                    "image": "/cards/injected.png"
+                })
+                .to_string()
+            }
+        })
+        .await;
+        let fetcher = BrowserFetcher::new(
+            McpConfig {
+                enabled: true,
+                transport: McpTransport::HttpSse(server.url("/mcp")),
+                browser_timeout: 5,
+                ..Default::default()
+            },
+            BrowserUsagePolicy::Always,
+        );
+
+        let preview = fetcher
+            .generate_preview("https://spa.example.com/posts/1")
+            .await
+            .unwrap();
+        assert_eq!(preview.title.as_deref(), Some("Injected title"));
+        assert_eq!(preview.description.as_deref(), Some("Set after load"));
+        assert_eq!(
+            preview.image_url.as_deref(),
+            Some("https://spa.example.com/cards/injected.png")
+        );
+
+        // The DOM is captured only after waiting for the page
+        let functions: Vec<String> = server
+            .requests()
+            .iter()
+            .filter_map(|req| {
+                let message: Value = serde_json::from_slice(&req.body).ok()?;
+                message["params"]["arguments"]["function"]
+                    .as_str()
+                    .map(String::from)
+            })
+            .collect();
+        let wait = functions
+            .iter()
+            .position(|function| function.contains("document.readyState"));
+        let capture = functions
+            .iter()
+            .position(|function| function.contains("outerHTML"));
+        assert!(wait.is_some() && wait < capture);
+    }
+
+    #[tokio::test]
+    async fn test_generate_preview_with_screenshot() {
+        use url_preview::BrowserPreviewService;
+
+        let server = mock_sse_mcp_server().await;
+        let service = BrowserPreviewService::new(
+            McpConfig {
+                enabled: true,
+                transport: McpTransport::HttpSse(server.url("/mcp")),
+                browser_timeout: 5,
+                ..Default::default()
+            },
+            BrowserUsagePolicy::Never,
+        );
+
+        let (preview, screenshot) = service
+            .generate_preview_with_screenshot("https://spa.example.com/")
+            .await
+            .unwrap();
+
+        assert_eq!(screenshot, b"fake-png");
+        assert_eq!(preview.title.as_deref(), Some("Rendered by JS"));
+        assert_eq!(
+            preview.image_url,
+            Some(format!("data:image/png;base64,{SCREENSHOT_BASE64}"))
+        );
+    }
+
+    #[cfg(feature = "llm")]
+    #[tokio::test]
+    async fn test_image_preprocessing_uses_screenshot() {
+        use std::sync::Arc;
+        use url_preview::{ContentFormat, ContentPreprocessor, LLMExtractorConfig};
+
+        let server = mock_sse_mcp_server().await;
+        let browser = Arc::new(BrowserFetcher::new(
+            McpConfig {
+                enabled: true,
+                transport: McpTransport::HttpSse(server.url("/mcp")),
+                browser_timeout: 5,
+                ..Default::default()
+            },
+            BrowserUsagePolicy::Never,
+        ));
+        let config = LLMExtractorConfig {
+            format: ContentFormat::Image,
+            ..Default::default()
+        };
+
+        // Without a screenshot source the format stays unsupported
+        let result = ContentPreprocessor::new()
+            .preprocess_url("https://spa.example.com/", "", &config)
+            .await;
+        assert!(matches!(result, Err(PreviewError::UnsupportedOperation(_))));
+
+        let processed = ContentPreprocessor::new()
+            .with_browser(browser)
+            .preprocess_url("https://spa.example.com/", "", &config)
+            .await
+            .unwrap();
+
+        assert_eq!(processed.format, ContentFormat::Image);
+        assert_eq!(processed.content, SCREENSHOT_BASE64);
+        assert_eq!(
+            processed.metadata.get("content_type").map(String::as_str),
+            Some("image")
+        );
+        assert_eq!(
+            processed.metadata.get("mime_type").map(String::as_str),
+            Some("image/png")
+        );
+    }
 }
 
 #[cfg(not(feature = "browser"))]
@@ -129,4 +533,4 @@ mod browser_tests {
 fn test_browser_feature_disabled() {
     // This test ensures the crate compiles without browser feature
     assert!(true, "Browser feature is disabled");
-}
\ No newline at end of file
+}
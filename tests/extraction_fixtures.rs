@@ -0,0 +1,83 @@
+//! Fixture-driven regression tests for `MetadataExtractor`
+//!
+//! Every `tests/fixtures/<name>.html` is paired with a `<name>.json` spec:
+//!
+//! ```json
+//! {
+//!   "url": "https://example.com/page",
+//!   "pending": "optional: why this fixture does not pass yet",
+//!   "expected": { "title": "...", "image_url": null }
+//! }
+//! ```
+//!
+//! Only the `Preview` fields listed under `expected` are compared. Fixtures marked
+//! `pending` document known bugs: they must still fail, so drop the marker once
+//! the bug is fixed. Fixture bytes are decoded with `decode_html`, so non-UTF-8
+//! pages must declare their charset in a `<meta>` tag.
+
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use url_preview::{decode_html, MetadataExtractor};
+
+fn fixtures_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
+}
+
+/// Returns the mismatching fields as `field: expected .. got ..` lines
+fn check_fixture(html_path: &Path, spec: &Value) -> Vec<String> {
+    let url = spec["url"].as_str().expect("fixture spec needs a `url`");
+    let bytes = fs::read(html_path).unwrap();
+    let html = decode_html(&bytes, None);
+
+    let preview = match MetadataExtractor::new().extract(&html, url) {
+        Ok(preview) => serde_json::to_value(preview).unwrap(),
+        Err(e) => return vec![format!("extraction failed: {e}")],
+    };
+
+    spec["expected"]
+        .as_object()
+        .expect("fixture spec needs an `expected` object")
+        .iter()
+        .filter(|(field, expected)| preview.get(field.as_str()) != Some(*expected))
+        .map(|(field, expected)| {
+            format!(
+                "{field}: expected {expected}, got {}",
+                preview.get(field.as_str()).unwrap_or(&Value::Null)
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn test_extraction_fixtures() {
+    let mut html_paths: Vec<PathBuf> = fs::read_dir(fixtures_dir())
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "html"))
+        .collect();
+    html_paths.sort();
+    assert!(!html_paths.is_empty(), "no fixtures found");
+
+    let mut failures = Vec::new();
+    for html_path in &html_paths {
+        let name = html_path.file_stem().unwrap().to_string_lossy();
+        let spec_path = html_path.with_extension("json");
+        let spec: Value = serde_json::from_str(
+            &fs::read_to_string(&spec_path)
+                .unwrap_or_else(|_| panic!("missing spec {}", spec_path.display())),
+        )
+        .unwrap_or_else(|e| panic!("invalid spec {}: {e}", spec_path.display()));
+
+        let mismatches = check_fixture(html_path, &spec);
+        match spec.get("pending").and_then(Value::as_str) {
+            Some(reason) if mismatches.is_empty() => failures.push(format!(
+                "{name}: passes now, remove `pending` (\"{reason}\")"
+            )),
+            Some(_) => {}
+            None => failures.extend(mismatches.into_iter().map(|m| format!("{name}: {m}"))),
+        }
+    }
+
+    assert!(failures.is_empty(), "fixture failures:\n{}", failures.join("\n"));
+}
@@ -0,0 +1,906 @@
+mod common;
+
+use common::{local_fetcher_config, MockResponse, MockServer};
+use reqwest::header::{HeaderMap, HeaderValue, COOKIE, REFERER};
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use url_preview::{
+    ContentLimits, FetchResult, Fetcher, FetcherConfig, PreviewError, ProxyConfig, RetryConfig,
+    UrlValidationConfig,
+};
+
+const OG_PAGE: &str = r#"<html><head>
+    <meta property="og:title" content="Forbidden but useful">
+</head><body></body></html>"#;
+
+#[tokio::test]
+async fn test_non_success_status_is_rejected_by_default() {
+    let server = MockServer::start(|_| MockResponse::html(OG_PAGE).status(403)).await;
+    let fetcher = Fetcher::with_config(local_fetcher_config());
+
+    let result = fetcher.fetch(&server.url("/page")).await;
+    assert!(matches!(
+        result,
+        Err(PreviewError::ClientError { status: 403, .. })
+    ));
+}
+
+#[tokio::test]
+async fn test_configured_success_statuses_allow_parsing_body() {
+    let server = MockServer::start(|_| MockResponse::html(OG_PAGE).status(403)).await;
+    let fetcher = Fetcher::with_config(FetcherConfig {
+        success_statuses: Some(HashSet::from([200, 403])),
+        ..local_fetcher_config()
+    });
+
+    match fetcher.fetch(&server.url("/page")).await {
+        Ok(FetchResult::Html(html)) => assert!(html.contains("Forbidden but useful")),
+        other => panic!("expected HTML, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_probe_uses_head_request() {
+    let server = MockServer::start(|_| MockResponse::html(OG_PAGE)).await;
+    let fetcher = Fetcher::with_config(local_fetcher_config());
+
+    let probe = fetcher.probe(&server.url("/page")).await.unwrap();
+    assert_eq!(probe.content_type.as_deref(), Some("text/html"));
+    assert_eq!(probe.content_length, Some(OG_PAGE.len() as u64));
+    assert!(probe.likely_html);
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].method, "HEAD");
+}
+
+#[tokio::test]
+async fn test_probe_falls_back_to_ranged_get() {
+    let server = MockServer::start(|req| {
+        if req.method == "HEAD" {
+            MockResponse::new(405)
+        } else {
+            MockResponse::new(206)
+                .header("Content-Type", "application/pdf")
+                .header("Content-Range", "bytes 0-0/52000")
+                .body(b"%")
+        }
+    })
+    .await;
+    let fetcher = Fetcher::with_config(local_fetcher_config());
+
+    let probe = fetcher.probe(&server.url("/paper.pdf")).await.unwrap();
+    assert_eq!(probe.content_type.as_deref(), Some("application/pdf"));
+    assert_eq!(probe.content_length, Some(52000));
+    assert!(!probe.likely_html);
+
+    let requests = server.requests();
+    assert_eq!(requests[1].method, "GET");
+    assert_eq!(requests[1].header("range"), Some("bytes=0-0"));
+}
+
+#[tokio::test]
+async fn test_probe_still_validates_url() {
+    let fetcher = Fetcher::new();
+    assert!(matches!(
+        fetcher.probe("http://localhost:8080/").await,
+        Err(PreviewError::LocalhostBlocked)
+    ));
+}
+
+#[tokio::test]
+async fn test_fetch_raw_returns_status_headers_and_body() {
+    let server = MockServer::start(|_| {
+        MockResponse::html(OG_PAGE)
+            .status(403)
+            .header("X-Served-By", "mock")
+    })
+    .await;
+    let fetcher = Fetcher::with_config(local_fetcher_config());
+
+    let raw = fetcher.fetch_raw(&server.url("/page")).await.unwrap();
+    assert_eq!(raw.status, 403);
+    assert_eq!(
+        raw.headers.get("x-served-by").and_then(|v| v.to_str().ok()),
+        Some("mock")
+    );
+    assert_eq!(raw.body, OG_PAGE.as_bytes());
+    assert_eq!(raw.final_url, server.url("/page"));
+}
+
+#[tokio::test]
+async fn test_fetch_raw_still_applies_limits() {
+    assert!(matches!(
+        Fetcher::new().fetch_raw("http://localhost:8080/").await,
+        Err(PreviewError::LocalhostBlocked)
+    ));
+
+    let server = MockServer::start(|_| MockResponse::html(&"x".repeat(4096))).await;
+    let fetcher = Fetcher::with_config(FetcherConfig {
+        content_limits: ContentLimits {
+            max_content_size: 1024,
+            ..Default::default()
+        },
+        ..local_fetcher_config()
+    });
+    assert!(matches!(
+        fetcher.fetch_raw(&server.url("/big")).await,
+        Err(PreviewError::ContentSizeExceeded { .. })
+    ));
+}
+
+fn brotli_compress(data: &[u8]) -> Vec<u8> {
+    let mut writer = brotli::CompressorWriter::new(Vec::new(), 4096, 9, 22);
+    writer.write_all(data).unwrap();
+    writer.into_inner()
+}
+
+#[tokio::test]
+async fn test_brotli_body_is_decoded() {
+    let server = MockServer::start(|_| {
+        MockResponse::html("")
+            .header("Content-Encoding", "br")
+            .body(&brotli_compress(OG_PAGE.as_bytes()))
+    })
+    .await;
+    let fetcher = Fetcher::with_config(local_fetcher_config());
+
+    match fetcher.fetch(&server.url("/page")).await {
+        Ok(FetchResult::Html(html)) => assert_eq!(html, OG_PAGE),
+        other => panic!("expected HTML, got {other:?}"),
+    }
+
+    let requests = server.requests();
+    let accept_encoding = requests[0].header("accept-encoding").unwrap_or("");
+    assert!(accept_encoding.contains("br"));
+}
+
+#[tokio::test]
+async fn test_decompressed_size_is_limited() {
+    const LIMIT: usize = 64 * 1024;
+    // Compresses to a few hundred bytes but expands far past the limit
+    let compressed = brotli_compress(&vec![b' '; 8 * 1024 * 1024]);
+    assert!(compressed.len() < LIMIT);
+    let server = MockServer::start(move |_| {
+        MockResponse::html("")
+            .header("Content-Encoding", "br")
+            .body(&compressed)
+    })
+    .await;
+    let fetcher = Fetcher::with_config(FetcherConfig {
+        content_limits: ContentLimits {
+            max_content_size: LIMIT,
+            ..Default::default()
+        },
+        ..local_fetcher_config()
+    });
+
+    assert!(matches!(
+        fetcher.fetch(&server.url("/bomb")).await,
+        Err(PreviewError::ContentSizeExceeded { limit: LIMIT, .. })
+    ));
+}
+
+#[tokio::test]
+async fn test_chunked_body_over_limit_is_rejected_while_streaming() {
+    const LIMIT: usize = 64 * 1024;
+    const TOTAL: usize = 4 * 1024 * 1024;
+    let body = format!("<html><body>{}</body></html>", "x".repeat(TOTAL));
+    let server = MockServer::start(move |_| MockResponse::html(&body).chunked(8 * 1024)).await;
+    let fetcher = Fetcher::with_config(FetcherConfig {
+        content_limits: ContentLimits {
+            max_content_size: LIMIT,
+            ..Default::default()
+        },
+        ..local_fetcher_config()
+    });
+
+    match fetcher.fetch(&server.url("/huge")).await {
+        Err(PreviewError::ContentSizeExceeded { size, limit }) => {
+            assert_eq!(limit, LIMIT);
+            assert!(size < TOTAL, "body was fully buffered before the limit check");
+        }
+        other => panic!("expected ContentSizeExceeded, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_non_utf8_body_is_decoded_from_header_charset() {
+    let html = "<html><head><title>Новости</title></head></html>";
+    let (bytes, _, _) = encoding_rs::WINDOWS_1251.encode(html);
+    let bytes = bytes.into_owned();
+    let server = MockServer::start(move |_| {
+        MockResponse::new(200)
+            .header("Content-Type", "text/html; charset=windows-1251")
+            .body(&bytes)
+    })
+    .await;
+    let fetcher = Fetcher::with_config(local_fetcher_config());
+
+    match fetcher.fetch(&server.url("/news")).await {
+        Ok(FetchResult::Html(body)) => assert_eq!(body, html),
+        other => panic!("expected HTML, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_respect_robots_skips_disallowed_paths() {
+    let server = MockServer::start(|req| match req.path.as_str() {
+        "/robots.txt" => MockResponse::new(200)
+            .header("Content-Type", "text/plain")
+            .body(b"User-agent: *\nDisallow: /private/\nAllow: /private/public\n"),
+        _ => MockResponse::html(OG_PAGE),
+    })
+    .await;
+    let fetcher = Fetcher::with_config(FetcherConfig {
+        respect_robots: true,
+        ..local_fetcher_config()
+    });
+
+    assert!(fetcher.fetch(&server.url("/articles/1")).await.is_ok());
+    assert!(fetcher.fetch(&server.url("/private/public/2")).await.is_ok());
+    assert!(matches!(
+        fetcher.fetch(&server.url("/private/secret")).await,
+        Err(PreviewError::RobotsDisallowed(_))
+    ));
+
+    // robots.txt is fetched once per origin and the disallowed page never is
+    let paths: Vec<String> = server.requests().into_iter().map(|req| req.path).collect();
+    assert_eq!(
+        paths.iter().filter(|path| *path == "/robots.txt").count(),
+        1
+    );
+    assert!(!paths.iter().any(|path| path == "/private/secret"));
+}
+
+#[tokio::test]
+async fn test_respect_robots_allows_everything_when_missing() {
+    let server = MockServer::start(|req| match req.path.as_str() {
+        "/robots.txt" => MockResponse::new(404),
+        _ => MockResponse::html(OG_PAGE),
+    })
+    .await;
+    let fetcher = Fetcher::with_config(FetcherConfig {
+        respect_robots: true,
+        ..local_fetcher_config()
+    });
+
+    assert!(fetcher.fetch(&server.url("/private/secret")).await.is_ok());
+}
+
+#[tokio::test]
+async fn test_oversized_robots_txt_is_read_only_up_to_the_cap() {
+    // Rules past the first 512 KiB are never read
+    let mut robots = b"User-agent: *\nDisallow: /early/\n".to_vec();
+    robots.extend(std::iter::repeat_n(b'#', 1024 * 1024));
+    robots.extend_from_slice(b"\nDisallow: /late/\n");
+    let server = MockServer::start(move |req| match req.path.as_str() {
+        "/robots.txt" => MockResponse::new(200)
+            .header("Content-Type", "text/plain")
+            .body(&robots)
+            .chunked(16 * 1024),
+        _ => MockResponse::html(OG_PAGE),
+    })
+    .await;
+    let fetcher = Fetcher::with_config(FetcherConfig {
+        respect_robots: true,
+        ..local_fetcher_config()
+    });
+
+    assert!(matches!(
+        fetcher.fetch(&server.url("/early/page")).await,
+        Err(PreviewError::RobotsDisallowed(_))
+    ));
+    assert!(fetcher.fetch(&server.url("/late/page")).await.is_ok());
+}
+
+#[tokio::test]
+async fn test_robots_fetch_is_validated() {
+    // The page itself is blocked before robots.txt is ever requested
+    let server = MockServer::start(|_| MockResponse::html(OG_PAGE)).await;
+    let fetcher = Fetcher::with_config(FetcherConfig {
+        respect_robots: true,
+        ..Default::default()
+    });
+
+    assert!(fetcher.fetch(&server.url("/page")).await.is_err());
+    assert!(server.requests().is_empty());
+}
+
+#[tokio::test]
+async fn test_fetch_with_headers_sends_custom_headers() {
+    let server = MockServer::start(|req| {
+        let referer = req.header("referer").unwrap_or("none");
+        MockResponse::html(&format!("<html><body>referer={referer}</body></html>"))
+    })
+    .await;
+    let fetcher = Fetcher::with_config(local_fetcher_config());
+
+    let mut headers = HeaderMap::new();
+    headers.insert(REFERER, HeaderValue::from_static("https://example.com/"));
+    headers.insert(COOKIE, HeaderValue::from_static("session=abc"));
+
+    match fetcher.fetch_with_headers(&server.url("/page"), headers).await {
+        Ok(FetchResult::Html(html)) => assert!(html.contains("referer=https://example.com/")),
+        other => panic!("expected HTML, got {other:?}"),
+    }
+    let requests = server.requests();
+    assert_eq!(requests[0].header("cookie"), Some("session=abc"));
+
+    // Plain fetch sends neither header
+    fetcher.fetch(&server.url("/page")).await.unwrap();
+    assert_eq!(server.requests()[1].header("referer"), None);
+}
+
+#[tokio::test]
+async fn test_fetch_with_headers_still_validates_url() {
+    let server = MockServer::start(|_| MockResponse::html(OG_PAGE)).await;
+    let fetcher = Fetcher::new();
+
+    let mut headers = HeaderMap::new();
+    headers.insert(REFERER, HeaderValue::from_static("https://example.com/"));
+    assert!(matches!(
+        fetcher.fetch_with_headers(&server.url("/page"), headers).await,
+        Err(PreviewError::LocalhostBlocked)
+    ));
+    assert!(server.requests().is_empty());
+}
+
+fn fast_retries(max_retries: u32) -> RetryConfig {
+    RetryConfig {
+        max_retries,
+        initial_delay: Duration::from_millis(10),
+        max_delay: Duration::from_millis(50),
+        jitter: false,
+    }
+}
+
+#[tokio::test]
+async fn test_fetch_retries_transient_server_errors() {
+    let attempts = AtomicUsize::new(0);
+    let server = MockServer::start(move |_| {
+        if attempts.fetch_add(1, Ordering::SeqCst) < 2 {
+            MockResponse::new(503).header("Retry-After", "0")
+        } else {
+            MockResponse::html(OG_PAGE)
+        }
+    })
+    .await;
+    let fetcher = Fetcher::with_config(FetcherConfig {
+        retry: fast_retries(2),
+        ..local_fetcher_config()
+    });
+
+    match fetcher.fetch(&server.url("/page")).await {
+        Ok(FetchResult::Html(html)) => assert!(html.contains("Forbidden but useful")),
+        other => panic!("expected HTML, got {other:?}"),
+    }
+    assert_eq!(server.requests().len(), 3);
+}
+
+#[tokio::test]
+async fn test_fetch_gives_up_after_max_retries() {
+    let server = MockServer::start(|_| MockResponse::new(503)).await;
+    let fetcher = Fetcher::with_config(FetcherConfig {
+        retry: fast_retries(1),
+        ..local_fetcher_config()
+    });
+
+    assert!(matches!(
+        fetcher.fetch(&server.url("/page")).await,
+        Err(PreviewError::ServerError { status: 503, .. })
+    ));
+    assert_eq!(server.requests().len(), 2);
+}
+
+#[tokio::test]
+async fn test_fetch_does_not_retry_client_errors() {
+    let server = MockServer::start(|_| MockResponse::new(403)).await;
+    let fetcher = Fetcher::with_config(FetcherConfig {
+        retry: fast_retries(3),
+        ..local_fetcher_config()
+    });
+
+    assert!(matches!(
+        fetcher.fetch(&server.url("/page")).await,
+        Err(PreviewError::ClientError { status: 403, .. })
+    ));
+    assert_eq!(server.requests().len(), 1);
+
+    // Blocked URLs never reach the network at all
+    let fetcher = Fetcher::with_config(FetcherConfig {
+        retry: fast_retries(3),
+        ..Default::default()
+    });
+    assert!(matches!(
+        fetcher.fetch(&server.url("/page")).await,
+        Err(PreviewError::LocalhostBlocked)
+    ));
+    assert_eq!(server.requests().len(), 1);
+}
+
+/// Serves `first` once, then the OG page
+async fn rate_limited_then_ok(first: MockResponse) -> MockServer {
+    let first = std::sync::Mutex::new(Some(first));
+    MockServer::start(move |_| {
+        first
+            .lock()
+            .unwrap()
+            .take()
+            .unwrap_or_else(|| MockResponse::html(OG_PAGE))
+    })
+    .await
+}
+
+#[tokio::test]
+async fn test_retry_after_seconds_replaces_backoff() {
+    let server = rate_limited_then_ok(MockResponse::new(429).header("Retry-After", "0")).await;
+    // Without Retry-After the first retry would wait a full minute
+    let fetcher = Fetcher::with_config(FetcherConfig {
+        retry: RetryConfig {
+            max_retries: 1,
+            initial_delay: Duration::from_secs(60),
+            max_delay: Duration::from_secs(60),
+            jitter: false,
+        },
+        ..local_fetcher_config()
+    });
+
+    let result = tokio::time::timeout(Duration::from_secs(5), fetcher.fetch(&server.url("/page")))
+        .await
+        .expect("Retry-After was not honored");
+    assert!(matches!(result, Ok(FetchResult::Html(_))));
+    assert_eq!(server.requests().len(), 2);
+}
+
+#[tokio::test]
+async fn test_retry_after_http_date() {
+    // A date in the past means "retry now"
+    let server = rate_limited_then_ok(
+        MockResponse::new(503).header("Retry-After", "Wed, 21 Oct 2015 07:28:00 GMT"),
+    )
+    .await;
+    let fetcher = Fetcher::with_config(local_fetcher_config());
+
+    let start = std::time::Instant::now();
+    let html = fetcher.fetch_with_backoff(&server.url("/page")).await.unwrap();
+    assert!(html.contains("Forbidden but useful"));
+    // fetch_with_backoff would otherwise sleep for a second
+    assert!(start.elapsed() < Duration::from_millis(900));
+    assert_eq!(server.requests().len(), 2);
+}
+
+#[tokio::test]
+async fn test_rate_limited_after_retries_exhausted() {
+    let server = MockServer::start(|_| MockResponse::new(429).header("Retry-After", "0")).await;
+    let fetcher = Fetcher::with_config(FetcherConfig {
+        retry: fast_retries(1),
+        ..local_fetcher_config()
+    });
+
+    match fetcher.fetch(&server.url("/page")).await {
+        Err(PreviewError::RateLimited { retry_after }) => {
+            assert_eq!(retry_after, Some(Duration::ZERO));
+        }
+        other => panic!("expected RateLimited, got {other:?}"),
+    }
+    assert_eq!(server.requests().len(), 2);
+}
+
+#[tokio::test]
+async fn test_resolve_and_check_blocks_hostnames_resolving_to_loopback() {
+    let server = MockServer::start(|_| MockResponse::html(OG_PAGE)).await;
+    // `localhost` passes the name checks here but resolves to a loopback address
+    let url = format!("http://localhost:{}/page", server.addr().port());
+    let validation = UrlValidationConfig {
+        block_localhost: false,
+        block_private_ips: true,
+        allowed_ports: HashSet::new(),
+        ..Default::default()
+    };
+
+    let unchecked = Fetcher::with_config(FetcherConfig {
+        url_validation: validation.clone(),
+        ..Default::default()
+    });
+    assert!(unchecked.fetch(&url).await.is_ok());
+    assert_eq!(server.requests().len(), 1);
+
+    let checked = Fetcher::with_config(FetcherConfig {
+        url_validation: UrlValidationConfig {
+            resolve_and_check: true,
+            ..validation
+        },
+        ..Default::default()
+    });
+    match checked.fetch(&url).await {
+        Err(PreviewError::PrivateIpBlocked(ip)) => {
+            assert!(ip == "127.0.0.1" || ip == "::1", "unexpected ip {ip}")
+        }
+        other => panic!("expected PrivateIpBlocked, got {other:?}"),
+    }
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[tokio::test]
+async fn test_resolved_host_pins_connection_address() {
+    let server = MockServer::start(|_| MockResponse::html(OG_PAGE)).await;
+    let fetcher = Fetcher::with_config(
+        local_fetcher_config().with_resolved_host("pinned.invalid", server.addr()),
+    );
+
+    // `.invalid` never resolves, so reaching the server proves the pin was used
+    let url = format!("http://pinned.invalid:{}/page", server.addr().port());
+    match fetcher.fetch(&url).await.unwrap() {
+        FetchResult::Html(html) => assert!(html.contains("Forbidden but useful")),
+        other => panic!("expected HTML, got {other:?}"),
+    }
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].path, "/page");
+}
+
+#[tokio::test]
+async fn test_user_agent_override_applies_to_matching_host() {
+    let server = MockServer::start(|_| MockResponse::html(OG_PAGE)).await;
+    let fetcher = Fetcher::with_config(FetcherConfig {
+        user_agent: "default-agent".to_string(),
+        user_agent_overrides: HashMap::from([(
+            "example.com".to_string(),
+            "example-agent".to_string(),
+        )]),
+        ..local_fetcher_config()
+            .with_resolved_host("www.example.com", server.addr())
+            .with_resolved_host("other.invalid", server.addr())
+    });
+
+    let port = server.addr().port();
+    fetcher
+        .fetch(&format!("http://www.example.com:{port}/a"))
+        .await
+        .unwrap();
+    fetcher
+        .fetch(&format!("http://other.invalid:{port}/b"))
+        .await
+        .unwrap();
+
+    let requests = server.requests();
+    assert_eq!(requests[0].header("user-agent"), Some("example-agent"));
+    assert_eq!(requests[1].header("user-agent"), Some("default-agent"));
+}
+
+#[tokio::test]
+async fn test_probe_sends_the_same_user_agent_as_fetch() {
+    let server = MockServer::start(|_| MockResponse::html(OG_PAGE)).await;
+    let fetcher = Fetcher::with_config(FetcherConfig {
+        user_agent: "default-agent".to_string(),
+        user_agent_overrides: HashMap::from([(
+            "example.com".to_string(),
+            "example-agent".to_string(),
+        )]),
+        ..local_fetcher_config().with_resolved_host("www.example.com", server.addr())
+    });
+
+    let url = format!("http://www.example.com:{}/a", server.addr().port());
+    fetcher.probe(&url).await.unwrap();
+
+    let requests = server.requests();
+    assert_eq!(requests[0].method, "HEAD");
+    assert_eq!(requests[0].header("user-agent"), Some("example-agent"));
+}
+
+#[tokio::test]
+async fn test_connect_timeout_fails_fast_as_connection_error() {
+    let fetcher = Fetcher::with_config(FetcherConfig {
+        timeout: Duration::from_secs(30),
+        connect_timeout: Duration::from_millis(200),
+        retry: fast_retries(0),
+        ..local_fetcher_config()
+    });
+
+    // A listener that never accepts: once its backlog is full, further
+    // connection attempts either hang until the connect timeout or are refused
+    let socket = tokio::net::TcpSocket::new_v4().unwrap();
+    socket.bind("127.0.0.1:0".parse().unwrap()).unwrap();
+    let listener = socket.listen(1).unwrap();
+    let addr = listener.local_addr().unwrap();
+    let mut backlog = Vec::new();
+    while backlog.len() < 64 {
+        match std::net::TcpStream::connect_timeout(&addr, Duration::from_millis(100)) {
+            Ok(stream) => backlog.push(stream),
+            Err(_) => break,
+        }
+    }
+
+    let started = std::time::Instant::now();
+    let result = fetcher.fetch(&format!("http://{addr}/")).await;
+    assert!(
+        matches!(result, Err(PreviewError::ConnectionError(_))),
+        "expected ConnectionError, got {result:?}"
+    );
+    assert!(started.elapsed() < Duration::from_secs(5));
+}
+
+#[tokio::test]
+async fn test_fetch_batch_all_keeps_every_result_in_order() {
+    let server = MockServer::start(|req| match req.path.as_str() {
+        "/a" => MockResponse::html("<title>A</title>"),
+        "/b" => MockResponse::html("<title>B</title>"),
+        _ => MockResponse::new(404),
+    })
+    .await;
+    let fetcher = Fetcher::with_config(local_fetcher_config());
+
+    let (a, missing, b) = (server.url("/a"), server.url("/missing"), server.url("/b"));
+    let urls = vec![
+        a.as_str(),
+        missing.as_str(),
+        "ftp://example.com/",
+        b.as_str(),
+    ];
+    let results = fetcher.fetch_batch_all(urls.clone()).await;
+
+    assert_eq!(results.len(), 4);
+    assert!(matches!(&results[0], Ok(FetchResult::Html(html)) if html.contains(">A<")));
+    assert!(matches!(results[1], Err(PreviewError::NotFound(_))));
+    assert!(matches!(results[2], Err(PreviewError::InvalidUrlScheme(_))));
+    assert!(matches!(&results[3], Ok(FetchResult::Html(html)) if html.contains(">B<")));
+
+    // The fail-fast variant reports the first failure in input order
+    assert!(matches!(
+        fetcher.fetch_batch(urls).await,
+        Err(PreviewError::NotFound(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_fetch_batch_with_concurrency_bounds_in_flight_requests() {
+    let server = MockServer::start(|req| {
+        let delay = if req.path == "/0" { 150 } else { 50 };
+        MockResponse::html(&format!("<title>{}</title>", req.path))
+            .delayed(Duration::from_millis(delay))
+    })
+    .await;
+    let fetcher = Fetcher::with_config(local_fetcher_config());
+
+    let urls: Vec<String> = (0..8).map(|i| server.url(&format!("/{i}"))).collect();
+    let results = fetcher
+        .fetch_batch_with_concurrency(urls.iter().map(String::as_str).collect(), 3)
+        .await;
+
+    assert_eq!(server.requests().len(), 8);
+    assert!(
+        server.max_in_flight() <= 3,
+        "saw {} concurrent requests",
+        server.max_in_flight()
+    );
+    // Input order is kept even though `/0` completes last
+    for (i, result) in results.iter().enumerate() {
+        let expected = format!("<title>/{i}</title>");
+        assert!(matches!(result, Ok(FetchResult::Html(html)) if *html == expected));
+    }
+}
+
+#[tokio::test]
+async fn test_accept_language_sent_only_when_configured() {
+    let server = MockServer::start(|_| MockResponse::html(OG_PAGE)).await;
+    let url = server.url("/page");
+
+    let french = Fetcher::with_config(FetcherConfig {
+        accept_language: Some("fr-FR,fr;q=0.9".to_string()),
+        ..local_fetcher_config()
+    });
+    french.fetch(&url).await.unwrap();
+    Fetcher::with_config(local_fetcher_config())
+        .fetch(&url)
+        .await
+        .unwrap();
+
+    let requests = server.requests();
+    assert_eq!(requests[0].header("accept-language"), Some("fr-FR,fr;q=0.9"));
+    assert_eq!(requests[1].header("accept-language"), None);
+}
+
+#[test]
+fn test_invalid_accept_language_is_a_configuration_error() {
+    let result = Fetcher::try_with_config(FetcherConfig {
+        accept_language: Some("fr-FR\nX-Injected: 1".to_string()),
+        ..local_fetcher_config()
+    });
+    assert!(matches!(result, Err(PreviewError::InvalidConfiguration(_))));
+}
+
+#[tokio::test]
+async fn test_sniffing_accepts_mislabeled_html_only() {
+    let server = MockServer::start(|req| {
+        let body: &[u8] = match req.path.as_str() {
+            "/page" => b"<!DOCTYPE html><html><head><title>Mislabeled</title></head></html>",
+            _ => b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR",
+        };
+        MockResponse::new(200)
+            .header("Content-Type", "application/octet-stream")
+            .body(body)
+    })
+    .await;
+
+    let strict = Fetcher::with_config(local_fetcher_config());
+    assert!(matches!(
+        strict.fetch(&server.url("/page")).await,
+        Err(PreviewError::ContentTypeNotAllowed(declared)) if declared == "application/octet-stream"
+    ));
+
+    let sniffing = Fetcher::with_config(FetcherConfig {
+        content_limits: ContentLimits {
+            sniff_content_type: true,
+            ..Default::default()
+        },
+        ..local_fetcher_config()
+    });
+    assert!(matches!(
+        sniffing.fetch(&server.url("/page")).await,
+        Ok(FetchResult::Html(html)) if html.contains("Mislabeled")
+    ));
+    assert!(matches!(
+        sniffing.fetch(&server.url("/image")).await,
+        Err(PreviewError::ContentTypeNotAllowed(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_head_precheck_skips_get_for_disallowed_resources() {
+    let server = MockServer::start(|req| match req.path.as_str() {
+        "/paper.pdf" => MockResponse::new(200).header("Content-Type", "application/pdf"),
+        _ => MockResponse::new(200)
+            .header("Content-Type", "text/html")
+            .header("Content-Length", "52000000"),
+    })
+    .await;
+    let fetcher = Fetcher::with_config(FetcherConfig {
+        head_precheck: true,
+        ..local_fetcher_config()
+    });
+
+    assert!(matches!(
+        fetcher.fetch(&server.url("/paper.pdf")).await,
+        Err(PreviewError::ContentTypeNotAllowed(declared)) if declared == "application/pdf"
+    ));
+    assert!(matches!(
+        fetcher.fetch(&server.url("/huge")).await,
+        Err(PreviewError::ContentSizeExceeded { size: 52000000, .. })
+    ));
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 2);
+    assert!(requests.iter().all(|request| request.method == "HEAD"));
+}
+
+#[tokio::test]
+async fn test_head_precheck_falls_back_to_get_when_head_unsupported() {
+    let server = MockServer::start(|req| {
+        if req.method == "HEAD" {
+            MockResponse::new(405)
+        } else {
+            MockResponse::html(OG_PAGE)
+        }
+    })
+    .await;
+    let fetcher = Fetcher::with_config(FetcherConfig {
+        head_precheck: true,
+        ..local_fetcher_config()
+    });
+
+    assert!(matches!(
+        fetcher.fetch(&server.url("/page")).await,
+        Ok(FetchResult::Html(html)) if html.contains("Forbidden but useful")
+    ));
+    let methods: Vec<_> = server.requests().into_iter().map(|r| r.method).collect();
+    assert_eq!(methods, ["HEAD", "GET"]);
+}
+
+#[tokio::test]
+async fn test_head_precheck_sends_the_same_user_agent_as_get() {
+    let server = MockServer::start(|_| MockResponse::html(OG_PAGE)).await;
+    let fetcher = Fetcher::with_config(FetcherConfig {
+        head_precheck: true,
+        user_agent: "default-agent".to_string(),
+        user_agent_overrides: HashMap::from([(
+            "example.com".to_string(),
+            "example-agent".to_string(),
+        )]),
+        ..local_fetcher_config().with_resolved_host("www.example.com", server.addr())
+    });
+
+    let url = format!("http://www.example.com:{}/page", server.addr().port());
+    fetcher.fetch(&url).await.unwrap();
+
+    let requests = server.requests();
+    let methods: Vec<_> = requests.iter().map(|r| r.method.as_str()).collect();
+    assert_eq!(methods, ["HEAD", "GET"]);
+    assert!(requests
+        .iter()
+        .all(|r| r.header("user-agent") == Some("example-agent")));
+}
+
+#[tokio::test]
+async fn test_detect_soft_404() {
+    let server = MockServer::start(|req| match req.path.as_str() {
+        "/missing" => MockResponse::html(
+            "<html><head><title>Page Not Found | Example News</title></head>\
+             <body>Sorry, we couldn't find that article.</body></html>",
+        ),
+        _ => MockResponse::html(
+            "<html><head><title>Debugging DNS lookups</title></head>\
+             <body>The resolver answered NXDOMAIN: host not found.</body></html>",
+        ),
+    })
+    .await;
+    let fetcher = Fetcher::with_config(FetcherConfig {
+        detect_soft_404: true,
+        ..local_fetcher_config()
+    });
+
+    assert!(matches!(
+        fetcher.fetch(&server.url("/missing")).await,
+        Err(PreviewError::NotFound(_))
+    ));
+    assert!(matches!(
+        fetcher.fetch(&server.url("/dns")).await,
+        Ok(FetchResult::Html(html)) if html.contains("host not found")
+    ));
+
+    // Off by default
+    let fetcher = Fetcher::with_config(local_fetcher_config());
+    assert!(fetcher.fetch(&server.url("/missing")).await.is_ok());
+}
+
+#[tokio::test]
+async fn test_requests_go_through_proxy() {
+    let proxy = MockServer::start(|_| {
+        MockResponse::html("<html><head><title>Via proxy</title></head></html>")
+    })
+    .await;
+    // The target is validated with the default policy; only the proxy is local
+    let fetcher = Fetcher::with_config(FetcherConfig {
+        proxy: Some(
+            ProxyConfig::http(&proxy.addr().to_string()).with_credentials("alice", "s3cret"),
+        ),
+        ..Default::default()
+    });
+
+    assert!(matches!(
+        fetcher.fetch("http://preview.example/article").await,
+        Ok(FetchResult::Html(html)) if html.contains("Via proxy")
+    ));
+    let requests = proxy.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].path, "http://preview.example/article");
+    // base64 of `alice:s3cret`
+    assert_eq!(
+        requests[0].header("proxy-authorization"),
+        Some("Basic YWxpY2U6czNjcmV0")
+    );
+
+    // Loopback targets are still rejected, and never reach the proxy
+    assert!(fetcher.fetch("http://127.0.0.1/admin").await.is_err());
+    assert_eq!(proxy.requests().len(), 1);
+}
+
+#[test]
+fn test_malformed_proxy_is_a_configuration_error() {
+    let proxies = [
+        ProxyConfig::http("proxy.corp:99999"),
+        ProxyConfig::http("proxy.corp:3128").with_credentials("ali:ce", "s3cret"),
+    ];
+    for proxy in proxies {
+        let result = Fetcher::try_with_config(FetcherConfig {
+            proxy: Some(proxy.clone()),
+            ..Default::default()
+        });
+        assert!(
+            matches!(result, Err(PreviewError::InvalidConfiguration(_))),
+            "{proxy:?} should be rejected"
+        );
+    }
+}
@@ -1,11 +1,16 @@
 //! Tests for LLM-based extraction functionality
 
+#[cfg(feature = "llm")]
+mod common;
+
 #[cfg(feature = "llm")]
 mod llm_tests {
+    use super::common::{local_fetcher_config, MockResponse, MockServer};
+    use futures::StreamExt;
     use serde::{Deserialize, Serialize};
     use std::sync::Arc;
     use url_preview::{
-        ContentFormat, Fetcher, LLMExtractor, LLMExtractorConfig, MockProvider,
+        ContentFormat, Fetcher, LLMExtractor, LLMExtractorConfig, MockProvider, PreviewError,
     };
 
     #[derive(Debug, Serialize, Deserialize, schemars::JsonSchema, PartialEq)]
@@ -92,6 +97,160 @@ mod llm_tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_extract_stream_yields_complete_objects() {
+        let server = MockServer::start(|_| {
+            MockResponse::html("<html><body><p>Two products</p></body></html>")
+        })
+        .await;
+        let chunks = [
+            r#"[{"title": "Fir"#,
+            r#"st", "count": 1, "#,
+            r#""active": true}, {"title": "Second", "#,
+            r#""count": 2, "active": false}]"#,
+        ];
+        let provider = MockProvider::new()
+            .with_stream_chunks(chunks.iter().map(|c| c.to_string()).collect());
+        let extractor = LLMExtractor::new(Arc::new(provider));
+        let fetcher = Fetcher::with_config(local_fetcher_config());
+
+        let items: Vec<_> = extractor
+            .extract_stream::<TestData>(&server.url("/products"), &fetcher)
+            .collect()
+            .await;
+
+        let items: Vec<TestData> = items.into_iter().map(Result::unwrap).collect();
+        assert_eq!(
+            items,
+            vec![
+                TestData { title: "First".to_string(), count: 1, active: true },
+                TestData { title: "Second".to_string(), count: 2, active: false },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_extract_stream_unsupported_provider() {
+        let server = MockServer::start(|_| MockResponse::html("<html><body>Hi</body></html>")).await;
+        let extractor = LLMExtractor::new(Arc::new(MockProvider::new()));
+        let fetcher = Fetcher::with_config(local_fetcher_config());
+
+        let items: Vec<_> = extractor
+            .extract_stream::<TestData>(&server.url("/"), &fetcher)
+            .collect()
+            .await;
+
+        assert_eq!(items.len(), 1);
+        assert!(matches!(items[0], Err(PreviewError::UnsupportedOperation(_))));
+    }
+
+    #[tokio::test]
+    async fn test_anthropic_sends_api_key_header_and_reads_usage() {
+        use url_preview::{AnthropicProvider, LLMProvider};
+
+        let server = MockServer::start(|_| {
+            MockResponse::json(
+                r#"{"content": [{"type": "text", "text": "{\"title\": \"Hi\"}"}],
+                    "usage": {"input_tokens": 12, "output_tokens": 5}}"#,
+            )
+        })
+        .await;
+        let provider = AnthropicProvider::new("test-key".to_string())
+            .with_base_url(server.url(""));
+
+        let (result, usage) = provider
+            .generate_with_usage("prompt".to_string(), serde_json::json!({}), &LLMExtractorConfig::default())
+            .await
+            .unwrap();
+        assert_eq!(result, serde_json::json!({"title": "Hi"}));
+
+        let requests = server.requests();
+        assert_eq!(requests[0].path, "/v1/messages");
+        assert_eq!(requests[0].header("x-api-key"), Some("test-key"));
+        assert_eq!(requests[0].header("anthropic-version"), Some("2023-06-01"));
+        assert_eq!(requests[0].header("authorization"), None);
+
+        let usage = usage.unwrap();
+        assert_eq!(
+            (usage.prompt_tokens, usage.completion_tokens, usage.total_tokens),
+            (12, 5, 17)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_anthropic_error_body_maps_to_external_service_error() {
+        use url_preview::{AnthropicProvider, LLMProvider};
+
+        let server = MockServer::start(|_| {
+            MockResponse::json(
+                r#"{"type": "error", "error": {"type": "authentication_error", "message": "invalid x-api-key"}}"#,
+            )
+            .status(401)
+        })
+        .await;
+        let provider = AnthropicProvider::new("bad-key".to_string())
+            .with_base_url(server.url(""));
+
+        let error = provider
+            .generate("prompt".to_string(), serde_json::json!({}), &LLMExtractorConfig::default())
+            .await
+            .unwrap_err();
+
+        match error {
+            PreviewError::ExternalServiceError { service, message } => {
+                assert_eq!(service, "Anthropic");
+                assert_eq!(message, "HTTP 401: authentication_error: invalid x-api-key");
+            }
+            other => panic!("expected ExternalServiceError, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_generation_controls_reach_request_payloads() {
+        use serde_json::Value;
+        use url_preview::{AnthropicProvider, LLMProvider, LocalProvider};
+
+        let config = LLMExtractorConfig {
+            max_tokens: Some(2048),
+            temperature: Some(0.25),
+            ..Default::default()
+        };
+
+        let anthropic_server = MockServer::start(|_| {
+            MockResponse::json(r#"{"content": [{"type": "text", "text": "{}"}]}"#)
+        })
+        .await;
+        AnthropicProvider::new("test-key".to_string())
+            .with_base_url(anthropic_server.url(""))
+            .generate("prompt".to_string(), serde_json::json!({}), &config)
+            .await
+            .unwrap();
+        let body: Value = serde_json::from_slice(&anthropic_server.requests()[0].body).unwrap();
+        assert_eq!(body["max_tokens"], 2048);
+        assert_eq!(body["temperature"], 0.25);
+
+        let local_server =
+            MockServer::start(|_| MockResponse::json(r#"{"response": "{}"}"#)).await;
+        LocalProvider::new(local_server.url(""), "llama3".to_string())
+            .generate("prompt".to_string(), serde_json::json!({}), &config)
+            .await
+            .unwrap();
+        let body: Value = serde_json::from_slice(&local_server.requests()[0].body).unwrap();
+        assert_eq!(local_server.requests()[0].path, "/api/generate");
+        assert_eq!(body["options"]["num_predict"], 2048);
+        assert_eq!(body["options"]["temperature"], 0.25);
+
+        // Unset controls fall back to provider defaults
+        AnthropicProvider::new("test-key".to_string())
+            .with_base_url(anthropic_server.url(""))
+            .generate("prompt".to_string(), serde_json::json!({}), &LLMExtractorConfig::default())
+            .await
+            .unwrap();
+        let body: Value = serde_json::from_slice(&anthropic_server.requests()[1].body).unwrap();
+        assert_eq!(body["max_tokens"], 4096);
+        assert!(body.get("temperature").is_none());
+    }
+
     #[test]
     fn test_schema_generation() {
         let schema = schemars::schema_for!(TestData);
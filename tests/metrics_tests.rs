@@ -0,0 +1,69 @@
+#![cfg(feature = "metrics")]
+
+mod common;
+
+use common::{local_fetcher_config, MockResponse, MockServer};
+use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+use url_preview::{CacheStrategy, Fetcher, PreviewService, PreviewServiceConfig};
+
+#[tokio::test]
+async fn test_preview_calls_emit_metrics() {
+    let recorder = DebuggingRecorder::new();
+    let snapshotter = recorder.snapshotter();
+    recorder.install().unwrap();
+
+    let server = MockServer::start(|req| match req.path.as_str() {
+        "/ok" => MockResponse::html("<html><head><title>Measured</title></head></html>"),
+        _ => MockResponse::new(404),
+    })
+    .await;
+    let service = PreviewService::new_with_config(
+        PreviewServiceConfig::new(10)
+            .with_cache_strategy(CacheStrategy::NoCache)
+            .with_default_fetcher(Fetcher::with_config(local_fetcher_config())),
+    );
+    service.generate_preview(&server.url("/ok")).await.unwrap();
+    service
+        .generate_preview(&server.url("/missing"))
+        .await
+        .unwrap_err();
+
+    let metrics: Vec<(String, Vec<(String, String)>, DebugValue)> = snapshotter
+        .snapshot()
+        .into_vec()
+        .into_iter()
+        .map(|(key, _, _, value)| {
+            let labels = key
+                .key()
+                .labels()
+                .map(|label| (label.key().to_string(), label.value().to_string()))
+                .collect();
+            (key.key().name().to_string(), labels, value)
+        })
+        .collect();
+    let find = |name: &str, labels: &[(&str, &str)]| {
+        metrics.iter().find_map(|(found, found_labels, value)| {
+            let matches = found == name
+                && found_labels.len() == labels.len()
+                && labels
+                    .iter()
+                    .all(|(k, v)| found_labels.contains(&(k.to_string(), v.to_string())));
+            matches.then_some(value)
+        })
+    };
+
+    assert!(matches!(
+        find("url_preview_requests_total", &[]),
+        Some(DebugValue::Counter(2))
+    ));
+    assert!(matches!(
+        find("url_preview_errors_total", &[("kind", "not_found")]),
+        Some(DebugValue::Counter(1))
+    ));
+    for outcome in ["success", "error"] {
+        assert!(matches!(
+            find("url_preview_fetch_duration_seconds", &[("outcome", outcome)]),
+            Some(DebugValue::Histogram(samples)) if samples.len() == 1
+        ));
+    }
+}
@@ -0,0 +1,404 @@
+mod common;
+
+use common::{local_fetcher_config, MockResponse, MockServer};
+use std::collections::HashSet;
+use url_preview::{
+    CacheStrategy, ContentLimits, Fetcher, FetcherConfig, HttpMeta, PreviewError,
+    PreviewGenerator, UrlPreviewGenerator,
+};
+#[cfg(feature = "cache")]
+use std::collections::HashMap;
+#[cfg(feature = "cache")]
+use std::sync::{Arc, Mutex};
+#[cfg(feature = "cache")]
+use url_preview::{Preview, PreviewCache};
+
+const PAGE: &str = r#"<html><head>
+    <meta property="og:title" content="Detailed">
+</head><body></body></html>"#;
+
+#[tokio::test]
+async fn test_generate_preview_detailed_reports_status_and_final_url() {
+    let server = MockServer::start(|_| MockResponse::html(PAGE).status(203)).await;
+    let fetcher = Fetcher::with_config(FetcherConfig {
+        success_statuses: Some(HashSet::from([200, 203])),
+        ..local_fetcher_config()
+    });
+    let generator = UrlPreviewGenerator::new_with_fetcher(10, CacheStrategy::NoCache, fetcher);
+
+    let url = server.url("/article");
+    let detailed = generator.generate_preview_detailed(&url).await.unwrap();
+    assert_eq!(detailed.preview.title.as_deref(), Some("Detailed"));
+    assert_eq!(detailed.status, Some(203));
+    assert_eq!(detailed.final_url, url);
+    assert!(!detailed.from_cache);
+}
+
+#[tokio::test]
+async fn test_capture_http_metadata() {
+    let server = MockServer::start(|_| {
+        MockResponse::html(PAGE)
+            .header("ETag", "\"33a64df5\"")
+            .header("Last-Modified", "Wed, 21 Oct 2015 07:28:00 GMT")
+    })
+    .await;
+    let url = server.url("/article");
+
+    let fetcher = Fetcher::with_config(FetcherConfig {
+        capture_http_metadata: true,
+        ..local_fetcher_config()
+    });
+    let generator = UrlPreviewGenerator::new_with_fetcher(10, CacheStrategy::NoCache, fetcher);
+    let preview = generator.generate_preview_detailed(&url).await.unwrap().preview;
+    assert_eq!(
+        preview.http_metadata,
+        Some(HttpMeta {
+            etag: Some("\"33a64df5\"".into()),
+            last_modified: Some("Wed, 21 Oct 2015 07:28:00 GMT".into()),
+            content_type: Some("text/html; charset=utf-8".into()),
+        })
+    );
+
+    // Not recorded by default
+    let fetcher = Fetcher::with_config(local_fetcher_config());
+    let generator = UrlPreviewGenerator::new_with_fetcher(10, CacheStrategy::NoCache, fetcher);
+    let preview = generator.generate_preview_detailed(&url).await.unwrap().preview;
+    assert_eq!(preview.http_metadata, None);
+}
+
+#[cfg(feature = "cache")]
+#[tokio::test]
+async fn test_generate_preview_detailed_reports_cache_hits() {
+    let server = MockServer::start(|_| MockResponse::html(PAGE)).await;
+    let fetcher = Fetcher::with_config(local_fetcher_config());
+    let generator = UrlPreviewGenerator::new_with_fetcher(10, CacheStrategy::UseCache, fetcher);
+
+    let url = server.url("/article");
+    let first = generator.generate_preview_detailed(&url).await.unwrap();
+    assert!(!first.from_cache);
+    assert_eq!(first.status, Some(200));
+
+    let second = generator.generate_preview_detailed(&url).await.unwrap();
+    assert!(second.from_cache);
+    assert_eq!(second.status, None);
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[cfg(feature = "cache")]
+#[tokio::test]
+async fn test_expired_preview_is_revalidated_with_conditional_request() {
+    const ETAG: &str = "\"v1\"";
+    const ROTATED_ETAG: &str = "\"v2\"";
+    const LAST_MODIFIED: &str = "Wed, 21 Oct 2015 07:28:00 GMT";
+    let server = MockServer::start(|req| {
+        if req.header("if-none-match") == Some(ETAG) {
+            // The server rotates the validator without changing the page
+            MockResponse::new(304).header("ETag", ROTATED_ETAG)
+        } else if req.header("if-none-match") == Some(ROTATED_ETAG) {
+            MockResponse::new(304).header("ETag", ROTATED_ETAG)
+        } else {
+            MockResponse::html(PAGE)
+                .header("ETag", ETAG)
+                .header("Last-Modified", LAST_MODIFIED)
+        }
+    })
+    .await;
+    let fetcher = Fetcher::with_config(FetcherConfig {
+        capture_http_metadata: true,
+        ..local_fetcher_config()
+    });
+    // Entries expire immediately, so every call goes back to the server
+    let generator = UrlPreviewGenerator::new_with_fetcher(
+        10,
+        CacheStrategy::UseCacheWithTtl(std::time::Duration::ZERO),
+        fetcher,
+    );
+
+    let url = server.url("/article");
+    let first = generator.generate_preview_detailed(&url).await.unwrap();
+    assert!(!first.from_cache);
+
+    let second = generator.generate_preview_detailed(&url).await.unwrap();
+    assert!(second.from_cache);
+    assert_eq!(second.status, Some(304));
+    assert_eq!(second.preview.title.as_deref(), Some("Detailed"));
+    let meta = second.preview.http_metadata.unwrap();
+    assert_eq!(meta.etag.as_deref(), Some(ROTATED_ETAG));
+    assert_eq!(meta.last_modified.as_deref(), Some(LAST_MODIFIED));
+
+    let third = generator.generate_preview_detailed(&url).await.unwrap();
+    assert!(third.from_cache);
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 3);
+    assert_eq!(requests[0].header("if-none-match"), None);
+    assert_eq!(requests[1].header("if-none-match"), Some(ETAG));
+    assert_eq!(requests[1].header("if-modified-since"), Some(LAST_MODIFIED));
+    assert_eq!(requests[2].header("if-none-match"), Some(ROTATED_ETAG));
+}
+
+async fn redirect_chain_server() -> MockServer {
+    MockServer::start(|req| match req.path.as_str() {
+        "/short" => MockResponse::new(301).header("Location", "/hop"),
+        "/hop" => MockResponse::new(301).header("Location", "/articles/final"),
+        _ => MockResponse::html(
+            r#"<html><head>
+                <title>Final</title>
+                <meta property="og:url" content="../canonical">
+            </head></html>"#,
+        ),
+    })
+    .await
+}
+
+#[tokio::test]
+async fn test_redirect_chain_sets_resolved_url() {
+    let server = redirect_chain_server().await;
+    let fetcher = Fetcher::with_config(local_fetcher_config());
+    let generator = UrlPreviewGenerator::new_with_fetcher(10, CacheStrategy::NoCache, fetcher);
+
+    let url = server.url("/short");
+    let detailed = generator.generate_preview_detailed(&url).await.unwrap();
+    let preview = detailed.preview;
+    assert_eq!(preview.url, url);
+    assert_eq!(preview.title.as_deref(), Some("Final"));
+    assert_eq!(
+        preview.resolved_url.as_deref(),
+        Some(server.url("/articles/final").as_str())
+    );
+    // Relative URLs resolve against the final location, not the shortlink
+    assert_eq!(
+        preview.canonical_url.as_deref(),
+        Some(server.url("/canonical").as_str())
+    );
+    assert_eq!(detailed.final_url, server.url("/articles/final"));
+}
+
+#[tokio::test]
+async fn test_redirect_limits() {
+    let server = redirect_chain_server().await;
+
+    let fetcher = Fetcher::with_config(FetcherConfig {
+        max_redirects: 1,
+        ..local_fetcher_config()
+    });
+    match fetcher.fetch(&server.url("/short")).await {
+        Err(PreviewError::RedirectLoop { count, .. }) => assert_eq!(count, 1),
+        other => panic!("expected RedirectLoop, got {other:?}"),
+    }
+
+    let fetcher = Fetcher::with_config(FetcherConfig {
+        follow_redirects: false,
+        ..local_fetcher_config()
+    });
+    assert!(fetcher.fetch(&server.url("/short")).await.is_err());
+    assert_eq!(server.requests().len(), 3);
+}
+
+/// Minimal external backend, standing in for e.g. Redis
+#[cfg(feature = "cache")]
+#[derive(Default)]
+struct MapCache {
+    entries: Mutex<HashMap<String, Preview>>,
+}
+
+#[cfg(feature = "cache")]
+#[async_trait::async_trait]
+impl PreviewCache for MapCache {
+    async fn get(&self, key: &str) -> Option<Preview> {
+        self.entries.lock().unwrap().get(key).cloned()
+    }
+
+    async fn set(&self, key: String, value: Preview) {
+        self.entries.lock().unwrap().insert(key, value);
+    }
+
+    async fn remove(&self, key: &str) {
+        self.entries.lock().unwrap().remove(key);
+    }
+}
+
+#[cfg(feature = "cache")]
+#[tokio::test]
+async fn test_custom_cache_backend_is_used() {
+    let server = MockServer::start(|_| MockResponse::html(PAGE)).await;
+    let cache = Arc::new(MapCache::default());
+    let generator = UrlPreviewGenerator::new_with_fetcher(
+        10,
+        CacheStrategy::UseCache,
+        Fetcher::with_config(local_fetcher_config()),
+    )
+    .with_cache(cache.clone());
+
+    let url = server.url("/article");
+    assert!(!generator.generate_preview_detailed(&url).await.unwrap().from_cache);
+    assert_eq!(
+        cache.entries.lock().unwrap()[&url].title.as_deref(),
+        Some("Detailed")
+    );
+
+    let second = generator.generate_preview_detailed(&url).await.unwrap();
+    assert!(second.from_cache);
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[tokio::test]
+async fn test_preview_from_existing_response() {
+    let large = format!(
+        "<html><head><title>Large</title></head><body>{}</body></html>",
+        "x".repeat(4096)
+    );
+    let server = MockServer::start(move |req| match req.path.as_str() {
+        "/large" => MockResponse::html(&large).chunked(512),
+        _ => MockResponse::html(PAGE),
+    })
+    .await;
+    let generator = UrlPreviewGenerator::new_with_fetcher(
+        10,
+        CacheStrategy::NoCache,
+        Fetcher::with_config(FetcherConfig {
+            content_limits: ContentLimits {
+                max_content_size: 1024,
+                ..Default::default()
+            },
+            ..local_fetcher_config()
+        }),
+    );
+
+    // Fetched with the caller's own client
+    let url = server.url("/article");
+    let response = reqwest::get(&url).await.unwrap();
+    let preview = generator
+        .preview_from_response(response, &url)
+        .await
+        .unwrap();
+    assert_eq!(preview.title.as_deref(), Some("Detailed"));
+    assert_eq!(preview.resolved_url.as_deref(), Some(url.as_str()));
+    assert_eq!(server.requests().len(), 1);
+
+    let url = server.url("/large");
+    let response = reqwest::get(&url).await.unwrap();
+    assert!(matches!(
+        generator.preview_from_response(response, &url).await,
+        Err(PreviewError::ContentSizeExceeded { limit: 1024, .. })
+    ));
+}
+
+fn fixture(name: &str) -> Vec<u8> {
+    let path = format!("{}/tests/fixtures/{name}", env!("CARGO_MANIFEST_DIR"));
+    std::fs::read(path).unwrap()
+}
+
+#[tokio::test]
+async fn test_generate_preview_for_direct_png_url() {
+    let png = fixture("images/pixel.png");
+    let server = MockServer::start(move |_| {
+        MockResponse::new(200)
+            .header("Content-Type", "image/png")
+            .body(&png)
+    })
+    .await;
+    let generator = UrlPreviewGenerator::new_with_fetcher(
+        10,
+        CacheStrategy::NoCache,
+        Fetcher::with_config(local_fetcher_config()),
+    );
+
+    let url = server.url("/assets/pixel.png");
+    let preview = generator.generate_preview(&url).await.unwrap();
+    assert_eq!(preview.title.as_deref(), Some("pixel.png"));
+    assert_eq!(preview.image_url.as_deref(), Some(url.as_str()));
+    assert_eq!(preview.media_type.as_deref(), Some("image/png"));
+    assert_eq!(preview.og_images.len(), 1);
+    #[cfg(feature = "image-dims")]
+    assert_eq!(
+        (preview.og_images[0].width, preview.og_images[0].height),
+        (Some(3), Some(2))
+    );
+}
+
+#[tokio::test]
+async fn test_generate_preview_for_direct_jpeg_url() {
+    let jpeg = fixture("images/photo.jpg");
+    let server = MockServer::start(move |_| {
+        MockResponse::new(200)
+            .header("Content-Type", "image/jpeg")
+            .body(&jpeg)
+    })
+    .await;
+    // Images are previewed even when only HTML pages are allowed
+    let content_limits = ContentLimits {
+        allowed_content_types: HashSet::from(["text/html".to_string()]),
+        ..Default::default()
+    };
+    let generator = UrlPreviewGenerator::new_with_fetcher(
+        10,
+        CacheStrategy::NoCache,
+        Fetcher::with_config(FetcherConfig {
+            content_limits,
+            ..local_fetcher_config()
+        }),
+    );
+
+    let url = server.url("/photos/photo.jpg?size=large");
+    let preview = generator.generate_preview(&url).await.unwrap();
+    assert_eq!(preview.title.as_deref(), Some("photo.jpg"));
+    assert_eq!(preview.image_url.as_deref(), Some(url.as_str()));
+    assert_eq!(preview.media_type.as_deref(), Some("image/jpeg"));
+    let image = &preview.og_images[0];
+    assert_eq!(image.mime_type.as_deref(), Some("image/jpeg"));
+    #[cfg(feature = "image-dims")]
+    assert_eq!((image.width, image.height), (Some(16), Some(16)));
+    #[cfg(not(feature = "image-dims"))]
+    assert_eq!((image.width, image.height), (None, None));
+}
+
+#[cfg(feature = "pdf")]
+#[tokio::test]
+async fn test_generate_preview_for_pdf_url() {
+    let pdf = fixture("pdf/sample.pdf");
+    let pdf_len = pdf.len();
+    let server = MockServer::start(move |_| {
+        MockResponse::new(200)
+            .header("Content-Type", "application/pdf")
+            .body(&pdf)
+    })
+    .await;
+    let generator = UrlPreviewGenerator::new_with_fetcher(
+        10,
+        CacheStrategy::NoCache,
+        Fetcher::with_config(local_fetcher_config()),
+    );
+
+    let preview = generator
+        .generate_preview(&server.url("/papers/latency.pdf"))
+        .await
+        .unwrap();
+    assert_eq!(
+        preview.title.as_deref(),
+        Some("Measuring Link Preview Latency")
+    );
+    assert_eq!(preview.author.as_deref(), Some("Ada Lovelace"));
+    assert_eq!(
+        preview.description.as_deref(),
+        Some("A short study of metadata extraction")
+    );
+    assert_eq!(preview.media_type.as_deref(), Some("application/pdf"));
+
+    let mut content_limits = ContentLimits::default();
+    content_limits.max_content_size = pdf_len - 1;
+    let limited = UrlPreviewGenerator::new_with_fetcher(
+        10,
+        CacheStrategy::NoCache,
+        Fetcher::with_config(FetcherConfig {
+            content_limits,
+            ..local_fetcher_config()
+        }),
+    );
+    assert!(matches!(
+        limited
+            .generate_preview(&server.url("/papers/latency.pdf"))
+            .await,
+        Err(PreviewError::ContentSizeExceeded { .. })
+    ));
+}
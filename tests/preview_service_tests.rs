@@ -0,0 +1,426 @@
+mod common;
+
+use async_trait::async_trait;
+use common::{local_fetcher_config, MockResponse, MockServer};
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use url::Url;
+use url_preview::{
+    CacheStrategy, ContentLimits, Fetcher, FetcherConfig, PlatformHandler, Preview, PreviewError,
+    PreviewService, PreviewServiceConfig, UrlValidationConfig, MAX_CONCURRENT_REQUESTS,
+};
+
+#[tokio::test]
+async fn test_generate_previews_returns_result_per_url_in_order() {
+    let server = MockServer::start(|req| match req.path.as_str() {
+        "/ok" => MockResponse::html("<html><head><title>Batch OK</title></head></html>"),
+        _ => MockResponse::new(404),
+    })
+    .await;
+
+    // Loopback by IP is reachable, but the `localhost` name is rejected
+    let base = local_fetcher_config();
+    let fetcher = Fetcher::with_config(FetcherConfig {
+        url_validation: UrlValidationConfig {
+            blocked_domains: HashSet::from(["localhost".to_string()]),
+            ..base.url_validation.clone()
+        },
+        ..base
+    });
+    let service = PreviewService::new_with_config(
+        PreviewServiceConfig::new(10)
+            .with_cache_strategy(CacheStrategy::NoCache)
+            .with_default_fetcher(fetcher),
+    );
+
+    let ok_url = server.url("/ok");
+    let blocked_url = format!("http://localhost:{}/ok", server.addr().port());
+    let missing_url = server.url("/missing");
+    let results = service
+        .generate_previews(&[&ok_url, &blocked_url, &missing_url])
+        .await;
+
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0].0, ok_url);
+    assert_eq!(
+        results[0].1.as_ref().unwrap().title.as_deref(),
+        Some("Batch OK")
+    );
+    assert_eq!(results[1].0, blocked_url);
+    assert!(matches!(results[1].1, Err(PreviewError::DomainBlocked(_))));
+    assert_eq!(results[2].0, missing_url);
+    assert!(matches!(results[2].1, Err(PreviewError::NotFound(_))));
+
+    // The blocked URL never reached the server
+    assert_eq!(server.requests().len(), 2);
+}
+
+#[tokio::test]
+async fn test_per_host_limit_caps_concurrency_against_one_host() {
+    let server = MockServer::start(|_| {
+        MockResponse::html("<html><head><title>Slow</title></head></html>")
+            .delayed(Duration::from_millis(100))
+    })
+    .await;
+    let service = PreviewService::new_with_config(
+        PreviewServiceConfig::new(10)
+            .with_cache_strategy(CacheStrategy::NoCache)
+            .with_max_per_host_requests(2)
+            .with_default_fetcher(Fetcher::with_config(local_fetcher_config())),
+    );
+
+    let urls: Vec<String> = (0..8).map(|i| server.url(&format!("/page/{i}"))).collect();
+    let urls: Vec<&str> = urls.iter().map(String::as_str).collect();
+    let results = service.generate_previews(&urls).await;
+
+    assert!(results.iter().all(|(_, result)| result.is_ok()));
+    assert_eq!(server.requests().len(), 8);
+    assert!(server.max_in_flight() <= 2, "saw {} concurrent requests", server.max_in_flight());
+    assert_eq!(service.available_permits(), MAX_CONCURRENT_REQUESTS);
+}
+
+#[tokio::test]
+async fn test_builder_matches_manually_configured_service() {
+    let server = MockServer::start(|req| match req.path.as_str() {
+        "/large" => MockResponse::html(&format!(
+            "<html><head><title>Large</title></head><body>{}</body></html>",
+            "x".repeat(1024)
+        )),
+        _ => MockResponse::html("<html><head><title>Small</title></head></html>"),
+    })
+    .await;
+
+    let base = local_fetcher_config();
+    let built = PreviewService::builder()
+        .url_validation(base.url_validation.clone())
+        .block_domain("localhost")
+        .max_content_size(256)
+        .timeout(Duration::from_secs(5))
+        .cache_capacity(10)
+        .cache_strategy(CacheStrategy::NoCache)
+        .build();
+    let manual = PreviewService::new_with_config(
+        PreviewServiceConfig::new(10)
+            .with_cache_strategy(CacheStrategy::NoCache)
+            .with_default_fetcher(Fetcher::with_config(FetcherConfig {
+                url_validation: UrlValidationConfig {
+                    blocked_domains: HashSet::from(["localhost".to_string()]),
+                    ..base.url_validation.clone()
+                },
+                content_limits: ContentLimits {
+                    max_content_size: 256,
+                    ..Default::default()
+                },
+                timeout: Duration::from_secs(5),
+                ..base
+            })),
+    );
+
+    let small = server.url("/small");
+    let large = server.url("/large");
+    let blocked = format!("http://localhost:{}/small", server.addr().port());
+    for service in [&built, &manual] {
+        let preview = service.generate_preview(&small).await.unwrap();
+        assert_eq!(preview.title.as_deref(), Some("Small"));
+        assert!(matches!(
+            service.generate_preview(&large).await,
+            Err(PreviewError::ContentSizeExceeded { limit: 256, .. })
+        ));
+        assert!(matches!(
+            service.generate_preview(&blocked).await,
+            Err(PreviewError::DomainBlocked(_))
+        ));
+    }
+
+    // https_only and allow_domain reject before any request is made
+    let restricted = PreviewService::builder()
+        .allow_domain("example.com")
+        .https_only()
+        .build();
+    assert!(matches!(
+        restricted.generate_preview("http://example.com/").await,
+        Err(PreviewError::InvalidUrlScheme(_))
+    ));
+    assert!(matches!(
+        restricted.generate_preview("https://example.org/").await,
+        Err(PreviewError::DomainNotAllowed(_))
+    ));
+}
+
+#[cfg(feature = "cache")]
+#[tokio::test]
+async fn test_prefetch_populates_cache() {
+    let server = MockServer::start(|_| {
+        MockResponse::html("<html><head><title>Warm</title></head></html>")
+    })
+    .await;
+    let service = PreviewService::new_with_config(
+        PreviewServiceConfig::new(10)
+            .with_default_fetcher(Fetcher::with_config(local_fetcher_config())),
+    );
+
+    let url = server.url("/warm");
+    let missing = "not a url";
+    let results = service.prefetch(&[&url, missing]).await;
+    assert!(results[0].is_ok());
+    assert!(results[1].is_err());
+    assert_eq!(server.requests().len(), 1);
+
+    // Already cached: skipped without another request
+    assert!(service.prefetch(&[&url]).await[0].is_ok());
+    assert_eq!(server.requests().len(), 1);
+
+    let hits = service.cache_stats().hits;
+    let preview = service.generate_preview(&url).await.unwrap();
+    assert_eq!(preview.title.as_deref(), Some("Warm"));
+    assert_eq!(service.cache_stats().hits, hits + 1);
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[cfg(feature = "mastodon")]
+#[tokio::test]
+async fn test_mastodon_status_uses_instance_api() {
+    let fixture = |name: &str| {
+        std::fs::read_to_string(format!(
+            "{}/tests/fixtures/mastodon/{name}",
+            env!("CARGO_MANIFEST_DIR")
+        ))
+        .unwrap()
+    };
+    let nodeinfo = fixture("nodeinfo.json");
+    let status = fixture("status.json");
+    let server = MockServer::start(move |req| match req.path.as_str() {
+        "/.well-known/nodeinfo" => MockResponse::json(
+            r#"{"links":[{"rel":"http://nodeinfo.diaspora.software/ns/schema/2.0","href":"/nodeinfo/2.0"}]}"#,
+        ),
+        "/nodeinfo/2.0" => MockResponse::json(&nodeinfo),
+        "/api/v1/statuses/112233445566778899" => MockResponse::json(&status),
+        _ => MockResponse::new(404),
+    })
+    .await;
+    let service = PreviewService::new_with_config(
+        PreviewServiceConfig::new(10)
+            .with_default_fetcher(Fetcher::with_config(local_fetcher_config())),
+    );
+
+    let preview = service
+        .generate_preview(&server.url("/@rustlang/112233445566778899"))
+        .await
+        .unwrap();
+    assert_eq!(preview.title.as_deref(), Some("Rust Language"));
+    assert_eq!(preview.site_name.as_deref(), Some("@rustlang@127.0.0.1"));
+    assert!(preview
+        .description
+        .is_some_and(|description| description.starts_with("Rust 1.80 is out!")));
+}
+
+#[cfg(feature = "mastodon")]
+#[tokio::test]
+async fn test_mastodon_shaped_url_on_other_host_falls_back() {
+    let server = MockServer::start(|req| match req.path.as_str() {
+        "/@someone/12345" => {
+            MockResponse::html("<html><head><title>Not a toot</title></head></html>")
+        }
+        _ => MockResponse::new(404),
+    })
+    .await;
+    let service = PreviewService::new_with_config(
+        PreviewServiceConfig::new(10)
+            .with_default_fetcher(Fetcher::with_config(local_fetcher_config())),
+    );
+
+    let preview = service
+        .generate_preview(&server.url("/@someone/12345"))
+        .await
+        .unwrap();
+    assert_eq!(preview.title.as_deref(), Some("Not a toot"));
+}
+
+#[tokio::test]
+async fn test_preview_from_html_makes_no_request() {
+    let server = MockServer::start(|_| {
+        MockResponse::html("<html><head><title>From the server</title></head></html>")
+    })
+    .await;
+    let service = PreviewService::new_with_config(
+        PreviewServiceConfig::new(10)
+            .with_default_fetcher(Fetcher::with_config(local_fetcher_config())),
+    );
+
+    let url = server.url("/articles/1");
+    let html = r#"<html><head>
+        <title>Crawled page</title>
+        <meta property="og:description" content="Already downloaded">
+        <meta property="og:image" content="/cover.png">
+    </head><body></body></html>"#;
+    let preview = service.generate_preview_from_html(&url, html).unwrap();
+
+    assert_eq!(preview.url, url);
+    assert_eq!(preview.title.as_deref(), Some("Crawled page"));
+    assert_eq!(preview.description.as_deref(), Some("Already downloaded"));
+    assert_eq!(preview.image_url, Some(server.url("/cover.png")));
+    assert!(server.requests().is_empty());
+
+    // The URL is still validated
+    assert!(matches!(
+        PreviewService::new().generate_preview_from_html("http://192.168.1.1/", html),
+        Err(PreviewError::PrivateIpBlocked(_))
+    ));
+}
+
+#[cfg(feature = "cache")]
+#[tokio::test]
+async fn test_invalidate_forces_refetch() {
+    let server = MockServer::start(|_| {
+        MockResponse::html("<html><head><title>Changing</title></head></html>")
+    })
+    .await;
+    let service = PreviewService::new_with_config(
+        PreviewServiceConfig::new(10)
+            .with_default_fetcher(Fetcher::with_config(local_fetcher_config())),
+    );
+
+    let url = server.url("/changing");
+    service.generate_preview(&url).await.unwrap();
+    let cached = service.generate_preview_detailed(&url).await.unwrap();
+    assert!(cached.from_cache);
+    assert_eq!(server.requests().len(), 1);
+
+    service.invalidate(&url).await;
+    let refreshed = service.generate_preview_detailed(&url).await.unwrap();
+    assert!(!refreshed.from_cache);
+    assert_eq!(server.requests().len(), 2);
+
+    // Invalidating an uncached URL is a no-op
+    service.invalidate(&server.url("/never-fetched")).await;
+}
+
+struct ExampleHandler {
+    calls: AtomicUsize,
+}
+
+#[async_trait]
+impl PlatformHandler for ExampleHandler {
+    fn matches(&self, url: &Url) -> bool {
+        url.host_str() == Some("example.com")
+    }
+
+    async fn generate(&self, url: &str, _fetcher: &Fetcher) -> Result<Preview, PreviewError> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        Ok(Preview {
+            url: url.to_string(),
+            title: Some("From the example.com handler".into()),
+            ..Default::default()
+        })
+    }
+}
+
+#[tokio::test]
+async fn test_registered_handler_is_used_for_matching_urls() {
+    let server = MockServer::start(|_| {
+        MockResponse::html("<html><head><title>Default</title></head></html>")
+    })
+    .await;
+    let handler = Arc::new(ExampleHandler {
+        calls: AtomicUsize::new(0),
+    });
+    let service = PreviewService::new_with_config(
+        PreviewServiceConfig::new(10)
+            .with_default_fetcher(Fetcher::with_config(local_fetcher_config()))
+            .with_handler(handler.clone()),
+    );
+
+    let preview = service
+        .generate_preview("https://example.com/anything")
+        .await
+        .unwrap();
+    assert_eq!(
+        preview.title.as_deref(),
+        Some("From the example.com handler")
+    );
+    assert_eq!(handler.calls.load(Ordering::SeqCst), 1);
+
+    // Other URLs still go to the default generator
+    let preview = service
+        .generate_preview(&server.url("/page"))
+        .await
+        .unwrap();
+    assert_eq!(preview.title.as_deref(), Some("Default"));
+    assert_eq!(handler.calls.load(Ordering::SeqCst), 1);
+}
+
+#[cfg(feature = "cache")]
+#[tokio::test]
+async fn test_handler_previews_are_cached_and_observed() {
+    use url_preview::{FetchOutcome, MetricsObserver};
+
+    #[derive(Default)]
+    struct CountingObserver {
+        fetches: AtomicUsize,
+        hits: AtomicUsize,
+    }
+
+    impl MetricsObserver for CountingObserver {
+        fn on_fetch(&self, _outcome: FetchOutcome, _duration: Duration) {
+            self.fetches.fetch_add(1, Ordering::SeqCst);
+        }
+
+        fn on_cache_hit(&self) {
+            self.hits.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    let handler = Arc::new(ExampleHandler {
+        calls: AtomicUsize::new(0),
+    });
+    let observer = Arc::new(CountingObserver::default());
+    let service = PreviewService::new_with_config(
+        PreviewServiceConfig::new(10)
+            .with_handler(handler.clone())
+            .with_metrics_observer(observer.clone()),
+    );
+
+    let url = "https://example.com/cached";
+    let first = service.generate_preview_detailed(url).await.unwrap();
+    assert!(!first.from_cache);
+    let second = service.generate_preview_detailed(url).await.unwrap();
+    assert!(second.from_cache);
+    assert_eq!(
+        second.preview.title.as_deref(),
+        Some("From the example.com handler")
+    );
+
+    assert_eq!(handler.calls.load(Ordering::SeqCst), 1);
+    assert_eq!(observer.fetches.load(Ordering::SeqCst), 1);
+    assert_eq!(observer.hits.load(Ordering::SeqCst), 1);
+}
+
+#[cfg(any(feature = "twitter", feature = "github"))]
+#[tokio::test]
+async fn test_builder_platform_fetchers_check_resolved_addresses() {
+    let service = PreviewService::builder()
+        .url_validation(UrlValidationConfig {
+            block_localhost: false,
+            block_private_ips: true,
+            allowed_ports: HashSet::new(),
+            resolve_and_check: true,
+            ..Default::default()
+        })
+        .build();
+
+    // `localhost` passes the name checks but resolves to a loopback address
+    let url = "http://localhost:9/page";
+    let mut fetchers = Vec::new();
+    #[cfg(feature = "twitter")]
+    fetchers.push(&service.twitter_generator.fetcher);
+    #[cfg(feature = "github")]
+    fetchers.push(&service.github_generator.fetcher);
+    for fetcher in fetchers {
+        assert!(matches!(
+            fetcher.fetch(url).await,
+            Err(PreviewError::PrivateIpBlocked(_))
+        ));
+    }
+}
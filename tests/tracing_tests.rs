@@ -0,0 +1,78 @@
+#![cfg(all(feature = "logging", feature = "cache"))]
+
+mod common;
+
+use common::{local_fetcher_config, MockResponse, MockServer};
+use std::sync::{Arc, Mutex};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::Subscriber;
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+use url_preview::{Fetcher, PreviewService, PreviewServiceConfig};
+
+/// Captures every `url_preview.cache_hit` value recorded on a span, with the span name
+#[derive(Clone, Default)]
+struct CacheHitLayer {
+    recorded: Arc<Mutex<Vec<(&'static str, bool)>>>,
+}
+
+struct CacheHitVisitor(Option<bool>);
+
+impl Visit for CacheHitVisitor {
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        if field.name() == "url_preview.cache_hit" {
+            self.0 = Some(value);
+        }
+    }
+
+    fn record_debug(&mut self, _field: &Field, _value: &dyn std::fmt::Debug) {}
+}
+
+impl CacheHitLayer {
+    fn capture<S: for<'a> LookupSpan<'a>>(&self, id: &Id, ctx: &Context<'_, S>, hit: Option<bool>) {
+        if let (Some(hit), Some(span)) = (hit, ctx.span(id)) {
+            self.recorded.lock().unwrap().push((span.name(), hit));
+        }
+    }
+}
+
+impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for CacheHitLayer {
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let mut visitor = CacheHitVisitor(None);
+        attrs.record(&mut visitor);
+        self.capture(id, &ctx, visitor.0);
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        let mut visitor = CacheHitVisitor(None);
+        values.record(&mut visitor);
+        self.capture(id, &ctx, visitor.0);
+    }
+}
+
+#[tokio::test]
+async fn test_generate_preview_span_records_cache_hit() {
+    let layer = CacheHitLayer::default();
+    let _guard =
+        tracing::subscriber::set_default(tracing_subscriber::registry().with(layer.clone()));
+
+    let server = MockServer::start(|_| {
+        MockResponse::html("<html><head><title>Traced</title></head></html>")
+    })
+    .await;
+    let service = PreviewService::new_with_config(
+        PreviewServiceConfig::new(10)
+            .with_default_fetcher(Fetcher::with_config(local_fetcher_config())),
+    );
+
+    let url = server.url("/traced");
+    service.generate_preview(&url).await.unwrap();
+    service.generate_preview(&url).await.unwrap();
+
+    assert_eq!(
+        *layer.recorded.lock().unwrap(),
+        vec![("generate_preview", false), ("generate_preview", true)]
+    );
+}